@@ -58,6 +58,8 @@ pub mod moderation {
 
     pub mod bans;
 
+    pub mod case;
+
     pub mod deafen;
     pub mod undeafen;
 
@@ -65,6 +67,12 @@ pub mod moderation {
 
     pub mod kick;
 
+    pub mod lockdown;
+
+    pub mod massban;
+
+    pub mod masskick;
+
     pub mod mute;
     pub mod unmute;
 
@@ -75,6 +83,8 @@ pub mod moderation {
     pub mod timeout;
     pub mod untimeout;
 
+    pub mod voice;
+
     pub mod warn;
 
     pub mod yeet;
@@ -85,6 +95,8 @@ pub mod moderation {
 pub mod music {
     pub mod filters;
 
+    pub mod loop_mode;
+
     pub mod play;
 
     pub mod queue;
@@ -113,6 +125,8 @@ pub mod utility {
 
     pub mod server_info;
 
+    pub mod snipe;
+
     pub mod solve;
 
     pub mod text_to_speech;
@@ -164,6 +178,7 @@ pub fn create_commands() -> Vec<poise::Command<Data, Error>> {
             utility::member_info::member_info_user_context_menu(),
             utility::minecraft_info::minecraft_info(),
             utility::role_info::role_info(),
+            utility::snipe::snipe(),
             utility::solve::solve(),
             utility::translate::translate(),
             utility::translate::translate_message_context_menu(),
@@ -176,9 +191,13 @@ pub fn create_commands() -> Vec<poise::Command<Data, Error>> {
         commands_to_register.extend(vec![
             moderation::ban::ban(),
             moderation::bans::bans(),
+            moderation::case::case(),
             moderation::deafen::deafen(),
             moderation::disconnect::disconnect(),
             moderation::kick::kick(),
+            moderation::lockdown::lockdown(),
+            moderation::massban::massban(),
+            moderation::masskick::masskick(),
             moderation::mute::mute(),
             moderation::purge::purge(),
             moderation::slowmode::slowmode(),
@@ -187,6 +206,7 @@ pub fn create_commands() -> Vec<poise::Command<Data, Error>> {
             moderation::undeafen::undeafen(),
             moderation::unmute::unmute(),
             moderation::untimeout::untimeout(),
+            moderation::voice::voice(),
             moderation::warn::warn(),
             moderation::yeet::yeet(),
             moderation::yoink::yoink(),
@@ -216,6 +236,7 @@ pub fn create_commands() -> Vec<poise::Command<Data, Error>> {
     if is_command_category_enabled("music") {
         commands_to_register.extend(vec![
             music::filters::filters(),
+            music::loop_mode::loop_mode(),
             music::play::play(),
             music::queue::queue(),
             music::seek::seek(),
@@ -4,8 +4,26 @@
 
 pub mod ai_chat;
 
+pub mod anti_raid;
+
+pub mod automod;
+
+pub mod commands;
+
+pub mod cooldowns;
+
 pub mod logging_channels;
 
+pub mod message_retention;
+
+pub mod moderation;
+
+pub mod moderation_escalation;
+
+pub mod music;
+
+pub mod spam_protection;
+
 //------------------------------------------------------------//
 
 use crate::Context;
@@ -14,8 +32,26 @@ use crate::Error;
 
 use ai_chat::{ai_chat_mode, ai_chat_channels};
 
+use anti_raid::{anti_raid};
+
+use automod::{automod};
+
+use commands::{commands};
+
+use cooldowns::{cooldowns};
+
 use logging_channels::{logging_channels};
 
+use message_retention::{message_retention_mode};
+
+use moderation::{moderation};
+
+use moderation_escalation::{moderation_escalation};
+
+use music::{music};
+
+use spam_protection::{spam_protection};
+
 //------------------------------------------------------------//
 
 /// Configure this guild's preferences and settings.
@@ -23,7 +59,20 @@ use logging_channels::{logging_channels};
     poise::command(
         slash_command,
         guild_only,
-        subcommands("ai_chat_mode", "ai_chat_channels", "logging_channels"),
+        subcommands(
+            "ai_chat_mode",
+            "ai_chat_channels",
+            "anti_raid",
+            "automod",
+            "commands",
+            "cooldowns",
+            "logging_channels",
+            "message_retention_mode",
+            "moderation",
+            "moderation_escalation",
+            "music",
+            "spam_protection",
+        ),
         category = "Configuration",
         install_context = "Guild",
         interaction_context = "Guild",
@@ -0,0 +1,138 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::Context;
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::common::database::interfaces::guild_config::{
+    GuildConfig,
+    GuildConfigAntiRaid,
+    GuildConfigAntiRaidAction,
+};
+
+//------------------------------------------------------------//
+
+// The list of anti-raid actions available publicly.
+// Note: Keep separate from `GuildConfigAntiRaidAction`.
+#[derive(poise::ChoiceParameter)]
+enum AntiRaidAction {
+    #[name = "Timeout new accounts for the duration of raid mode"]
+    Timeout,
+
+    #[name = "Kick new accounts"]
+    Kick,
+}
+
+impl AntiRaidAction {
+    pub fn to_guild_config_value(
+        &self,
+    ) -> GuildConfigAntiRaidAction {
+        match self {
+            AntiRaidAction::Timeout => GuildConfigAntiRaidAction::Timeout,
+            AntiRaidAction::Kick => GuildConfigAntiRaidAction::Kick,
+        }
+    }
+}
+
+fn format_anti_raid(
+    anti_raid: &GuildConfigAntiRaid,
+) -> String {
+    let action_string = match anti_raid.action {
+        GuildConfigAntiRaidAction::Timeout => "Timeout",
+        GuildConfigAntiRaidAction::Kick => "Kick",
+    };
+
+    format!(
+        concat!(
+            "Enabled: **{}**\n",
+            "Raid mode starts after **{}** joins within **{}** seconds.\n",
+            "During raid mode, accounts younger than **{}** days are actioned: **{}**\n",
+            "During raid mode, text channel slowmode is raised to **{}** seconds.\n",
+            "Raid mode automatically ends after **{}** minutes.",
+        ),
+        if anti_raid.enabled { "Yes" } else { "No" },
+        anti_raid.join_threshold,
+        anti_raid.join_window_seconds,
+        anti_raid.min_account_age_days,
+        action_string,
+        anti_raid.raid_slowmode_seconds,
+        anti_raid.raid_duration_minutes,
+    )
+}
+
+//------------------------------------------------------------//
+
+/// Configure join raid detection for your guild, omitted options are left unchanged.
+#[poise::command(slash_command)]
+pub async fn anti_raid(
+    ctx: Context<'_>,
+
+    #[description = "Whether join raids should be detected"]
+    enabled: Option<bool>,
+
+    #[min = 2]
+    #[max = 100]
+    #[description = "How many joins within the window start raid mode"]
+    join_threshold: Option<u32>,
+
+    #[min = 1]
+    #[max = 300]
+    #[description = "The window (in seconds) that joins are counted in"]
+    join_window_seconds: Option<u32>,
+
+    #[min = 0]
+    #[max = 365]
+    #[description = "During raid mode, accounts younger than this many days are actioned"]
+    min_account_age_days: Option<u32>,
+
+    #[description = "What to do with new accounts during raid mode"]
+    action: Option<AntiRaidAction>,
+
+    #[min = 0]
+    #[max = 21600] // 6 hours in seconds
+    #[description = "The slowmode (in seconds) applied to text channels during raid mode"]
+    raid_slowmode_seconds: Option<u16>,
+
+    #[min = 1]
+    #[max = 1440] // 1 day in minutes
+    #[description = "How many minutes raid mode lasts"]
+    raid_duration_minutes: Option<u32>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let current_anti_raid = guild_config.get_anti_raid().await;
+
+    let new_anti_raid = GuildConfigAntiRaid {
+        enabled: enabled.unwrap_or(current_anti_raid.enabled),
+        join_threshold: join_threshold.unwrap_or(current_anti_raid.join_threshold),
+        join_window_seconds: join_window_seconds.unwrap_or(current_anti_raid.join_window_seconds),
+        min_account_age_days: min_account_age_days.unwrap_or(current_anti_raid.min_account_age_days),
+        action: action.map(|action| action.to_guild_config_value()).unwrap_or(current_anti_raid.action),
+        raid_slowmode_seconds: raid_slowmode_seconds.unwrap_or(current_anti_raid.raid_slowmode_seconds),
+        raid_duration_minutes: raid_duration_minutes.unwrap_or(current_anti_raid.raid_duration_minutes),
+    };
+
+    guild_config.set_anti_raid(new_anti_raid.clone()).await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Anti-Raid")
+            .description(format_anti_raid(&new_anti_raid))
+        )
+    ).await?;
+
+    Ok(())
+}
@@ -0,0 +1,584 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use regex::Regex;
+
+use poise::ChoiceParameter;
+use poise::serenity_prelude::{self as serenity, Mentionable};
+
+//------------------------------------------------------------//
+
+use crate::Context;
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::common::helpers::time::format_duration;
+
+use crate::common::database::interfaces::guild_config::{
+    GuildConfig,
+    GuildConfigAutomod,
+    GuildConfigAutomodAction,
+    GuildConfigAutomodRule,
+    GuildConfigAutomodRuleKind,
+};
+
+//------------------------------------------------------------//
+
+// The list of automod rule kinds available publicly.
+// Note: Keep separate from `GuildConfigAutomodRuleKind`.
+#[derive(poise::ChoiceParameter, Clone, Copy)]
+enum AutomodRuleKind {
+    #[name = "Banned words or phrases"]
+    BannedWords,
+
+    #[name = "Banned regular expressions"]
+    BannedPatterns,
+
+    #[name = "Invite links"]
+    InviteLinks,
+
+    #[name = "Mass mentions"]
+    MassMentions,
+
+    #[name = "Excessive caps"]
+    ExcessiveCaps,
+
+    #[name = "Repeated messages"]
+    RepeatedMessages,
+
+    #[name = "Zalgo text"]
+    ZalgoText,
+
+    #[name = "Links to domains outside of an allow list"]
+    LinkDomainAllowList,
+
+    #[name = "Links to domains in a deny list"]
+    LinkDomainDenyList,
+}
+
+impl AutomodRuleKind {
+    pub fn to_guild_config_value(
+        &self,
+    ) -> GuildConfigAutomodRuleKind {
+        match self {
+            AutomodRuleKind::BannedWords => GuildConfigAutomodRuleKind::BannedWords,
+            AutomodRuleKind::BannedPatterns => GuildConfigAutomodRuleKind::BannedPatterns,
+            AutomodRuleKind::InviteLinks => GuildConfigAutomodRuleKind::InviteLinks,
+            AutomodRuleKind::MassMentions => GuildConfigAutomodRuleKind::MassMentions,
+            AutomodRuleKind::ExcessiveCaps => GuildConfigAutomodRuleKind::ExcessiveCaps,
+            AutomodRuleKind::RepeatedMessages => GuildConfigAutomodRuleKind::RepeatedMessages,
+            AutomodRuleKind::ZalgoText => GuildConfigAutomodRuleKind::ZalgoText,
+            AutomodRuleKind::LinkDomainAllowList => GuildConfigAutomodRuleKind::LinkDomainAllowList,
+            AutomodRuleKind::LinkDomainDenyList => GuildConfigAutomodRuleKind::LinkDomainDenyList,
+        }
+    }
+
+    fn from_guild_config_value(
+        value: GuildConfigAutomodRuleKind,
+    ) -> Self {
+        match value {
+            GuildConfigAutomodRuleKind::BannedWords => AutomodRuleKind::BannedWords,
+            GuildConfigAutomodRuleKind::BannedPatterns => AutomodRuleKind::BannedPatterns,
+            GuildConfigAutomodRuleKind::InviteLinks => AutomodRuleKind::InviteLinks,
+            GuildConfigAutomodRuleKind::MassMentions => AutomodRuleKind::MassMentions,
+            GuildConfigAutomodRuleKind::ExcessiveCaps => AutomodRuleKind::ExcessiveCaps,
+            GuildConfigAutomodRuleKind::RepeatedMessages => AutomodRuleKind::RepeatedMessages,
+            GuildConfigAutomodRuleKind::ZalgoText => AutomodRuleKind::ZalgoText,
+            GuildConfigAutomodRuleKind::LinkDomainAllowList => AutomodRuleKind::LinkDomainAllowList,
+            GuildConfigAutomodRuleKind::LinkDomainDenyList => AutomodRuleKind::LinkDomainDenyList,
+        }
+    }
+
+    /// Whether the rule needs a list of words, patterns, or domains.
+    fn requires_values(
+        &self,
+    ) -> bool {
+        matches!(
+            self,
+            AutomodRuleKind::BannedWords |
+            AutomodRuleKind::BannedPatterns |
+            AutomodRuleKind::LinkDomainAllowList |
+            AutomodRuleKind::LinkDomainDenyList
+        )
+    }
+}
+
+// The list of automod actions available publicly.
+// Note: Keep separate from `GuildConfigAutomodAction`.
+#[derive(poise::ChoiceParameter)]
+enum AutomodAction {
+    #[name = "Delete the message"]
+    Delete,
+
+    #[name = "Delete the message and warn"]
+    Warn,
+
+    #[name = "Delete the message and timeout"]
+    Timeout,
+}
+
+impl AutomodAction {
+    pub fn to_guild_config_value(
+        &self,
+    ) -> GuildConfigAutomodAction {
+        match self {
+            AutomodAction::Delete => GuildConfigAutomodAction::Delete,
+            AutomodAction::Warn => GuildConfigAutomodAction::Warn,
+            AutomodAction::Timeout => GuildConfigAutomodAction::Timeout,
+        }
+    }
+}
+
+fn format_automod_rule(
+    rule: &GuildConfigAutomodRule,
+) -> String {
+    let action_string = match rule.action {
+        GuildConfigAutomodAction::Delete => "Delete".to_string(),
+        GuildConfigAutomodAction::Warn => "Delete and warn".to_string(),
+        GuildConfigAutomodAction::Timeout => {
+            match rule.duration_seconds {
+                Some(seconds) => format!(
+                    "Delete and timeout for {}",
+                    format_duration(std::time::Duration::from_secs(seconds as u64))
+                ),
+                None => "Delete and timeout".to_string(),
+            }
+        },
+    };
+
+    let mut rule_string = format!(
+        "- **{}** -> {}",
+        AutomodRuleKind::from_guild_config_value(rule.kind).name(),
+        action_string,
+    );
+
+    if let Some(threshold) = rule.threshold {
+        rule_string.push_str(&format!(" (threshold: {})", threshold));
+    }
+
+    if !rule.values.is_empty() {
+        rule_string.push_str(&format!("\n  `{}`", rule.values.join("`, `")));
+    }
+
+    rule_string
+}
+
+fn validate_banned_pattern(
+    pattern: &str,
+) -> Result<(), Error> {
+    if Regex::new(pattern).is_err() {
+        return Err(format!("`{}` is not a valid regular expression.", pattern).into());
+    }
+
+    Ok(())
+}
+
+//------------------------------------------------------------//
+
+/// Lists the automod rules and exemptions for this guild.
+#[
+    poise::command(
+        slash_command,
+        rename = "list",
+    )
+]
+pub async fn list_automod(
+    ctx: Context<'_>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let automod = guild_config.get_automod().await;
+
+    let rules_string =
+        if automod.rules.is_empty() {
+            "No automod rules are configured.".to_string()
+        } else {
+            automod.rules
+            .iter()
+            .map(format_automod_rule)
+            .collect::<Vec<String>>()
+            .join("\n")
+        };
+
+    let exempt_roles_string =
+        if automod.exempt_role_ids.is_empty() {
+            "None".to_string()
+        } else {
+            automod.exempt_role_ids
+            .iter()
+            .map(|role_id| role_id.mention().to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+        };
+
+    let exempt_channels_string =
+        if automod.exempt_channel_ids.is_empty() {
+            "None".to_string()
+        } else {
+            automod.exempt_channel_ids
+            .iter()
+            .map(|channel_id| channel_id.mention().to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+        };
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Automod")
+            .description(
+                format!(
+                    "{}\n\nExempt roles: {}\nExempt channels: {}",
+                    rules_string,
+                    exempt_roles_string,
+                    exempt_channels_string,
+                )
+            )
+        )
+    ).await?;
+
+    Ok(())
+}
+
+/// Adds or replaces an automod rule for this guild.
+#[
+    poise::command(
+        slash_command,
+        rename = "set_rule",
+    )
+]
+pub async fn set_automod_rule(
+    ctx: Context<'_>,
+
+    #[description = "What the rule should detect"]
+    kind: AutomodRuleKind,
+
+    #[description = "What to do when the rule is violated"]
+    action: AutomodAction,
+
+    #[description = "Comma separated words or domains, or a single pattern (only used by rules that need them)"]
+    values: Option<String>,
+
+    #[min = 1]
+    #[max = 100]
+    #[description = "Mentions, caps percentage, repeats, or combining characters that trigger the rule"]
+    threshold: Option<u32>,
+
+    #[min = 1]
+    #[max = 40320]
+    #[description = "How many minutes (max 28 days) to timeout, only used for timeouts"]
+    minutes: Option<u32>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let current_automod = guild_config.get_automod().await;
+
+    let values: Vec<String> = match kind {
+        // patterns may contain commas, so they are added one at a time, keeping the existing ones
+        AutomodRuleKind::BannedPatterns => {
+            let mut patterns =
+                current_automod.rules
+                .iter()
+                .find(|rule| rule.kind == GuildConfigAutomodRuleKind::BannedPatterns)
+                .map(|rule| rule.values.clone())
+                .unwrap_or_default();
+
+            if let Some(pattern) = values.map(|pattern| pattern.trim().to_string()).filter(|pattern| !pattern.is_empty()) {
+                validate_banned_pattern(&pattern)?;
+
+                if !patterns.contains(&pattern) {
+                    patterns.push(pattern);
+                }
+            }
+
+            patterns
+        },
+        _ => {
+            values
+            .unwrap_or_default()
+            .split(',')
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .collect()
+        },
+    };
+
+    if kind.requires_values() && values.is_empty() {
+        return Err(format!("The **{}** rule requires at least one value.", kind.name()).into());
+    }
+
+    let new_rule = GuildConfigAutomodRule {
+        kind: kind.to_guild_config_value(),
+        action: action.to_guild_config_value(),
+        duration_seconds: minutes.map(|minutes| minutes * 60),
+        values: values,
+        threshold: threshold,
+    };
+
+    let new_rule_string = format_automod_rule(&new_rule);
+
+    // only one rule is allowed per kind
+    let new_rules = [
+        current_automod.rules
+        .into_iter()
+        .filter(|rule| rule.kind != new_rule.kind)
+        .collect::<Vec<GuildConfigAutomodRule>>(),
+        vec![new_rule],
+    ].concat();
+
+    guild_config.set_automod(
+        GuildConfigAutomod {
+            rules: new_rules,
+            ..current_automod
+        }
+    ).await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Automod")
+            .description(format!("Set automod rule:\n{}", new_rule_string))
+        )
+    ).await?;
+
+    Ok(())
+}
+
+/// Removes an automod rule from this guild.
+#[
+    poise::command(
+        slash_command,
+        rename = "remove_rule",
+    )
+]
+pub async fn remove_automod_rule(
+    ctx: Context<'_>,
+
+    #[description = "The kind of rule to remove"]
+    kind: AutomodRuleKind,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let current_automod = guild_config.get_automod().await;
+
+    let new_rules =
+        current_automod.rules
+        .into_iter()
+        .filter(|rule| rule.kind != kind.to_guild_config_value())
+        .collect::<Vec<GuildConfigAutomodRule>>();
+
+    guild_config.set_automod(
+        GuildConfigAutomod {
+            rules: new_rules,
+            ..current_automod
+        }
+    ).await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Automod")
+            .description(format!("Removed the **{}** automod rule.", kind.name()))
+        )
+    ).await?;
+
+    Ok(())
+}
+
+/// Adds (or removes) a single pattern of the banned regular expressions rule.
+#[
+    poise::command(
+        slash_command,
+        rename = "toggle_banned_pattern",
+    )
+]
+pub async fn toggle_automod_banned_pattern(
+    ctx: Context<'_>,
+
+    #[description = "The regular expression to toggle"]
+    pattern: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let pattern = pattern.trim().to_string();
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let mut current_automod = guild_config.get_automod().await;
+
+    let Some(rule) =
+        current_automod.rules
+        .iter_mut()
+        .find(|rule| rule.kind == GuildConfigAutomodRuleKind::BannedPatterns)
+    else {
+        return Err(format!("Set the **{}** rule first.", AutomodRuleKind::BannedPatterns.name()).into());
+    };
+
+    let was_banned = rule.values.contains(&pattern);
+
+    if was_banned {
+        rule.values.retain(|value| *value != pattern);
+    } else {
+        validate_banned_pattern(&pattern)?;
+
+        rule.values.push(pattern.clone());
+    }
+
+    guild_config.set_automod(current_automod).await?;
+
+    let description =
+        if was_banned {
+            format!("`{}` is no longer a banned pattern.", pattern)
+        } else {
+            format!("`{}` is now a banned pattern.", pattern)
+        };
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Automod")
+            .description(description)
+        )
+    ).await?;
+
+    Ok(())
+}
+
+/// Exempts (or stops exempting) a role from automod.
+#[
+    poise::command(
+        slash_command,
+        rename = "toggle_exempt_role",
+    )
+]
+pub async fn toggle_automod_exempt_role(
+    ctx: Context<'_>,
+
+    #[description = "The role to toggle the exemption of"]
+    role: serenity::Role,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let mut current_automod = guild_config.get_automod().await;
+
+    let was_exempt = current_automod.exempt_role_ids.contains(&role.id);
+
+    if was_exempt {
+        current_automod.exempt_role_ids.retain(|role_id| *role_id != role.id);
+    } else {
+        current_automod.exempt_role_ids.push(role.id);
+    }
+
+    guild_config.set_automod(current_automod).await?;
+
+    let description =
+        if was_exempt {
+            format!("{} is no longer exempt from automod.", role.mention())
+        } else {
+            format!("{} is now exempt from automod.", role.mention())
+        };
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Automod")
+            .description(description)
+        )
+    ).await?;
+
+    Ok(())
+}
+
+/// Exempts (or stops exempting) a channel from automod.
+#[
+    poise::command(
+        slash_command,
+        rename = "toggle_exempt_channel",
+    )
+]
+pub async fn toggle_automod_exempt_channel(
+    ctx: Context<'_>,
+
+    #[description = "The channel to toggle the exemption of"]
+    channel: serenity::GuildChannel,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let mut current_automod = guild_config.get_automod().await;
+
+    let channel_id_generic: serenity::GenericChannelId = channel.id.into();
+
+    let was_exempt = current_automod.exempt_channel_ids.contains(&channel_id_generic);
+
+    if was_exempt {
+        current_automod.exempt_channel_ids.retain(|channel_id| *channel_id != channel_id_generic);
+    } else {
+        current_automod.exempt_channel_ids.push(channel_id_generic);
+    }
+
+    guild_config.set_automod(current_automod).await?;
+
+    let description =
+        if was_exempt {
+            format!("{} is no longer exempt from automod.", channel.mention())
+        } else {
+            format!("{} is now exempt from automod.", channel.mention())
+        };
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Automod")
+            .description(description)
+        )
+    ).await?;
+
+    Ok(())
+}
+
+//------------------------------------------------------------//
+
+/// Configure automatic enforcement of message content rules for your guild.
+#[
+    poise::command(
+        slash_command,
+        subcommands(
+            "list_automod",
+            "set_automod_rule",
+            "remove_automod_rule",
+            "toggle_automod_banned_pattern",
+            "toggle_automod_exempt_role",
+            "toggle_automod_exempt_channel",
+        ),
+    )
+]
+pub async fn automod(
+    _ctx: Context<'_>,
+) -> Result<(), Error> {
+    Ok(())
+}
@@ -0,0 +1,469 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::ChoiceParameter;
+use poise::serenity_prelude::{self as serenity, CreateAutocompleteResponse, Mentionable};
+
+//------------------------------------------------------------//
+
+use crate::Context;
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::commands::create_commands;
+
+use crate::common::database::interfaces::guild_config::{
+    GuildConfig,
+    GuildConfigCommandAccess,
+    GuildConfigCommandListMode,
+    GuildConfigCommandRestriction,
+    GuildConfigCommandTarget,
+    GuildConfigCommandTargetKind,
+};
+
+//------------------------------------------------------------//
+
+const COMMAND_TARGET_PREFIX: &str = "command:";
+
+const CATEGORY_TARGET_PREFIX: &str = "category:";
+
+/// Discord only shows this many autocomplete choices.
+const MAX_AUTOCOMPLETE_CHOICES: usize = 25;
+
+//------------------------------------------------------------//
+
+// The list of command list modes available publicly.
+// Note: Keep separate from `GuildConfigCommandListMode`.
+#[derive(poise::ChoiceParameter)]
+enum CommandListMode {
+    #[name = "All commands except the listed ones are enabled"]
+    DenyList,
+
+    #[name = "Only the listed commands are enabled"]
+    AllowList,
+}
+
+impl CommandListMode {
+    pub fn to_guild_config_value(
+        &self,
+    ) -> GuildConfigCommandListMode {
+        match self {
+            CommandListMode::DenyList => GuildConfigCommandListMode::DenyList,
+            CommandListMode::AllowList => GuildConfigCommandListMode::AllowList,
+        }
+    }
+}
+
+/// Lists every top-level command and command category that can be configured.
+fn get_configurable_targets() -> Vec<GuildConfigCommandTarget> {
+    let commands = create_commands();
+
+    let mut targets: Vec<GuildConfigCommandTarget> = Vec::new();
+
+    for command in &commands {
+        if let Some(category) = command.category.as_deref() {
+            let category_target = GuildConfigCommandTarget {
+                kind: GuildConfigCommandTargetKind::Category,
+                name: category.to_lowercase(),
+            };
+
+            if !targets.contains(&category_target) {
+                targets.push(category_target);
+            }
+        }
+    }
+
+    for command in &commands {
+        targets.push(
+            GuildConfigCommandTarget {
+                kind: GuildConfigCommandTargetKind::Command,
+                name: command.name.to_lowercase(),
+            }
+        );
+    }
+
+    targets
+}
+
+fn format_command_target(
+    target: &GuildConfigCommandTarget,
+) -> String {
+    match target.kind {
+        GuildConfigCommandTargetKind::Command => format!("`/{}`", target.name),
+        GuildConfigCommandTargetKind::Category => format!("`{}` (category)", target.name),
+    }
+}
+
+fn parse_command_target(
+    value: &str,
+) -> Result<GuildConfigCommandTarget, Error> {
+    let target =
+        if let Some(name) = value.strip_prefix(COMMAND_TARGET_PREFIX) {
+            GuildConfigCommandTarget {
+                kind: GuildConfigCommandTargetKind::Command,
+                name: name.to_lowercase(),
+            }
+        } else if let Some(name) = value.strip_prefix(CATEGORY_TARGET_PREFIX) {
+            GuildConfigCommandTarget {
+                kind: GuildConfigCommandTargetKind::Category,
+                name: name.to_lowercase(),
+            }
+        } else {
+            // allow plain command names to be typed without using autocomplete
+            GuildConfigCommandTarget {
+                kind: GuildConfigCommandTargetKind::Command,
+                name: value.trim_start_matches('/').to_lowercase(),
+            }
+        };
+
+    if !get_configurable_targets().contains(&target) {
+        return Err(format!("{} is not a known command or category.", format_command_target(&target)).into());
+    }
+
+    if target.kind == GuildConfigCommandTargetKind::Command && target.name == "setup" {
+        return Err("The `/setup` command cannot be configured.".into());
+    }
+
+    Ok(target)
+}
+
+async fn autocomplete_command_target<'a>(
+    _ctx: Context<'_>,
+    partial: &'a str,
+) -> CreateAutocompleteResponse<'a> {
+    let lowercase_user_input = partial.to_lowercase();
+
+    let choices =
+        get_configurable_targets()
+        .into_iter()
+        .filter(|target| target.name.contains(&lowercase_user_input))
+        .take(MAX_AUTOCOMPLETE_CHOICES)
+        .map(|target| {
+            let value = match target.kind {
+                GuildConfigCommandTargetKind::Command => format!("{}{}", COMMAND_TARGET_PREFIX, target.name),
+                GuildConfigCommandTargetKind::Category => format!("{}{}", CATEGORY_TARGET_PREFIX, target.name),
+            };
+
+            let label = match target.kind {
+                GuildConfigCommandTargetKind::Command => format!("/{}", target.name),
+                GuildConfigCommandTargetKind::Category => format!("{} (category)", target.name),
+            };
+
+            serenity::AutocompleteChoice::new(label, value)
+        })
+        .collect::<Vec<serenity::AutocompleteChoice>>();
+
+    CreateAutocompleteResponse::new().set_choices(choices)
+}
+
+fn format_command_access(
+    command_access: &GuildConfigCommandAccess,
+) -> String {
+    let list_mode_string = match command_access.list_mode {
+        GuildConfigCommandListMode::DenyList => "Disabled",
+        GuildConfigCommandListMode::AllowList => "Enabled (all others are disabled)",
+    };
+
+    let listed_targets_string =
+        if command_access.listed_targets.is_empty() {
+            "None".to_string()
+        } else {
+            command_access.listed_targets
+            .iter()
+            .map(format_command_target)
+            .collect::<Vec<String>>()
+            .join(", ")
+        };
+
+    let restrictions_string =
+        if command_access.restrictions.is_empty() {
+            "None".to_string()
+        } else {
+            command_access.restrictions
+            .iter()
+            .map(|restriction| {
+                let channels_string =
+                    if restriction.allowed_channel_ids.is_empty() {
+                        "any channel".to_string()
+                    } else {
+                        restriction.allowed_channel_ids
+                        .iter()
+                        .map(|channel_id| channel_id.mention().to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                    };
+
+                let roles_string =
+                    if restriction.allowed_role_ids.is_empty() {
+                        "anyone".to_string()
+                    } else {
+                        restriction.allowed_role_ids
+                        .iter()
+                        .map(|role_id| role_id.mention().to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                    };
+
+                format!("- {}: in {}, by {}", format_command_target(&restriction.target), channels_string, roles_string)
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+        };
+
+    format!(
+        "**{}**: {}\n\n**Restrictions**\n{}",
+        list_mode_string,
+        listed_targets_string,
+        restrictions_string,
+    )
+}
+
+async fn send_command_access_embed(
+    ctx: Context<'_>,
+    content: &str,
+    command_access: &GuildConfigCommandAccess,
+) -> Result<(), Error> {
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Commands")
+            .description(format!("{}\n\n{}", content, format_command_access(command_access)))
+        )
+    ).await?;
+
+    Ok(())
+}
+
+//------------------------------------------------------------//
+
+/// Lists which commands are enabled and restricted in this guild.
+#[
+    poise::command(
+        slash_command,
+        rename = "list",
+    )
+]
+pub async fn list_commands(
+    ctx: Context<'_>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let command_access = guild_config.get_command_access().await;
+
+    send_command_access_embed(ctx, "Administrators are not subject to restrictions.", &command_access).await
+}
+
+/// Configure whether listed commands are the disabled or the only enabled ones.
+#[
+    poise::command(
+        slash_command,
+        rename = "list_mode",
+    )
+]
+pub async fn set_commands_list_mode(
+    ctx: Context<'_>,
+
+    #[description = "How the listed commands and categories are treated"]
+    list_mode: CommandListMode,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let new_command_access = GuildConfigCommandAccess {
+        list_mode: list_mode.to_guild_config_value(),
+        ..guild_config.get_command_access().await
+    };
+
+    guild_config.set_command_access(new_command_access.clone()).await?;
+
+    send_command_access_embed(
+        ctx,
+        &format!("Set list mode to **{}**.", list_mode.name()),
+        &new_command_access,
+    ).await
+}
+
+/// Adds (or removes) a command or category from the list.
+#[
+    poise::command(
+        slash_command,
+        rename = "toggle",
+    )
+]
+pub async fn toggle_commands_listed(
+    ctx: Context<'_>,
+
+    #[autocomplete = "autocomplete_command_target"]
+    #[description = "The command or category"]
+    target: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let target = parse_command_target(&target)?;
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let mut command_access = guild_config.get_command_access().await;
+
+    let was_listed = command_access.listed_targets.contains(&target);
+
+    if was_listed {
+        command_access.listed_targets.retain(|listed_target| *listed_target != target);
+    } else {
+        command_access.listed_targets.push(target.clone());
+    }
+
+    guild_config.set_command_access(command_access.clone()).await?;
+
+    let content =
+        if was_listed {
+            format!("Removed {} from the list.", format_command_target(&target))
+        } else {
+            format!("Added {} to the list.", format_command_target(&target))
+        };
+
+    send_command_access_embed(ctx, &content, &command_access).await
+}
+
+/// Restricts a command or category to a channel and/or role (toggles if already restricted).
+#[
+    poise::command(
+        slash_command,
+        rename = "restrict",
+    )
+]
+pub async fn restrict_commands(
+    ctx: Context<'_>,
+
+    #[autocomplete = "autocomplete_command_target"]
+    #[description = "The command or category"]
+    target: String,
+
+    #[description = "A channel that the command may be used in"]
+    channel: Option<serenity::GuildChannel>,
+
+    #[description = "A role that may use the command"]
+    role: Option<serenity::Role>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    if channel.is_none() && role.is_none() {
+        return Err("Specify a channel and/or a role to restrict to.".into());
+    }
+
+    let target = parse_command_target(&target)?;
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let mut command_access = guild_config.get_command_access().await;
+
+    let restriction_index = match command_access.restrictions.iter().position(|restriction| restriction.target == target) {
+        Some(index) => index,
+        None => {
+            command_access.restrictions.push(
+                GuildConfigCommandRestriction {
+                    target: target.clone(),
+                    allowed_channel_ids: Vec::new(),
+                    allowed_role_ids: Vec::new(),
+                }
+            );
+
+            command_access.restrictions.len() - 1
+        },
+    };
+
+    let restriction = &mut command_access.restrictions[restriction_index];
+
+    if let Some(channel) = &channel {
+        let channel_id: serenity::GenericChannelId = channel.id.into();
+
+        if restriction.allowed_channel_ids.contains(&channel_id) {
+            restriction.allowed_channel_ids.retain(|allowed_channel_id| *allowed_channel_id != channel_id);
+        } else {
+            restriction.allowed_channel_ids.push(channel_id);
+        }
+    }
+
+    if let Some(role) = &role {
+        if restriction.allowed_role_ids.contains(&role.id) {
+            restriction.allowed_role_ids.retain(|allowed_role_id| *allowed_role_id != role.id);
+        } else {
+            restriction.allowed_role_ids.push(role.id);
+        }
+    }
+
+    // a restriction without channels or roles does not restrict anything
+    command_access.restrictions.retain(|restriction| {
+        !restriction.allowed_channel_ids.is_empty() || !restriction.allowed_role_ids.is_empty()
+    });
+
+    guild_config.set_command_access(command_access.clone()).await?;
+
+    send_command_access_embed(
+        ctx,
+        &format!("Updated the restrictions of {}.", format_command_target(&target)),
+        &command_access,
+    ).await
+}
+
+/// Removes every channel and role restriction from a command or category.
+#[
+    poise::command(
+        slash_command,
+        rename = "unrestrict",
+    )
+]
+pub async fn unrestrict_commands(
+    ctx: Context<'_>,
+
+    #[autocomplete = "autocomplete_command_target"]
+    #[description = "The command or category"]
+    target: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let target = parse_command_target(&target)?;
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let mut command_access = guild_config.get_command_access().await;
+
+    command_access.restrictions.retain(|restriction| restriction.target != target);
+
+    guild_config.set_command_access(command_access.clone()).await?;
+
+    send_command_access_embed(
+        ctx,
+        &format!("Removed the restrictions of {}.", format_command_target(&target)),
+        &command_access,
+    ).await
+}
+
+//------------------------------------------------------------//
+
+/// Configure which commands are enabled, and where (and by whom) they may be used.
+#[
+    poise::command(
+        slash_command,
+        subcommands(
+            "list_commands",
+            "set_commands_list_mode",
+            "toggle_commands_listed",
+            "restrict_commands",
+            "unrestrict_commands",
+        ),
+    )
+]
+pub async fn commands(
+    _ctx: Context<'_>,
+) -> Result<(), Error> {
+    Ok(())
+}
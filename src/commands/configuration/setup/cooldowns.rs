@@ -0,0 +1,304 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::serenity_prelude::{self as serenity, CreateAutocompleteResponse, Mentionable};
+
+//------------------------------------------------------------//
+
+use crate::Context;
+
+use crate::Data;
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::commands::create_commands;
+
+use crate::common::database::interfaces::guild_config::{
+    GuildConfig,
+    GuildConfigCooldownOverride,
+    GuildConfigCooldowns,
+};
+
+//------------------------------------------------------------//
+
+/// Discord only shows this many autocomplete choices.
+const MAX_AUTOCOMPLETE_CHOICES: usize = 25;
+
+//------------------------------------------------------------//
+
+fn collect_qualified_command_names(
+    commands: &[poise::Command<Data, Error>],
+    qualified_command_names: &mut Vec<String>,
+) {
+    for command in commands {
+        qualified_command_names.push(command.qualified_name.to_lowercase());
+
+        collect_qualified_command_names(&command.subcommands, qualified_command_names);
+    }
+}
+
+/// Lists the qualified names (e.g. `setup automod list`) of every command, including subcommands.
+fn get_qualified_command_names() -> Vec<String> {
+    let mut qualified_command_names = Vec::new();
+
+    collect_qualified_command_names(&create_commands(), &mut qualified_command_names);
+
+    qualified_command_names
+}
+
+fn parse_command_name(
+    value: &str,
+) -> Result<String, Error> {
+    let command_name = value.trim().trim_start_matches('/').to_lowercase();
+
+    if !get_qualified_command_names().contains(&command_name) {
+        return Err(format!("`/{}` is not a known command.", command_name).into());
+    }
+
+    Ok(command_name)
+}
+
+async fn autocomplete_command_name<'a>(
+    _ctx: Context<'_>,
+    partial: &'a str,
+) -> CreateAutocompleteResponse<'a> {
+    let lowercase_user_input = partial.trim_start_matches('/').to_lowercase();
+
+    let choices =
+        get_qualified_command_names()
+        .into_iter()
+        .filter(|command_name| command_name.contains(&lowercase_user_input))
+        .take(MAX_AUTOCOMPLETE_CHOICES)
+        .map(|command_name| serenity::AutocompleteChoice::new(format!("/{}", command_name), command_name))
+        .collect::<Vec<serenity::AutocompleteChoice>>();
+
+    CreateAutocompleteResponse::new().set_choices(choices)
+}
+
+fn format_cooldown_override(
+    cooldown_override: &GuildConfigCooldownOverride,
+) -> String {
+    let format_seconds = |seconds: Option<u32>| match seconds {
+        Some(seconds) => format!("{}s", seconds),
+        None => "none".to_string(),
+    };
+
+    format!(
+        "- `/{}`: user {}, channel {}, guild {}",
+        cooldown_override.command_name,
+        format_seconds(cooldown_override.user_seconds),
+        format_seconds(cooldown_override.channel_seconds),
+        format_seconds(cooldown_override.guild_seconds),
+    )
+}
+
+fn format_cooldowns(
+    cooldowns: &GuildConfigCooldowns,
+) -> String {
+    let overrides_string =
+        if cooldowns.overrides.is_empty() {
+            "None, the default cooldowns are used.".to_string()
+        } else {
+            cooldowns.overrides
+            .iter()
+            .map(format_cooldown_override)
+            .collect::<Vec<String>>()
+            .join("\n")
+        };
+
+    let bypass_roles_string =
+        if cooldowns.bypass_role_ids.is_empty() {
+            "None".to_string()
+        } else {
+            cooldowns.bypass_role_ids
+            .iter()
+            .map(|role_id| role_id.mention().to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+        };
+
+    format!("**Overrides**\n{}\n\n**Bypass Roles**\n{}", overrides_string, bypass_roles_string)
+}
+
+async fn send_cooldowns_embed(
+    ctx: Context<'_>,
+    content: &str,
+    cooldowns: &GuildConfigCooldowns,
+) -> Result<(), Error> {
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Cooldowns")
+            .description(format!("{}\n\n{}", content, format_cooldowns(cooldowns)))
+        )
+    ).await?;
+
+    Ok(())
+}
+
+//------------------------------------------------------------//
+
+/// Lists the cooldown overrides and bypass roles for this guild.
+#[
+    poise::command(
+        slash_command,
+        rename = "list",
+    )
+]
+pub async fn list_cooldowns(
+    ctx: Context<'_>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let cooldowns = guild_config.get_cooldowns().await;
+
+    send_cooldowns_embed(ctx, "Cooldowns of commands without overrides are unchanged.", &cooldowns).await
+}
+
+/// Overrides the cooldowns of a command, omitted cooldowns are disabled.
+#[
+    poise::command(
+        slash_command,
+        rename = "set",
+    )
+]
+pub async fn set_cooldown(
+    ctx: Context<'_>,
+
+    #[autocomplete = "autocomplete_command_name"]
+    #[description = "The command to override the cooldowns of"]
+    command: String,
+
+    #[min = 1]
+    #[max = 3600]
+    #[description = "Seconds before each member may use the command again"]
+    user_seconds: Option<u32>,
+
+    #[min = 1]
+    #[max = 3600]
+    #[description = "Seconds before the command may be used again in the same channel"]
+    channel_seconds: Option<u32>,
+
+    #[min = 1]
+    #[max = 3600]
+    #[description = "Seconds before the command may be used again anywhere in this guild"]
+    guild_seconds: Option<u32>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let command_name = parse_command_name(&command)?;
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let mut cooldowns = guild_config.get_cooldowns().await;
+
+    cooldowns.overrides.retain(|cooldown_override| cooldown_override.command_name != command_name);
+
+    cooldowns.overrides.push(
+        GuildConfigCooldownOverride {
+            command_name: command_name.clone(),
+            user_seconds: user_seconds,
+            channel_seconds: channel_seconds,
+            guild_seconds: guild_seconds,
+        }
+    );
+
+    guild_config.set_cooldowns(cooldowns.clone()).await?;
+
+    send_cooldowns_embed(ctx, &format!("Overrode the cooldowns of `/{}`.", command_name), &cooldowns).await
+}
+
+/// Restores the default cooldowns of a command.
+#[
+    poise::command(
+        slash_command,
+        rename = "reset",
+    )
+]
+pub async fn reset_cooldown(
+    ctx: Context<'_>,
+
+    #[autocomplete = "autocomplete_command_name"]
+    #[description = "The command to restore the default cooldowns of"]
+    command: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let command_name = parse_command_name(&command)?;
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let mut cooldowns = guild_config.get_cooldowns().await;
+
+    cooldowns.overrides.retain(|cooldown_override| cooldown_override.command_name != command_name);
+
+    guild_config.set_cooldowns(cooldowns.clone()).await?;
+
+    send_cooldowns_embed(ctx, &format!("Restored the default cooldowns of `/{}`.", command_name), &cooldowns).await
+}
+
+/// Allows (or stops allowing) a role to bypass all cooldowns.
+#[
+    poise::command(
+        slash_command,
+        rename = "toggle_bypass_role",
+    )
+]
+pub async fn toggle_cooldown_bypass_role(
+    ctx: Context<'_>,
+
+    #[description = "The role to toggle the bypass of"]
+    role: serenity::Role,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let mut cooldowns = guild_config.get_cooldowns().await;
+
+    let was_bypassing = cooldowns.bypass_role_ids.contains(&role.id);
+
+    if was_bypassing {
+        cooldowns.bypass_role_ids.retain(|role_id| *role_id != role.id);
+    } else {
+        cooldowns.bypass_role_ids.push(role.id);
+    }
+
+    guild_config.set_cooldowns(cooldowns.clone()).await?;
+
+    let content =
+        if was_bypassing {
+            format!("{} no longer bypasses cooldowns.", role.mention())
+        } else {
+            format!("{} now bypasses cooldowns.", role.mention())
+        };
+
+    send_cooldowns_embed(ctx, &content, &cooldowns).await
+}
+
+//------------------------------------------------------------//
+
+/// Configure command cooldowns for your guild.
+#[
+    poise::command(
+        slash_command,
+        subcommands(
+            "list_cooldowns",
+            "set_cooldown",
+            "reset_cooldown",
+            "toggle_cooldown_bypass_role",
+        ),
+    )
+]
+pub async fn cooldowns(
+    _ctx: Context<'_>,
+) -> Result<(), Error> {
+    Ok(())
+}
@@ -2,6 +2,7 @@
 //                   Copyright (c) MidSpike                   //
 //------------------------------------------------------------//
 
+use poise::ChoiceParameter;
 use poise::serenity_prelude::{self as serenity, Mentionable};
 
 //------------------------------------------------------------//
@@ -12,63 +13,107 @@ use crate::Error;
 
 use crate::common::branding;
 
-use crate::common::database::interfaces::guild_config::{GuildConfig, GuildConfigLoggingChannels};
+use crate::common::database::interfaces::guild_config::{GuildConfig, GuildConfigLoggingChannelKind};
 
 //------------------------------------------------------------//
 
-/// Sets the channel used for logging member joins.
-#[
-    poise::command(
-        slash_command,
-        rename = "set_member_joins",
-    )
-]
-pub async fn set_member_joins_logging_channel(
-    ctx: Context<'_>,
+// The list of logging channel kinds available publicly.
+// Note: Keep separate from `GuildConfigLoggingChannelKind`.
+#[derive(poise::ChoiceParameter, Clone, Copy)]
+pub enum LoggingChannelKind {
+    #[name = "Member Joins"]
+    MemberJoins,
 
-    #[description = "A channel to log member joins in."]
-    channel: serenity::GuildChannel,
-) -> Result<(), Error> {
-    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+    #[name = "Member Leaves"]
+    MemberLeaves,
 
-    let guild_config = GuildConfig::ensure(guild_id).await?;
+    #[name = "Message Edits"]
+    MessageEdits,
 
-    let current_logging_channels = guild_config.get_logging_channels().await;
+    #[name = "Message Deletes"]
+    MessageDeletes,
 
-    let channel_id_generic: serenity::GenericChannelId = channel.id.into();
+    #[name = "Message Bulk Deletes"]
+    MessageBulkDeletes,
 
-    let new_logging_channels = GuildConfigLoggingChannels {
-        guild_member_join: Some(channel_id_generic),
-        ..current_logging_channels
-    };
+    #[name = "Member Role Changes"]
+    MemberRoleChanges,
 
-    guild_config.set_logging_channels(new_logging_channels).await?;
+    #[name = "Member Nickname Changes"]
+    MemberNicknameChanges,
 
-    ctx.send(
-        poise::CreateReply::default()
-        .embed(
-            serenity::CreateEmbed::default()
-            .color(branding::color::PRIMARY)
-            .title("Guild Configuration - Logging Channels")
-            .description(
-                [
-                    format!("Added guild member joins logging channel {}.", channel.mention()).as_str(),
-                ].join("\n")
-            )
-        )
-    ).await?;
+    #[name = "Voice Activity"]
+    VoiceActivity,
 
-    Ok(())
+    #[name = "Channel Creates"]
+    ChannelCreates,
+
+    #[name = "Channel Deletes"]
+    ChannelDeletes,
+
+    #[name = "Bans"]
+    Bans,
+
+    #[name = "Unbans"]
+    Unbans,
+
+    #[name = "Moderation Actions"]
+    ModerationActions,
+
+    #[name = "Moderation Appeals"]
+    ModerationAppeals,
+}
+
+impl LoggingChannelKind {
+    pub const ALL: [LoggingChannelKind; 14] = [
+        LoggingChannelKind::MemberJoins,
+        LoggingChannelKind::MemberLeaves,
+        LoggingChannelKind::MessageEdits,
+        LoggingChannelKind::MessageDeletes,
+        LoggingChannelKind::MessageBulkDeletes,
+        LoggingChannelKind::MemberRoleChanges,
+        LoggingChannelKind::MemberNicknameChanges,
+        LoggingChannelKind::VoiceActivity,
+        LoggingChannelKind::ChannelCreates,
+        LoggingChannelKind::ChannelDeletes,
+        LoggingChannelKind::Bans,
+        LoggingChannelKind::Unbans,
+        LoggingChannelKind::ModerationActions,
+        LoggingChannelKind::ModerationAppeals,
+    ];
+
+    pub fn to_guild_config_value(
+        &self,
+    ) -> GuildConfigLoggingChannelKind {
+        match self {
+            LoggingChannelKind::MemberJoins => GuildConfigLoggingChannelKind::GuildMemberJoin,
+            LoggingChannelKind::MemberLeaves => GuildConfigLoggingChannelKind::GuildMemberLeave,
+            LoggingChannelKind::MessageEdits => GuildConfigLoggingChannelKind::MessageEdit,
+            LoggingChannelKind::MessageDeletes => GuildConfigLoggingChannelKind::MessageDelete,
+            LoggingChannelKind::MessageBulkDeletes => GuildConfigLoggingChannelKind::MessageBulkDelete,
+            LoggingChannelKind::MemberRoleChanges => GuildConfigLoggingChannelKind::MemberRoleUpdate,
+            LoggingChannelKind::MemberNicknameChanges => GuildConfigLoggingChannelKind::MemberNicknameUpdate,
+            LoggingChannelKind::VoiceActivity => GuildConfigLoggingChannelKind::VoiceActivity,
+            LoggingChannelKind::ChannelCreates => GuildConfigLoggingChannelKind::ChannelCreate,
+            LoggingChannelKind::ChannelDeletes => GuildConfigLoggingChannelKind::ChannelDelete,
+            LoggingChannelKind::Bans => GuildConfigLoggingChannelKind::GuildBanAdd,
+            LoggingChannelKind::Unbans => GuildConfigLoggingChannelKind::GuildBanRemove,
+            LoggingChannelKind::ModerationActions => GuildConfigLoggingChannelKind::ModerationActions,
+            LoggingChannelKind::ModerationAppeals => GuildConfigLoggingChannelKind::ModerationAppeals,
+        }
+    }
 }
 
-/// Unsets the channel used for logging member joins.
+//------------------------------------------------------------//
+
+/// Lists the logging channels configured for this guild.
 #[
     poise::command(
         slash_command,
-        rename = "unset_member_joins",
+        rename = "list",
     )
 ]
-pub async fn unset_member_joins_logging_channel(
+pub async fn list_logging_channels(
     ctx: Context<'_>,
 ) -> Result<(), Error> {
     let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
@@ -77,12 +122,19 @@ pub async fn unset_member_joins_logging_channel(
 
     let current_logging_channels = guild_config.get_logging_channels().await;
 
-    let new_logging_channels = GuildConfigLoggingChannels {
-        guild_member_join: None,
-        ..current_logging_channels
-    };
+    let logging_channels_string =
+        LoggingChannelKind::ALL
+        .iter()
+        .map(|kind| {
+            let channel_string = match current_logging_channels.get(kind.to_guild_config_value()) {
+                Some(channel_id) => channel_id.mention().to_string(),
+                None => "*Not set*".to_string(),
+            };
 
-    guild_config.set_logging_channels(new_logging_channels).await?;
+            format!("- **{}**: {}", kind.name(), channel_string)
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
 
     ctx.send(
         poise::CreateReply::default()
@@ -90,30 +142,27 @@ pub async fn unset_member_joins_logging_channel(
             serenity::CreateEmbed::default()
             .color(branding::color::PRIMARY)
             .title("Guild Configuration - Logging Channels")
-            .description(
-                [
-                    "Removed guild member joins logging channel.".to_string(),
-                ].join("\n")
-            )
+            .description(logging_channels_string)
         )
     ).await?;
 
     Ok(())
 }
 
-//------------------------------------------------------------//
-
-/// Sets the channel used for logging member leaves.
+/// Sets the channel used for a kind of logging.
 #[
     poise::command(
         slash_command,
-        rename = "set_member_leaves",
+        rename = "set",
     )
 ]
-pub async fn set_member_leaves_logging_channel(
+pub async fn set_logging_channel(
     ctx: Context<'_>,
 
-    #[description = "A channel to log member leaves in."]
+    #[description = "The kind of events to log."]
+    kind: LoggingChannelKind,
+
+    #[description = "A channel to log the events in."]
     channel: serenity::GuildChannel,
 ) -> Result<(), Error> {
     let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
@@ -124,10 +173,7 @@ pub async fn set_member_leaves_logging_channel(
 
     let channel_id_generic: serenity::GenericChannelId = channel.id.into();
 
-    let new_logging_channels = GuildConfigLoggingChannels {
-        guild_member_leave: Some(channel_id_generic),
-        ..current_logging_channels
-    };
+    let new_logging_channels = current_logging_channels.with(kind.to_guild_config_value(), Some(channel_id_generic));
 
     guild_config.set_logging_channels(new_logging_channels).await?;
 
@@ -138,9 +184,7 @@ pub async fn set_member_leaves_logging_channel(
             .color(branding::color::PRIMARY)
             .title("Guild Configuration - Logging Channels")
             .description(
-                [
-                    format!("Added guild member leaves logging channel {}.", channel.mention()).as_str(),
-                ].join("\n")
+                format!("Added {} logging channel {}.", kind.name().to_lowercase(), channel.mention())
             )
         )
     ).await?;
@@ -148,15 +192,18 @@ pub async fn set_member_leaves_logging_channel(
     Ok(())
 }
 
-/// Unsets the channel used for logging member leaves.
+/// Unsets the channel used for a kind of logging.
 #[
     poise::command(
         slash_command,
-        rename = "unset_member_leaves",
+        rename = "unset",
     )
 ]
-pub async fn unset_member_leaves_logging_channel(
+pub async fn unset_logging_channel(
     ctx: Context<'_>,
+
+    #[description = "The kind of events to stop logging."]
+    kind: LoggingChannelKind,
 ) -> Result<(), Error> {
     let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
 
@@ -164,10 +211,7 @@ pub async fn unset_member_leaves_logging_channel(
 
     let current_logging_channels = guild_config.get_logging_channels().await;
 
-    let new_logging_channels = GuildConfigLoggingChannels {
-        guild_member_leave: None,
-        ..current_logging_channels
-    };
+    let new_logging_channels = current_logging_channels.with(kind.to_guild_config_value(), None);
 
     guild_config.set_logging_channels(new_logging_channels).await?;
 
@@ -178,9 +222,7 @@ pub async fn unset_member_leaves_logging_channel(
             .color(branding::color::PRIMARY)
             .title("Guild Configuration - Logging Channels")
             .description(
-                [
-                    "Removed guild member leaves logging channel.".to_string(),
-                ].join("\n")
+                format!("Removed {} logging channel.", kind.name().to_lowercase())
             )
         )
     ).await?;
@@ -195,10 +237,9 @@ pub async fn unset_member_leaves_logging_channel(
     poise::command(
         slash_command,
         subcommands(
-            "set_member_joins_logging_channel",
-            "unset_member_joins_logging_channel",
-            "set_member_leaves_logging_channel",
-            "unset_member_leaves_logging_channel",
+            "list_logging_channels",
+            "set_logging_channel",
+            "unset_logging_channel",
         ),
     )
 ]
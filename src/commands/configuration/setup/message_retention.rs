@@ -0,0 +1,87 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::ChoiceParameter;
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::Context;
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::common::message_cache;
+
+use crate::common::database::interfaces::guild_config::GuildConfig;
+use crate::common::database::interfaces::guild_config::GuildConfigMessageRetentionMode;
+
+//------------------------------------------------------------//
+
+// The list of message retention modes available publicly.
+// Note: Keep separate from `GuildConfigMessageRetentionMode`.
+#[derive(poise::ChoiceParameter)]
+enum MessageRetentionMode {
+    #[name = "Disabled (messages are never retained)"]
+    Disabled,
+
+    #[name = "Only for message edit/delete logging channels"]
+    LoggingOnly,
+
+    #[name = "For message logging channels and /snipe"]
+    LoggingAndSnipe,
+}
+
+impl MessageRetentionMode {
+    pub fn to_guild_config_value(
+        &self,
+    ) -> GuildConfigMessageRetentionMode {
+        match self {
+            MessageRetentionMode::Disabled =>
+                GuildConfigMessageRetentionMode::Disabled,
+
+            MessageRetentionMode::LoggingOnly =>
+                GuildConfigMessageRetentionMode::LoggingOnly,
+
+            MessageRetentionMode::LoggingAndSnipe =>
+                GuildConfigMessageRetentionMode::LoggingAndSnipe,
+        }
+    }
+}
+
+//------------------------------------------------------------//
+
+/// Configure if (and why) recent messages are temporarily retained in this guild.
+#[poise::command(slash_command)]
+pub async fn message_retention_mode(
+    ctx: Context<'_>,
+
+    #[description = "Control how recent messages are retained in this guild."]
+    message_retention_mode: MessageRetentionMode,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let new_message_retention_mode = message_retention_mode.to_guild_config_value();
+
+    guild_config.set_message_retention_mode(new_message_retention_mode).await?;
+
+    if new_message_retention_mode == GuildConfigMessageRetentionMode::Disabled {
+        message_cache::clear_guild_messages(guild_id);
+    }
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Message Retention Mode")
+            .description(format!("Set message retention mode to **{}**.", message_retention_mode.name()))
+        )
+    ).await?;
+
+    Ok(())
+}
@@ -0,0 +1,350 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::ChoiceParameter;
+use poise::serenity_prelude::{self as serenity, Mentionable};
+
+//------------------------------------------------------------//
+
+use crate::Context;
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::common::database::interfaces::guild_config::{
+    GuildConfig,
+    GuildConfigModerationCapability,
+    GuildConfigModerationMode,
+    GuildConfigModerationRole,
+    GuildConfigModerationRoles,
+};
+
+//------------------------------------------------------------//
+
+// The list of moderation modes available publicly.
+// Note: Keep separate from `GuildConfigModerationMode`.
+#[derive(poise::ChoiceParameter)]
+enum ModerationMode {
+    #[name = "Only roles granted by this bot (and administrators)"]
+    DenyDiscordOverrides,
+
+    #[name = "Roles granted by this bot and discord's own permissions"]
+    AllowDiscordOverrides,
+}
+
+impl ModerationMode {
+    pub fn to_guild_config_value(
+        &self,
+    ) -> GuildConfigModerationMode {
+        match self {
+            ModerationMode::DenyDiscordOverrides =>
+                GuildConfigModerationMode::DenyDiscordOverrides,
+
+            ModerationMode::AllowDiscordOverrides =>
+                GuildConfigModerationMode::AllowDiscordOverrides,
+        }
+    }
+}
+
+// The list of moderation capabilities available publicly.
+// Note: Keep separate from `GuildConfigModerationCapability`.
+#[derive(poise::ChoiceParameter, Clone, Copy)]
+enum ModerationCapability {
+    #[name = "Warn"]
+    Warn,
+
+    #[name = "Timeout"]
+    Timeout,
+
+    #[name = "Kick"]
+    Kick,
+
+    #[name = "Ban"]
+    Ban,
+
+    #[name = "Purge"]
+    Purge,
+
+    #[name = "Voice (mute, deafen, disconnect, move)"]
+    Voice,
+
+    #[name = "Channels (slowmode, lockdown)"]
+    Channels,
+
+    #[name = "Cases"]
+    Cases,
+}
+
+impl ModerationCapability {
+    pub fn to_guild_config_value(
+        &self,
+    ) -> GuildConfigModerationCapability {
+        match self {
+            ModerationCapability::Warn => GuildConfigModerationCapability::Warn,
+            ModerationCapability::Timeout => GuildConfigModerationCapability::Timeout,
+            ModerationCapability::Kick => GuildConfigModerationCapability::Kick,
+            ModerationCapability::Ban => GuildConfigModerationCapability::Ban,
+            ModerationCapability::Purge => GuildConfigModerationCapability::Purge,
+            ModerationCapability::Voice => GuildConfigModerationCapability::Voice,
+            ModerationCapability::Channels => GuildConfigModerationCapability::Channels,
+            ModerationCapability::Cases => GuildConfigModerationCapability::Cases,
+        }
+    }
+
+    fn from_guild_config_value(
+        value: GuildConfigModerationCapability,
+    ) -> Self {
+        match value {
+            GuildConfigModerationCapability::Warn => ModerationCapability::Warn,
+            GuildConfigModerationCapability::Timeout => ModerationCapability::Timeout,
+            GuildConfigModerationCapability::Kick => ModerationCapability::Kick,
+            GuildConfigModerationCapability::Ban => ModerationCapability::Ban,
+            GuildConfigModerationCapability::Purge => ModerationCapability::Purge,
+            GuildConfigModerationCapability::Voice => ModerationCapability::Voice,
+            GuildConfigModerationCapability::Channels => ModerationCapability::Channels,
+            GuildConfigModerationCapability::Cases => ModerationCapability::Cases,
+        }
+    }
+}
+
+fn format_moderation_role(
+    role: &GuildConfigModerationRole,
+) -> String {
+    let capabilities_string =
+        role.capabilities
+        .iter()
+        .map(|capability| ModerationCapability::from_guild_config_value(*capability).name())
+        .collect::<Vec<&str>>()
+        .join(", ");
+
+    format!("- {}: {}", role.role_id.mention(), capabilities_string)
+}
+
+//------------------------------------------------------------//
+
+/// Configure whether discord's own permissions count towards moderation commands.
+#[
+    poise::command(
+        slash_command,
+        rename = "mode",
+    )
+]
+pub async fn moderation_mode(
+    ctx: Context<'_>,
+
+    #[description = "Who may use moderation commands in this guild."]
+    moderation_mode: ModerationMode,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    guild_config.set_moderation_mode(
+        moderation_mode.to_guild_config_value(),
+    ).await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Moderation Mode")
+            .description(format!("Set moderation mode to **{}**.", moderation_mode.name()))
+        )
+    ).await?;
+
+    Ok(())
+}
+
+/// Lists the moderation capabilities granted to roles in this guild.
+#[
+    poise::command(
+        slash_command,
+        rename = "roles",
+    )
+]
+pub async fn list_moderation_roles(
+    ctx: Context<'_>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let moderation_roles = guild_config.get_moderation_roles().await;
+
+    let description =
+        if moderation_roles.roles.is_empty() {
+            "No moderation capabilities have been granted to roles.\nUntil they are, discord's own permissions are used.".to_string()
+        } else {
+            format!(
+                "{}\n\nCapabilities that have not been granted to any role still use discord's own permissions.",
+                moderation_roles.roles
+                .iter()
+                .map(format_moderation_role)
+                .collect::<Vec<String>>()
+                .join("\n"),
+            )
+        };
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Moderation Roles")
+            .description(description)
+        )
+    ).await?;
+
+    Ok(())
+}
+
+/// Grants a moderation capability to a role.
+#[
+    poise::command(
+        slash_command,
+        rename = "grant",
+    )
+]
+pub async fn grant_moderation_role(
+    ctx: Context<'_>,
+
+    #[description = "The role to grant the capability to"]
+    role: serenity::Role,
+
+    #[description = "The capability to grant"]
+    capability: ModerationCapability,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let mut moderation_roles = guild_config.get_moderation_roles().await;
+
+    let capability_value = capability.to_guild_config_value();
+
+    match moderation_roles.roles.iter_mut().find(|moderation_role| moderation_role.role_id == role.id) {
+        Some(moderation_role) => {
+            if !moderation_role.capabilities.contains(&capability_value) {
+                moderation_role.capabilities.push(capability_value);
+            }
+        },
+        None => {
+            moderation_roles.roles.push(
+                GuildConfigModerationRole {
+                    role_id: role.id,
+                    capabilities: vec![capability_value],
+                }
+            );
+        },
+    }
+
+    guild_config.set_moderation_roles(moderation_roles).await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Moderation Roles")
+            .description(
+                format!(
+                    concat!(
+                        "Granted **{}** to {}.\n",
+                        "Unless discord overrides are allowed, **{}** now requires a granted role (or administrator), ",
+                        "instead of discord's own permissions.",
+                    ),
+                    capability.name(),
+                    role.mention(),
+                    capability.name(),
+                )
+            )
+        )
+    ).await?;
+
+    Ok(())
+}
+
+/// Revokes a moderation capability (or all of them) from a role.
+#[
+    poise::command(
+        slash_command,
+        rename = "revoke",
+    )
+]
+pub async fn revoke_moderation_role(
+    ctx: Context<'_>,
+
+    #[description = "The role to revoke the capability from"]
+    role: serenity::Role,
+
+    #[description = "The capability to revoke (all if omitted)"]
+    capability: Option<ModerationCapability>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let current_moderation_roles = guild_config.get_moderation_roles().await;
+
+    let new_roles =
+        current_moderation_roles.roles
+        .into_iter()
+        .map(|mut moderation_role| {
+            if moderation_role.role_id == role.id {
+                match capability {
+                    Some(capability) => moderation_role.capabilities.retain(|c| *c != capability.to_guild_config_value()),
+                    None => moderation_role.capabilities.clear(),
+                }
+            }
+
+            moderation_role
+        })
+        .filter(|moderation_role| !moderation_role.capabilities.is_empty())
+        .collect::<Vec<GuildConfigModerationRole>>();
+
+    guild_config.set_moderation_roles(
+        GuildConfigModerationRoles {
+            roles: new_roles,
+        }
+    ).await?;
+
+    let capability_string = match capability {
+        Some(capability) => format!("**{}**", capability.name()),
+        None => "all moderation capabilities".to_string(),
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Moderation Roles")
+            .description(format!("Revoked {} from {}.", capability_string, role.mention()))
+        )
+    ).await?;
+
+    Ok(())
+}
+
+//------------------------------------------------------------//
+
+/// Configure which roles may use moderation commands in your guild.
+#[
+    poise::command(
+        slash_command,
+        subcommands(
+            "moderation_mode",
+            "list_moderation_roles",
+            "grant_moderation_role",
+            "revoke_moderation_role",
+        ),
+    )
+]
+pub async fn moderation(
+    _ctx: Context<'_>,
+) -> Result<(), Error> {
+    Ok(())
+}
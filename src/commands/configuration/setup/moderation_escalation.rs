@@ -0,0 +1,302 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use itertools::Itertools;
+
+use poise::ChoiceParameter;
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::Context;
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::common::helpers::time::format_duration;
+
+use crate::common::database::interfaces::guild_config::{
+    GuildConfig,
+    GuildConfigEscalationAction,
+    GuildConfigEscalationRule,
+    GuildConfigModerationEscalation,
+};
+
+//------------------------------------------------------------//
+
+// The list of escalation actions available publicly.
+// Note: Keep separate from `GuildConfigEscalationAction`.
+#[derive(poise::ChoiceParameter)]
+enum EscalationAction {
+    #[name = "Timeout"]
+    Timeout,
+
+    #[name = "Kick"]
+    Kick,
+
+    #[name = "Ban"]
+    Ban,
+}
+
+impl EscalationAction {
+    pub fn to_guild_config_value(
+        &self,
+    ) -> GuildConfigEscalationAction {
+        match self {
+            EscalationAction::Timeout => GuildConfigEscalationAction::Timeout,
+            EscalationAction::Kick => GuildConfigEscalationAction::Kick,
+            EscalationAction::Ban => GuildConfigEscalationAction::Ban,
+        }
+    }
+}
+
+fn format_escalation_rule(
+    rule: &GuildConfigEscalationRule,
+) -> String {
+    let action_string = match rule.action {
+        GuildConfigEscalationAction::Timeout => {
+            match rule.duration_seconds {
+                Some(seconds) => format!(
+                    "Timeout for {}",
+                    format_duration(std::time::Duration::from_secs(seconds as u64))
+                ),
+                None => "Timeout".to_string(),
+            }
+        },
+        GuildConfigEscalationAction::Kick => "Kick".to_string(),
+        GuildConfigEscalationAction::Ban => "Ban".to_string(),
+    };
+
+    format!("- **{}** warnings -> {}", rule.warning_count, action_string)
+}
+
+//------------------------------------------------------------//
+
+/// Lists the warning escalation rules for this guild.
+#[
+    poise::command(
+        slash_command,
+        rename = "list",
+    )
+]
+pub async fn list_moderation_escalation(
+    ctx: Context<'_>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let moderation_escalation = guild_config.get_moderation_escalation().await;
+
+    let rules_string =
+        if moderation_escalation.rules.is_empty() {
+            "No escalation rules are configured.".to_string()
+        } else {
+            moderation_escalation.rules
+            .iter()
+            .sorted_by_key(|rule| rule.warning_count)
+            .map(format_escalation_rule)
+            .join("\n") +
+            "\n\nEach warning applies the rule with the highest amount of warnings that was reached."
+        };
+
+    let decay_string = match moderation_escalation.warning_decay_days {
+        Some(days) => format!("Warnings expire after **{}** days.", days),
+        None => "Warnings never expire.".to_string(),
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Moderation Escalation")
+            .description(format!("{}\n\n{}", rules_string, decay_string))
+        )
+    ).await?;
+
+    Ok(())
+}
+
+/// Adds or replaces a warning escalation rule for this guild.
+#[
+    poise::command(
+        slash_command,
+        rename = "set_rule",
+    )
+]
+pub async fn set_moderation_escalation_rule(
+    ctx: Context<'_>,
+
+    #[min = 1]
+    #[max = 100]
+    #[description = "The amount of active warnings that triggers this rule"]
+    warnings: u32,
+
+    #[description = "The action to perform"]
+    action: EscalationAction,
+
+    #[min = 1]
+    #[max = 672]
+    #[description = "How many hours (max 28 days) to timeout, only used for timeouts"]
+    hours: Option<u32>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let current_moderation_escalation = guild_config.get_moderation_escalation().await;
+
+    let new_rule = GuildConfigEscalationRule {
+        warning_count: warnings,
+        action: action.to_guild_config_value(),
+        duration_seconds: hours.map(|hours| hours * 60 * 60),
+    };
+
+    let new_rule_string = format_escalation_rule(&new_rule);
+
+    // only one rule is allowed per warning count
+    let new_rules = [
+        current_moderation_escalation.rules
+        .into_iter()
+        .filter(|rule| rule.warning_count != warnings)
+        .collect::<Vec<GuildConfigEscalationRule>>(),
+        vec![new_rule],
+    ].concat();
+
+    guild_config.set_moderation_escalation(
+        GuildConfigModerationEscalation {
+            rules: new_rules,
+            ..current_moderation_escalation
+        }
+    ).await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Moderation Escalation")
+            .description(format!("Set escalation rule:\n{}", new_rule_string))
+        )
+    ).await?;
+
+    Ok(())
+}
+
+/// Removes a warning escalation rule from this guild.
+#[
+    poise::command(
+        slash_command,
+        rename = "remove_rule",
+    )
+]
+pub async fn remove_moderation_escalation_rule(
+    ctx: Context<'_>,
+
+    #[min = 1]
+    #[max = 100]
+    #[description = "The amount of warnings of the rule to remove"]
+    warnings: u32,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let current_moderation_escalation = guild_config.get_moderation_escalation().await;
+
+    let new_rules =
+        current_moderation_escalation.rules
+        .into_iter()
+        .filter(|rule| rule.warning_count != warnings)
+        .collect::<Vec<GuildConfigEscalationRule>>();
+
+    guild_config.set_moderation_escalation(
+        GuildConfigModerationEscalation {
+            rules: new_rules,
+            ..current_moderation_escalation
+        }
+    ).await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Moderation Escalation")
+            .description(format!("Removed the escalation rule for **{}** warnings.", warnings))
+        )
+    ).await?;
+
+    Ok(())
+}
+
+/// Sets how long warnings count towards escalation.
+#[
+    poise::command(
+        slash_command,
+        rename = "set_decay",
+    )
+]
+pub async fn set_moderation_escalation_decay(
+    ctx: Context<'_>,
+
+    #[min = 0]
+    #[max = 365]
+    #[description = "Days until a warning expires (`0` to never expire)"]
+    days: u32,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let current_moderation_escalation = guild_config.get_moderation_escalation().await;
+
+    let warning_decay_days = if days == 0 { None } else { Some(days) };
+
+    guild_config.set_moderation_escalation(
+        GuildConfigModerationEscalation {
+            warning_decay_days: warning_decay_days,
+            ..current_moderation_escalation
+        }
+    ).await?;
+
+    let description = match warning_decay_days {
+        Some(days) => format!("Warnings will now expire after **{}** days.", days),
+        None => "Warnings will now never expire.".to_string(),
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Moderation Escalation")
+            .description(description)
+        )
+    ).await?;
+
+    Ok(())
+}
+
+//------------------------------------------------------------//
+
+/// Configure automatic escalation of warnings for your guild.
+#[
+    poise::command(
+        slash_command,
+        subcommands(
+            "list_moderation_escalation",
+            "set_moderation_escalation_rule",
+            "remove_moderation_escalation_rule",
+            "set_moderation_escalation_decay",
+        ),
+    )
+]
+pub async fn moderation_escalation(
+    _ctx: Context<'_>,
+) -> Result<(), Error> {
+    Ok(())
+}
@@ -0,0 +1,79 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::Context;
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::common::music::{self, autocomplete_search_source};
+
+use crate::common::database::interfaces::guild_config::{GuildConfig, GuildConfigMusic};
+
+//------------------------------------------------------------//
+
+fn format_music(
+    music: &GuildConfigMusic,
+) -> String {
+    format!(
+        concat!(
+            "Plain queries are searched on **{}**, unless another source is requested.\n",
+            "At most **{}** tracks are added to the queue from a playlist.",
+        ),
+        music.default_search_source.name(),
+        music.max_playlist_tracks,
+    )
+}
+
+//------------------------------------------------------------//
+
+/// Configure music playback for your guild, omitted options are left unchanged.
+#[poise::command(slash_command)]
+pub async fn music(
+    ctx: Context<'_>,
+
+    #[autocomplete = "autocomplete_search_source"]
+    #[description = "The source that plain queries are searched on"]
+    default_source: Option<String>,
+
+    #[min = 1]
+    #[max = 1000]
+    #[description = "The maximum amount of tracks that are added to the queue from a playlist"]
+    max_playlist_tracks: Option<u32>,
+) -> Result<(), Error> {
+    let default_search_source = match default_source {
+        Some(default_source) => Some(music::find_search_source(&default_source).ok_or("Unknown search source.")?),
+        None => None,
+    };
+
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let current_music = guild_config.get_music().await;
+
+    let new_music = GuildConfigMusic {
+        max_playlist_tracks: max_playlist_tracks.unwrap_or(current_music.max_playlist_tracks),
+        default_search_source: default_search_source.unwrap_or(current_music.default_search_source),
+    };
+
+    guild_config.set_music(new_music.clone()).await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Music")
+            .description(format_music(&new_music))
+        )
+    ).await?;
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::Context;
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::common::helpers::time::format_duration;
+
+use crate::common::database::interfaces::guild_config::{GuildConfig, GuildConfigSpamProtection};
+
+//------------------------------------------------------------//
+
+fn format_spam_protection(
+    spam_protection: &GuildConfigSpamProtection,
+) -> String {
+    format!(
+        concat!(
+            "Enabled: **{}**\n",
+            "More than **{}** messages in one channel within **{}** seconds is spam.\n",
+            "The same message **{}** times within **{}** seconds is spam.\n",
+            "Spam is deleted, after **{}** recent violations members are also timed out.\n",
+            "The first timeout lasts **{}**, doubling for each further violation.",
+        ),
+        if spam_protection.enabled { "Yes" } else { "No" },
+        spam_protection.max_messages_per_channel,
+        spam_protection.message_window_seconds,
+        spam_protection.max_duplicate_messages,
+        spam_protection.duplicate_window_seconds,
+        spam_protection.violations_before_timeout,
+        format_duration(std::time::Duration::from_secs(spam_protection.base_timeout_seconds as u64)),
+    )
+}
+
+//------------------------------------------------------------//
+
+/// Configure message spam protection for your guild, omitted options are left unchanged.
+#[poise::command(slash_command)]
+pub async fn spam_protection(
+    ctx: Context<'_>,
+
+    #[description = "Whether spam should be detected"]
+    enabled: Option<bool>,
+
+    #[min = 2]
+    #[max = 50]
+    #[description = "Sending more than this many messages in one channel within the window is spam"]
+    max_messages_per_channel: Option<u32>,
+
+    #[min = 1]
+    #[max = 60]
+    #[description = "The window (in seconds) that messages per channel are counted in"]
+    message_window_seconds: Option<u32>,
+
+    #[min = 2]
+    #[max = 20]
+    #[description = "Sending the same message this many times within the window is spam"]
+    max_duplicate_messages: Option<u32>,
+
+    #[min = 1]
+    #[max = 600]
+    #[description = "The window (in seconds) that duplicate messages are counted in"]
+    duplicate_window_seconds: Option<u32>,
+
+    #[min = 0]
+    #[max = 10]
+    #[description = "How many recent violations only delete spam, before members are timed out"]
+    violations_before_timeout: Option<u32>,
+
+    #[min = 1]
+    #[max = 40320]
+    #[description = "How many minutes (max 28 days) the first timeout lasts"]
+    base_timeout_minutes: Option<u32>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let current_spam_protection = guild_config.get_spam_protection().await;
+
+    let new_spam_protection = GuildConfigSpamProtection {
+        enabled: enabled.unwrap_or(current_spam_protection.enabled),
+        max_messages_per_channel: max_messages_per_channel.unwrap_or(current_spam_protection.max_messages_per_channel),
+        message_window_seconds: message_window_seconds.unwrap_or(current_spam_protection.message_window_seconds),
+        max_duplicate_messages: max_duplicate_messages.unwrap_or(current_spam_protection.max_duplicate_messages),
+        duplicate_window_seconds: duplicate_window_seconds.unwrap_or(current_spam_protection.duplicate_window_seconds),
+        violations_before_timeout: violations_before_timeout.unwrap_or(current_spam_protection.violations_before_timeout),
+        base_timeout_seconds:
+            base_timeout_minutes
+            .map(|minutes| minutes * 60)
+            .unwrap_or(current_spam_protection.base_timeout_seconds),
+    };
+
+    guild_config.set_spam_protection(new_spam_protection.clone()).await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Guild Configuration - Spam Protection")
+            .description(format_spam_protection(&new_spam_protection))
+        )
+    ).await?;
+
+    Ok(())
+}
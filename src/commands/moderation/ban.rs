@@ -15,7 +15,93 @@ use crate::common::branding;
 
 use crate::common::helpers::bot::create_escaped_code_block;
 
-use crate::common::moderation;
+use crate::common::helpers::time::{autocomplete_duration, format_duration, parse_duration_within};
+
+use crate::common::moderation::{self, ModerationActionContext};
+
+use crate::common::moderation_appeals;
+
+use crate::common::database::interfaces::guild_config::GuildConfigModerationCapability;
+
+use crate::common::database::interfaces::moderation_case::{ModerationCase, ModerationCaseAction};
+
+use crate::common::scheduler;
+
+//------------------------------------------------------------//
+
+/// Bans a member, notifies them, and records a moderation case.
+/// When a duration is provided, the member will automatically be unbanned afterwards.
+pub async fn ban_member(
+    action_ctx: &ModerationActionContext<'_>,
+    target_member: &serenity::Member,
+    duration: Option<chrono::Duration>,
+    reason: &str,
+) -> Result<ModerationCase, Error> {
+    let duration_string = match duration {
+        Some(duration) => format!(" for {}", format_duration(duration.to_std()?)),
+        None => String::new(),
+    };
+
+    let notification_message = target_member.user.id.dm(
+        action_ctx.http,
+        serenity::CreateMessage::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title(format!("{} - Moderation", action_ctx.guild_name))
+            .description(
+                format!(
+                    "You were banned from {}{} by {} for:\n{}",
+                    action_ctx.guild_name,
+                    duration_string,
+                    action_ctx.actor_user_id.mention(),
+                    create_escaped_code_block(None, reason),
+                )
+            )
+        )
+    ).await.ok(); // ignore errors
+
+    // Hard-coded for now to not delete any messages.
+    // In the future, this should be a configurable option.
+    let delete_message_days = 0;
+
+    target_member.ban(action_ctx.http, delete_message_days, Some(reason)).await?;
+
+    let unban_dedupe_key = scheduler::create_unban_dedupe_key(target_member.guild_id, target_member.user.id);
+
+    match duration {
+        Some(duration) => {
+            scheduler::schedule_job(
+                scheduler::ScheduledJobPayload::Unban {
+                    discord_guild_id: target_member.guild_id,
+                    discord_user_id: target_member.user.id,
+                    reason: format!("Temporary ban of {} has expired.", format_duration(duration.to_std()?)),
+                },
+                chrono::Utc::now() + duration,
+                Some(unban_dedupe_key),
+            ).await?;
+        },
+        None => {
+            // a permanent ban replaces any earlier temporary ban
+            scheduler::cancel_job(&unban_dedupe_key).await?;
+        },
+    }
+
+    let moderation_case = moderation::record_moderation_case(
+        action_ctx,
+        ModerationCaseAction::Ban,
+        Some(target_member.user.id),
+        reason,
+        duration.map(|duration| duration.num_seconds() as u32),
+    ).await?;
+
+    // the action has already been performed, so failing to offer an appeal should not fail the action
+    if let Err(why) = moderation_appeals::add_appeal_button_to_notification(action_ctx.http, notification_message, &moderation_case).await {
+        eprintln!("Failed to add an appeal button to a moderation notification: {:?}", why);
+    }
+
+    Ok(moderation_case)
+}
 
 //------------------------------------------------------------//
 
@@ -28,7 +114,6 @@ use crate::common::moderation;
         install_context = "Guild",
         interaction_context = "Guild",
         user_cooldown = "5", // in seconds
-        default_member_permissions = "BAN_MEMBERS",
         required_bot_permissions = "BAN_MEMBERS",
     )
 ]
@@ -38,6 +123,10 @@ pub async fn ban(
     #[description = "The member to ban"]
     member: serenity::Member,
 
+    #[autocomplete = "autocomplete_duration"]
+    #[description = "How long until the member is automatically unbanned, e.g. `12h` or `1w` (permanent if omitted)"]
+    duration: Option<String>,
+
     #[description = "Why this action was performed"]
     reason: Option<String>,
 ) -> Result<(), Error> {
@@ -49,6 +138,13 @@ pub async fn ban(
         .expect("There should be a member in this context.")
         .clone();
 
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Ban,
+    ).await?;
+
     let guild = ctx.guild().expect("There should be a guild in this context.").clone();
 
     let my_id = ctx.serenity_context().cache.current_user().id;
@@ -77,29 +173,32 @@ pub async fn ban(
 
     let reason = reason.unwrap_or("A reason was not provided.".to_string());
 
-    target_member.user.id.dm(
-        &ctx.http(),
-        serenity::CreateMessage::default()
-        .embed(
-            serenity::CreateEmbed::default()
-            .color(branding::color::PRIMARY)
-            .title(format!("{} - Moderation", guild.name))
-            .description(
-                format!(
-                    "You were banned from {} by {} for:\n{}",
-                    guild.name,
-                    executing_member.user.mention(),
-                    create_escaped_code_block(None, &reason),
-                )
-            )
-        )
-    ).await.ok(); // ignore errors
+    let duration = match duration {
+        Some(duration) => Some(
+            chrono::Duration::from_std(
+                parse_duration_within(
+                    &duration,
+                    std::time::Duration::from_secs(60),
+                    std::time::Duration::from_secs(365 * 24 * 60 * 60),
+                )?
+            )?
+        ),
+        None => None,
+    };
+
+    let moderation_case = ban_member(
+        &ModerationActionContext::from_context(&ctx),
+        &target_member,
+        duration,
+        &reason,
+    ).await?;
 
-    // Hard-coded for now to not delete any messages.
-    // In the future, this should be a configurable option.
-    let delete_message_days = 0;
+    let case_id = moderation_case.get_case_id().await;
 
-    target_member.ban(&ctx.http(), delete_message_days, Some(&reason)).await?;
+    let duration_string = match duration {
+        Some(duration) => format!(" for {}", format_duration(duration.to_std()?)),
+        None => String::new(),
+    };
 
     ctx.send(
         poise::CreateReply::default()
@@ -109,12 +208,14 @@ pub async fn ban(
             .title("Moderation")
             .description(
                 format!(
-                    "{} was banned by {} for:\n{}",
+                    "{} was banned{} by {} for:\n{}",
                     target_member.user.mention(),
+                    duration_string,
                     executing_member.user.mention(),
                     create_escaped_code_block(None, &reason),
                 )
             )
+            .footer(moderation::create_moderation_case_footer(case_id))
         )
     ).await?;
 
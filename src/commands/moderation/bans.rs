@@ -2,8 +2,14 @@
 //                   Copyright (c) MidSpike                   //
 //------------------------------------------------------------//
 
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
 use poise::serenity_prelude::nonmax::NonMaxU16;
+use poise::serenity_prelude::ComponentInteractionCollector;
 use poise::serenity_prelude::{self as serenity};
+use serenity::futures::stream::StreamExt;
 
 //------------------------------------------------------------//
 
@@ -15,33 +21,221 @@ use crate::common::branding;
 
 use crate::common::helpers::bot::create_escaped_code_block;
 
+use crate::common::moderation;
+
+use crate::common::mass_moderation::{self, MassModerationAction};
+
+use crate::common::database::interfaces::guild_config::GuildConfigModerationCapability;
+
 //------------------------------------------------------------//
 
-/// Lists the most recent bans.
-#[poise::command(slash_command)]
-pub async fn list(
-    ctx: Context<'_>,
-) -> Result<(), Error> {
-    let guild = ctx.guild().expect("There should be a guild in this context.").clone();
+const DISCORD_BANS_PAGE_SIZE: u16 = 1000;
 
-    let _executing_member =
-        ctx
-        .author_member().await
-        .expect("There should be a member in this context.")
-        .clone();
+const BANS_PER_PAGE: usize = 10;
+
+/// Reasons (of up to 512 characters) are shortened, so that a full page fits in an embed description.
+const MAX_BAN_REASON_LENGTH_PER_PAGE: usize = 200;
+
+/// The largest ban list export that will be read.
+const MAX_BANS_EXPORT_FILE_SIZE: u32 = 1024 * 1024;
+
+const BANS_EXPORT_CSV_HEADER: &str = "user_id,user_name,reason";
+
+/// Imports are larger than other mass bans, but must still finish before the interaction expires (after 15 minutes).
+const MAX_BANS_IMPORT_TARGETS: usize = 1500;
+
+//------------------------------------------------------------//
 
-    let guild_bans = guild.id.bans(&ctx.http(), None, Some(NonMaxU16::from(25))).await?;
+#[derive(Serialize, Deserialize)]
+struct BanExportEntry {
+    user_id: serenity::UserId,
+    user_name: String,
+    reason: Option<String>,
+}
+
+impl BanExportEntry {
+    fn from_ban(
+        ban: &serenity::Ban,
+    ) -> Self {
+        Self {
+            user_id: ban.user.id,
+            user_name: ban.user.name.to_string(),
+            reason: ban.reason.as_deref().map(|reason| reason.to_string()),
+        }
+    }
+}
+
+/// Fetches every ban in the guild, ordered by user id (discord does not expose when a ban was created).
+async fn fetch_all_guild_bans(
+    ctx: &Context<'_>,
+    guild_id: serenity::GuildId,
+) -> Result<Vec<serenity::Ban>, Error> {
+    let mut guild_bans = Vec::new();
+
+    loop {
+        let target = guild_bans.last().map(|ban: &serenity::Ban| serenity::UserPagination::After(ban.user.id));
+
+        let page = guild_id.bans(
+            &ctx.http(),
+            target,
+            Some(NonMaxU16::new(DISCORD_BANS_PAGE_SIZE).unwrap_or(NonMaxU16::MAX)),
+        ).await?;
+
+        let is_last_page = page.len() < DISCORD_BANS_PAGE_SIZE as usize;
+
+        guild_bans.extend(page);
+
+        if is_last_page {
+            break;
+        }
+
+        // throttle our requests to avoid hitting the rate limit
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+
+    Ok(guild_bans)
+}
+
+fn is_ban_matching_search(
+    ban: &serenity::Ban,
+    lowercase_search: &str,
+) -> bool {
+    ban.user.name.to_lowercase().contains(lowercase_search) ||
+    ban.user.id.to_string().contains(lowercase_search) ||
+    ban.reason.as_deref().is_some_and(|reason| reason.to_lowercase().contains(lowercase_search))
+}
+
+//------------------------------------------------------------//
+
+fn escape_csv_field(
+    value: &str,
+) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn generate_bans_export_csv(
+    entries: &[BanExportEntry],
+) -> String {
+    let rows =
+        entries
+        .iter()
+        .map(
+            |entry| format!(
+                "{},{},{}",
+                entry.user_id,
+                escape_csv_field(&entry.user_name),
+                escape_csv_field(entry.reason.as_deref().unwrap_or("")),
+            )
+        );
+
+    std::iter::once(BANS_EXPORT_CSV_HEADER.to_string())
+    .chain(rows)
+    .collect::<Vec<String>>()
+    .join("\n")
+}
+
+/// Parses csv records, allowing quoted fields to contain separators, quotes, and new lines.
+fn parse_csv_records(
+    content: &str,
+) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut is_quoted = false;
+
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if is_quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next(); // skip the escaped quote
+            },
+            '"' => is_quoted = !is_quoted,
+            ',' if !is_quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !is_quoted => {}, // handled by the following new line
+            '\n' if !is_quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            },
+            _ => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    records
+}
+
+/// Parses the user ids from a ban list export, in either the json or csv format.
+fn parse_bans_export_user_ids(
+    content: &str,
+) -> Result<Vec<serenity::UserId>, Error> {
+    if content.trim_start().starts_with('[') {
+        let Ok(entries) = serde_json::from_str::<Vec<BanExportEntry>>(content) else {
+            return Err("The ban list export is not valid json.".into());
+        };
+
+        return Ok(entries.into_iter().map(|entry| entry.user_id).collect());
+    }
+
+    let mut user_ids = Vec::new();
+
+    let records =
+        parse_csv_records(content)
+        .into_iter()
+        .filter(|record| record.iter().any(|field| !field.trim().is_empty())); // skip blank lines
+
+    for record in records {
+        let user_id_field = record.first().map(|field| field.trim()).unwrap_or_default();
+
+        if user_id_field == "user_id" {
+            continue; // skip the header
+        }
+
+        match user_id_field.parse::<u64>() {
+            Ok(user_id) if user_id > 0 => user_ids.push(serenity::UserId::new(user_id)),
+            _ => return Err(format!("`{}` is not a valid user id.", user_id_field).into()),
+        }
+    }
+
+    Ok(user_ids)
+}
+
+//------------------------------------------------------------//
+
+fn create_bans_page_embed<'a>(
+    guild_bans: &[serenity::Ban],
+    page_index: usize,
+    num_pages: usize,
+) -> serenity::CreateEmbed<'a> {
+    let current_page_number = page_index + 1;
 
     let guild_bans_string =
         guild_bans
         .iter()
+        .skip(page_index * BANS_PER_PAGE)
+        .take(BANS_PER_PAGE)
         .map(
             |ban| {
                 let user = &ban.user;
                 let user_id = user.id.get();
                 let user_name = &user.name;
                 let reason = ban.reason.as_deref().unwrap_or("No reason provided.");
-                let reason = create_escaped_code_block(None, &reason.to_string());
+                let reason =
+                    if reason.chars().count() > MAX_BAN_REASON_LENGTH_PER_PAGE {
+                        format!("{}...", reason.chars().take(MAX_BAN_REASON_LENGTH_PER_PAGE - 3).collect::<String>())
+                    } else {
+                        reason.to_string()
+                    };
+                let reason = create_escaped_code_block(None, &reason);
 
                 format!("**{} ({})**\n{}", user_name, user_id, reason)
             }
@@ -49,18 +243,285 @@ pub async fn list(
         .collect::<Vec<String>>()
         .join("\n");
 
+    serenity::CreateEmbed::default()
+    .color(branding::color::PRIMARY)
+    .title(format!("Bans ({} / {})", current_page_number, num_pages))
+    .description(guild_bans_string)
+    .footer(serenity::CreateEmbedFooter::new(format!("{} bans", guild_bans.len())))
+}
+
+//------------------------------------------------------------//
+
+#[derive(poise::ChoiceParameter, Clone, Copy)]
+enum BansExportFormat {
+    #[name = "CSV"]
+    Csv,
+
+    #[name = "JSON"]
+    Json,
+}
+
+//------------------------------------------------------------//
+
+/// Lists the bans in this guild.
+#[poise::command(slash_command)]
+pub async fn list(
+    ctx: Context<'_>,
+
+    #[description = "Only list bans whose username, id, or reason contains this"]
+    search: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let executing_member =
+        ctx
+        .author_member().await
+        .expect("There should be a member in this context.")
+        .clone();
+
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Ban,
+    ).await?;
+
+    // fetching large ban lists may take a while
+    ctx.defer().await?;
+
+    let mut guild_bans = fetch_all_guild_bans(&ctx, guild_id).await?;
+
+    if let Some(search) = &search {
+        let lowercase_search = search.to_lowercase();
+
+        guild_bans.retain(|ban| is_ban_matching_search(ban, &lowercase_search));
+    }
+
+    if guild_bans.is_empty() {
+        ctx.send(
+            poise::CreateReply::default().embed(
+                serenity::CreateEmbed::default()
+                .color(branding::color::PRIMARY)
+                .title("Bans")
+                .description("No bans were found.")
+            )
+        ).await?;
+
+        return Ok(());
+    }
+
+    let previous_page_button_id = format!("{}-previous-button", ctx.id());
+    let next_page_button_id = format!("{}-next-button", ctx.id());
+
+    let num_pages = guild_bans.len().div_ceil(BANS_PER_PAGE);
+    let mut page_index = 0;
+
+    let reply_handle = ctx.send(
+        poise::CreateReply::default()
+        .embed(create_bans_page_embed(&guild_bans, page_index, num_pages))
+        .components(vec![
+            serenity::CreateComponent::ActionRow(
+                serenity::CreateActionRow::buttons(vec![
+                    serenity::CreateButton::new(&previous_page_button_id)
+                    .style(serenity::ButtonStyle::Secondary)
+                    .label("Previous Page"),
+
+                    serenity::CreateButton::new(&next_page_button_id)
+                    .style(serenity::ButtonStyle::Secondary)
+                    .label("Next Page"),
+                ])
+            )
+        ])
+    ).await?;
+
+    let message = reply_handle.message().await?;
+
+    let mut component_interaction_collector =
+        ComponentInteractionCollector::new(&ctx.serenity_context())
+        .author_id(ctx.author().id)
+        .message_id(message.id)
+        .timeout(std::time::Duration::from_secs(5 * 60))
+        .stream();
+
+    while let Some(component_interaction) = component_interaction_collector.next().await {
+        // Defer while we process the interaction.
+        component_interaction.defer(&ctx.http()).await?;
+
+        let component_interaction_id = component_interaction.data.custom_id.clone();
+
+        match component_interaction_id {
+            id if id == previous_page_button_id => {
+                page_index =
+                    if page_index == 0 { num_pages - 1 } // skip to end
+                    else { page_index - 1 }; // previous page
+            },
+            id if id == next_page_button_id => {
+                page_index =
+                    if page_index == num_pages - 1 { 0 } // skip to start
+                    else { page_index + 1 }; // next page
+            },
+            _ => {}, // Ignore unknown button ids
+        }
+
+        // Edit response since we deferred earlier.
+        component_interaction.edit_response(
+            &ctx.http(),
+            serenity::EditInteractionResponse::default().embed(
+                create_bans_page_embed(&guild_bans, page_index, num_pages)
+            )
+        ).await?;
+    }
+
+    // After the loop, remove the buttons to clean up.
+    // This prevents stale components from being left behind.
+    reply_handle.edit(
+        poise::Context::Application(ctx),
+        poise::CreateReply::default()
+        .embed(create_bans_page_embed(&guild_bans, page_index, num_pages))
+        .components(vec![])
+    ).await?;
+
+    Ok(())
+}
+
+/// Exports every ban in this guild to a file.
+#[poise::command(slash_command)]
+pub async fn export(
+    ctx: Context<'_>,
+
+    #[description = "The format of the exported file"]
+    format: BansExportFormat,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let executing_member =
+        ctx
+        .author_member().await
+        .expect("There should be a member in this context.")
+        .clone();
+
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Ban,
+    ).await?;
+
+    // fetching large ban lists may take a while
+    ctx.defer().await?;
+
+    let guild_bans = fetch_all_guild_bans(&ctx, guild_id).await?;
+
+    let entries: Vec<BanExportEntry> =
+        guild_bans
+        .iter()
+        .map(BanExportEntry::from_ban)
+        .collect();
+
+    let unix_epoch = chrono::Utc::now().timestamp();
+
+    let attachment = match format {
+        BansExportFormat::Csv => serenity::CreateAttachment::bytes(
+            generate_bans_export_csv(&entries).into_bytes(),
+            format!("bans_{}_{}.csv", guild_id, unix_epoch),
+        ),
+        BansExportFormat::Json => serenity::CreateAttachment::bytes(
+            serde_json::to_string_pretty(&entries)?.into_bytes(),
+            format!("bans_{}_{}.json", guild_id, unix_epoch),
+        ),
+    };
+
     ctx.send(
-        poise::CreateReply::default().embed(
+        poise::CreateReply::default()
+        .embed(
             serenity::CreateEmbed::default()
             .color(branding::color::PRIMARY)
-            .title("Recent Bans")
-            .description(guild_bans_string)
+            .title("Bans")
+            .description(
+                format!(
+                    "Exported {} bans, they can be replicated in another guild with `/bans import`.",
+                    entries.len(),
+                )
+            )
         )
+        .attachment(attachment)
     ).await?;
 
     Ok(())
 }
 
+/// Replicates the bans from another guild's ban list export.
+#[poise::command(slash_command)]
+pub async fn import(
+    ctx: Context<'_>,
+
+    #[description = "A ban list export (CSV or JSON) from `/bans export`"]
+    file: serenity::Attachment,
+
+    #[description = "Why this action was performed"]
+    reason: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let executing_member =
+        ctx
+        .author_member().await
+        .expect("There should be a member in this context.")
+        .clone();
+
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Ban,
+    ).await?;
+
+    if file.size > MAX_BANS_EXPORT_FILE_SIZE {
+        return Err("The ban list export must not be larger than 1 MiB.".into());
+    }
+
+    let reason = reason.unwrap_or("Replicated from another guild's ban list.".to_string());
+
+    // fetching large ban lists may take a while
+    ctx.defer().await?;
+
+    let Ok(file_content) = String::from_utf8(file.download().await?) else {
+        return Err("The ban list export must be a text file.".into());
+    };
+
+    let imported_user_ids = parse_bans_export_user_ids(&file_content)?;
+
+    let banned_user_ids: HashSet<serenity::UserId> =
+        fetch_all_guild_bans(&ctx, guild_id).await?
+        .iter()
+        .map(|ban| ban.user.id)
+        .collect();
+
+    let mut target_user_ids = Vec::new();
+
+    for user_id in imported_user_ids {
+        if !banned_user_ids.contains(&user_id) && !target_user_ids.contains(&user_id) {
+            target_user_ids.push(user_id);
+        }
+    }
+
+    if target_user_ids.is_empty() {
+        return Err("Every user in the ban list export is already banned.".into());
+    }
+
+    mass_moderation::perform_mass_moderation_action_on_users(
+        &ctx,
+        MassModerationAction::Ban {
+            delete_message_days: 0,
+        },
+        &target_user_ids,
+        MAX_BANS_IMPORT_TARGETS,
+        &reason,
+    ).await
+}
+
+//------------------------------------------------------------//
+
 /// Manage banned users in this guild.
 #[
     poise::command(
@@ -69,9 +530,8 @@ pub async fn list(
         category = "Moderation",
         install_context = "Guild",
         interaction_context = "Guild",
-        subcommands("list"),
+        subcommands("list", "export", "import"),
         user_cooldown = "10", // in seconds
-        default_member_permissions = "VIEW_AUDIT_LOG | BAN_MEMBERS",
         required_bot_permissions = "VIEW_AUDIT_LOG | BAN_MEMBERS",
     )
 ]
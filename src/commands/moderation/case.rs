@@ -0,0 +1,221 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::serenity_prelude::{self as serenity, FormattedTimestamp, FormattedTimestampStyle, Mentionable};
+
+//------------------------------------------------------------//
+
+use crate::Context;
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::common::helpers::bot::create_escaped_code_block;
+
+use crate::common::moderation;
+
+use crate::common::database::interfaces::guild_config::GuildConfigModerationCapability;
+
+use crate::common::database::interfaces::moderation_case::ModerationCase;
+
+//------------------------------------------------------------//
+
+const MAX_HISTORY_CASES: i64 = 15;
+
+const MAX_HISTORY_REASON_LENGTH: usize = 100;
+
+//------------------------------------------------------------//
+
+/// View a moderation case.
+#[poise::command(slash_command)]
+pub async fn view(
+    ctx: Context<'_>,
+
+    #[min = 1]
+    #[description = "The case number to view"]
+    id: u32,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let executing_member =
+        ctx
+        .author_member().await
+        .expect("There should be a member in this context.")
+        .clone();
+
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Cases,
+    ).await?;
+
+    let Some(moderation_case) = ModerationCase::fetch(guild_id, id).await? else {
+        ctx.say(format!("Case #{} does not exist.", id)).await?;
+
+        return Ok(());
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(moderation::create_moderation_case_embed(&moderation_case).await?)
+    ).await?;
+
+    Ok(())
+}
+
+/// Change the reason of a moderation case.
+#[poise::command(slash_command)]
+pub async fn edit_reason(
+    ctx: Context<'_>,
+
+    #[min = 1]
+    #[description = "The case number to edit"]
+    id: u32,
+
+    #[description = "The new reason for this case"]
+    reason: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let executing_member =
+        ctx
+        .author_member().await
+        .expect("There should be a member in this context.")
+        .clone();
+
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Cases,
+    ).await?;
+
+    let Some(moderation_case) = ModerationCase::fetch(guild_id, id).await? else {
+        ctx.say(format!("Case #{} does not exist.", id)).await?;
+
+        return Ok(());
+    };
+
+    moderation_case.set_reason(reason).await?;
+
+    // re-fetch the case to reflect the updated reason
+    let moderation_case =
+        ModerationCase::fetch(guild_id, id).await?
+        .expect("The case should still exist after updating it.");
+
+    ctx.send(
+        poise::CreateReply::default()
+        .content(format!("Updated the reason for case #{}.", id))
+        .embed(moderation::create_moderation_case_embed(&moderation_case).await?)
+    ).await?;
+
+    Ok(())
+}
+
+/// View the moderation history of a member.
+#[poise::command(slash_command)]
+pub async fn history(
+    ctx: Context<'_>,
+
+    #[description = "The member to view the moderation history of"]
+    member: serenity::User,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let executing_member =
+        ctx
+        .author_member().await
+        .expect("There should be a member in this context.")
+        .clone();
+
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Cases,
+    ).await?;
+
+    let moderation_cases = ModerationCase::fetch_for_target(guild_id, member.id, MAX_HISTORY_CASES).await?;
+
+    if moderation_cases.is_empty() {
+        ctx.send(
+            poise::CreateReply::default()
+            .embed(
+                serenity::CreateEmbed::default()
+                .color(branding::color::PRIMARY)
+                .title(format!("Moderation History - {}", member.name))
+                .description(format!("{} has no moderation cases.", member.mention()))
+            )
+        ).await?;
+
+        return Ok(());
+    }
+
+    let mut history_lines = Vec::new();
+
+    for moderation_case in &moderation_cases {
+        let created_at = moderation_case.get_created_at().await;
+        let created_at_timestamp = serenity::Timestamp::from_millis(created_at.timestamp_millis())?;
+        let created_at_relative_format =
+            FormattedTimestamp::new(created_at_timestamp, Some(FormattedTimestampStyle::RelativeTime));
+
+        let reason = moderation_case.get_reason().await;
+        let reason =
+            if reason.chars().count() > MAX_HISTORY_REASON_LENGTH {
+                format!("{}...", reason.chars().take(MAX_HISTORY_REASON_LENGTH).collect::<String>())
+            } else {
+                reason
+            };
+
+        history_lines.push(
+            format!(
+                "**#{}** `{}` by {} {}\n{}",
+                moderation_case.get_case_id().await,
+                moderation_case.get_action().await.name(),
+                moderation_case.get_actor_user_id().await.mention(),
+                created_at_relative_format,
+                create_escaped_code_block(None, &reason),
+            )
+        );
+    }
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title(format!("Moderation History - {}", member.name))
+            .description(history_lines.join("\n"))
+            .footer(
+                serenity::CreateEmbedFooter::new(
+                    format!("Showing up to {} of the most recent cases.", MAX_HISTORY_CASES)
+                )
+            )
+        )
+    ).await?;
+
+    Ok(())
+}
+
+//------------------------------------------------------------//
+
+/// View and manage moderation cases in this guild.
+#[
+    poise::command(
+        slash_command,
+        guild_only,
+        subcommands("view", "edit_reason", "history"),
+        category = "Moderation",
+        install_context = "Guild",
+        interaction_context = "Guild",
+        user_cooldown = "3", // in seconds
+    )
+]
+pub async fn case(
+    _ctx: Context<'_>,
+) -> Result<(), Error> {
+    Ok(())
+}
@@ -17,6 +17,10 @@ use crate::common::helpers::bot::create_escaped_code_block;
 
 use crate::common::moderation;
 
+use crate::common::database::interfaces::guild_config::GuildConfigModerationCapability;
+
+use crate::common::database::interfaces::moderation_case::ModerationCaseAction;
+
 //------------------------------------------------------------//
 
 /// Deafens a member in a voice channel.
@@ -28,7 +32,6 @@ use crate::common::moderation;
         install_context = "Guild",
         interaction_context = "Guild",
         user_cooldown = "5", // in seconds
-        default_member_permissions = "DEAFEN_MEMBERS",
         required_bot_permissions = "DEAFEN_MEMBERS",
     )
 ]
@@ -49,6 +52,14 @@ pub async fn deafen(
         .expect("There should be a member in this context.")
         .clone();
 
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability_with_discord_permissions(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Voice,
+        serenity::Permissions::DEAFEN_MEMBERS,
+    ).await?;
+
     let guild = ctx.guild().expect("There should be a guild in this context.").clone();
 
     let my_id = ctx.serenity_context().cache.current_user().id;
@@ -116,6 +127,16 @@ pub async fn deafen(
         .audit_log_reason(&reason),
     ).await?;
 
+    let moderation_case = moderation::create_moderation_case(
+        &ctx,
+        ModerationCaseAction::Deafen,
+        Some(target_member.user.id),
+        &reason,
+        None,
+    ).await?;
+
+    let case_id = moderation_case.get_case_id().await;
+
     ctx.send(
         poise::CreateReply::default()
         .embed(
@@ -130,6 +151,7 @@ pub async fn deafen(
                     create_escaped_code_block(None, &reason),
                 )
             )
+            .footer(moderation::create_moderation_case_footer(case_id))
         )
     ).await?;
 
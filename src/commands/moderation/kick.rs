@@ -15,7 +15,50 @@ use crate::common::branding;
 
 use crate::common::helpers::bot::create_escaped_code_block;
 
-use crate::common::moderation;
+use crate::common::moderation::{self, ModerationActionContext};
+
+use crate::common::database::interfaces::guild_config::GuildConfigModerationCapability;
+
+use crate::common::database::interfaces::moderation_case::{ModerationCase, ModerationCaseAction};
+
+//------------------------------------------------------------//
+
+/// Kicks a member, notifies them, and records a moderation case.
+pub async fn kick_member(
+    action_ctx: &ModerationActionContext<'_>,
+    target_member: &serenity::Member,
+    reason: &str,
+) -> Result<ModerationCase, Error> {
+    target_member.user.id.dm(
+        action_ctx.http,
+        serenity::CreateMessage::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title(format!("{} - Moderation", action_ctx.guild_name))
+            .description(
+                format!(
+                    "You were kicked from {} by {} for:\n{}",
+                    action_ctx.guild_name,
+                    action_ctx.actor_user_id.mention(),
+                    create_escaped_code_block(None, reason),
+                )
+            )
+        )
+    ).await.ok(); // ignore errors
+
+    target_member.kick(action_ctx.http, Some(reason)).await?;
+
+    let moderation_case = moderation::record_moderation_case(
+        action_ctx,
+        ModerationCaseAction::Kick,
+        Some(target_member.user.id),
+        reason,
+        None,
+    ).await?;
+
+    Ok(moderation_case)
+}
 
 //------------------------------------------------------------//
 
@@ -28,7 +71,6 @@ use crate::common::moderation;
         install_context = "Guild",
         interaction_context = "Guild",
         user_cooldown = "5", // in seconds
-        default_member_permissions = "KICK_MEMBERS",
         required_bot_permissions = "KICK_MEMBERS",
     )
 ]
@@ -49,6 +91,13 @@ pub async fn kick(
         .expect("There should be a member in this context.")
         .clone();
 
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Kick,
+    ).await?;
+
     let guild = ctx.guild().expect("There should be a guild in this context.").clone();
 
     let my_id = ctx.serenity_context().cache.current_user().id;
@@ -77,25 +126,13 @@ pub async fn kick(
 
     let reason = reason.unwrap_or("A reason was not provided.".to_string());
 
-    target_member.user.id.dm(
-        &ctx.http(),
-        serenity::CreateMessage::default()
-        .embed(
-            serenity::CreateEmbed::default()
-            .color(branding::color::PRIMARY)
-            .title(format!("{} - Moderation", guild.name))
-            .description(
-                format!(
-                    "You were kicked from {} by {} for:\n{}",
-                    guild.name,
-                    executing_member.user.mention(),
-                    create_escaped_code_block(None, &reason),
-                )
-            )
-        )
-    ).await.ok(); // ignore errors
+    let moderation_case = kick_member(
+        &ModerationActionContext::from_context(&ctx),
+        &target_member,
+        &reason,
+    ).await?;
 
-    target_member.kick(&ctx.http(), Some(&reason)).await?;
+    let case_id = moderation_case.get_case_id().await;
 
     ctx.send(
         poise::CreateReply::default()
@@ -111,6 +148,7 @@ pub async fn kick(
                     create_escaped_code_block(None, &reason),
                 )
             )
+            .footer(moderation::create_moderation_case_footer(case_id))
         )
     ).await?;
 
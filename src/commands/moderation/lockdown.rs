@@ -0,0 +1,364 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::Context;
+
+use crate::Error;
+
+use crate::common::anti_raid;
+
+use crate::common::branding;
+
+use crate::common::helpers::bot::create_escaped_code_block;
+
+use crate::common::moderation;
+
+use crate::common::database::interfaces::guild_config::{
+    GuildConfig,
+    GuildConfigLockdown,
+    GuildConfigLockedChannel,
+    GuildConfigModerationCapability,
+};
+
+use crate::common::database::interfaces::moderation_case::ModerationCaseAction;
+
+//------------------------------------------------------------//
+
+/// The permissions denied to `@everyone` in each channel during a lockdown.
+const LOCKDOWN_DENIED_PERMISSIONS: serenity::Permissions =
+    serenity::Permissions::SEND_MESSAGES
+    .union(serenity::Permissions::SEND_MESSAGES_IN_THREADS)
+    .union(serenity::Permissions::CREATE_PUBLIC_THREADS)
+    .union(serenity::Permissions::CREATE_PRIVATE_THREADS)
+    .union(serenity::Permissions::ADD_REACTIONS);
+
+//------------------------------------------------------------//
+
+fn is_everyone_overwrite(
+    guild_id: serenity::GuildId,
+    overwrite: &serenity::PermissionOverwrite,
+) -> bool {
+    // Per https://docs.discord.com/developers/topics/permissions#role-object
+    // > "The `@everyone` role has the same ID as the guild it belongs to."
+    matches!(
+        overwrite.kind,
+        serenity::PermissionOverwriteType::Role(role_id) if role_id.get() == guild_id.get()
+    )
+}
+
+/// Denies `@everyone` from talking in every text channel of a guild.
+/// Returns the previous permissions of each locked channel, so that they can be restored later.
+pub async fn lock_guild_channels(
+    http: &serenity::Http,
+    guild_id: serenity::GuildId,
+    reason: &str,
+) -> Result<GuildConfigLockdown, Error> {
+    let guild_channels = guild_id.channels(http).await?;
+
+    let mut locked_channels = Vec::new();
+
+    for mut guild_channel in guild_channels {
+        let is_text_channel = matches!(
+            guild_channel.base.kind,
+            serenity::ChannelType::Text | serenity::ChannelType::News | serenity::ChannelType::Forum
+        );
+
+        if !is_text_channel {
+            continue;
+        }
+
+        let previous_everyone_overwrite =
+            guild_channel.permission_overwrites
+            .iter()
+            .find(|overwrite| is_everyone_overwrite(guild_id, overwrite))
+            .cloned();
+
+        let (previous_allow, previous_deny) = match &previous_everyone_overwrite {
+            Some(overwrite) => (overwrite.allow, overwrite.deny),
+            None => (serenity::Permissions::empty(), serenity::Permissions::empty()),
+        };
+
+        let locked_everyone_overwrite = serenity::PermissionOverwrite {
+            allow: previous_allow - LOCKDOWN_DENIED_PERMISSIONS,
+            deny: previous_deny | LOCKDOWN_DENIED_PERMISSIONS,
+            kind: serenity::PermissionOverwriteType::Role(serenity::RoleId::new(guild_id.get())),
+        };
+
+        let new_overwrites = [
+            guild_channel.permission_overwrites
+            .iter()
+            .filter(|overwrite| !is_everyone_overwrite(guild_id, overwrite))
+            .cloned()
+            .collect::<Vec<serenity::PermissionOverwrite>>(),
+            vec![locked_everyone_overwrite],
+        ].concat();
+
+        let edit_result = guild_channel.edit(
+            http,
+            serenity::EditChannel::default()
+            .permissions(new_overwrites)
+            .audit_log_reason(reason)
+        ).await;
+
+        // a channel that cannot be locked should not prevent locking the others
+        if let Err(why) = edit_result {
+            eprintln!("Failed to lock channel {}: {:?}", guild_channel.id, why);
+
+            continue;
+        }
+
+        locked_channels.push(
+            GuildConfigLockedChannel {
+                channel_id: guild_channel.id,
+                previous_everyone_overwrite: previous_everyone_overwrite,
+            }
+        );
+    }
+
+    Ok(
+        GuildConfigLockdown {
+            started_at: chrono::Utc::now(),
+            locked_channels: locked_channels,
+        }
+    )
+}
+
+/// Restores the `@everyone` permissions of each channel that was locked.
+/// Returns how many channels were restored.
+pub async fn unlock_guild_channels(
+    http: &serenity::Http,
+    guild_id: serenity::GuildId,
+    lockdown: &GuildConfigLockdown,
+    reason: &str,
+) -> Result<usize, Error> {
+    let guild_channels = guild_id.channels(http).await?;
+
+    let mut unlocked_channel_count = 0;
+
+    for mut guild_channel in guild_channels {
+        let Some(locked_channel) =
+            lockdown.locked_channels
+            .iter()
+            .find(|locked_channel| locked_channel.channel_id == guild_channel.id)
+        else {
+            continue;
+        };
+
+        let new_overwrites = [
+            guild_channel.permission_overwrites
+            .iter()
+            .filter(|overwrite| !is_everyone_overwrite(guild_id, overwrite))
+            .cloned()
+            .collect::<Vec<serenity::PermissionOverwrite>>(),
+            locked_channel.previous_everyone_overwrite.iter().cloned().collect(),
+        ].concat();
+
+        let edit_result = guild_channel.edit(
+            http,
+            serenity::EditChannel::default()
+            .permissions(new_overwrites)
+            .audit_log_reason(reason)
+        ).await;
+
+        // a channel that cannot be unlocked should not prevent unlocking the others
+        if let Err(why) = edit_result {
+            eprintln!("Failed to unlock channel {}: {:?}", guild_channel.id, why);
+
+            continue;
+        }
+
+        unlocked_channel_count += 1;
+    }
+
+    Ok(unlocked_channel_count)
+}
+
+//------------------------------------------------------------//
+
+/// Prevents members from talking in every text channel.
+#[
+    poise::command(
+        slash_command,
+        rename = "start",
+    )
+]
+pub async fn start_lockdown(
+    ctx: Context<'_>,
+
+    #[description = "Why this action was performed"]
+    reason: Option<String>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let executing_member =
+        ctx
+        .author_member().await
+        .expect("There should be a member in this context.")
+        .clone();
+
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability_with_discord_permissions(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Channels,
+        serenity::Permissions::MANAGE_CHANNELS | serenity::Permissions::MANAGE_ROLES,
+    ).await?;
+
+    let reason = reason.unwrap_or("A reason was not provided.".to_string());
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    if guild_config.get_lockdown().await.is_some() {
+        return Err("A lockdown is already active, use `/lockdown end` to end it.".into());
+    }
+
+    let lockdown = lock_guild_channels(ctx.http(), guild_id, &reason).await?;
+
+    let locked_channel_count = lockdown.locked_channels.len();
+
+    guild_config.set_lockdown(Some(lockdown)).await?;
+
+    let moderation_case = moderation::create_moderation_case(
+        &ctx,
+        ModerationCaseAction::Lockdown,
+        None,
+        &reason,
+        None,
+    ).await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Moderation")
+            .description(
+                format!(
+                    "Locked {} channels for:\n{}",
+                    locked_channel_count,
+                    create_escaped_code_block(None, &reason),
+                )
+            )
+            .footer(moderation::create_moderation_case_footer(moderation_case.get_case_id().await))
+        )
+    ).await?;
+
+    Ok(())
+}
+
+/// Restores the channel permissions from before the lockdown, and ends raid mode (if active).
+#[
+    poise::command(
+        slash_command,
+        rename = "end",
+    )
+]
+pub async fn end_lockdown(
+    ctx: Context<'_>,
+
+    #[description = "Why this action was performed"]
+    reason: Option<String>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let executing_member =
+        ctx
+        .author_member().await
+        .expect("There should be a member in this context.")
+        .clone();
+
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability_with_discord_permissions(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Channels,
+        serenity::Permissions::MANAGE_CHANNELS | serenity::Permissions::MANAGE_ROLES,
+    ).await?;
+
+    let reason = reason.unwrap_or("A reason was not provided.".to_string());
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let lockdown = guild_config.get_lockdown().await;
+
+    let raid_mode_was_active = anti_raid::end_raid_mode(ctx.http(), guild_id).await?;
+
+    let Some(lockdown) = lockdown else {
+        if raid_mode_was_active {
+            ctx.send(
+                poise::CreateReply::default()
+                .embed(
+                    serenity::CreateEmbed::default()
+                    .color(branding::color::PRIMARY)
+                    .title("Moderation")
+                    .description("Ended raid mode, there was no active lockdown.")
+                )
+            ).await?;
+
+            return Ok(());
+        }
+
+        return Err("There is no active lockdown.".into());
+    };
+
+    let unlocked_channel_count = unlock_guild_channels(ctx.http(), guild_id, &lockdown, &reason).await?;
+
+    guild_config.set_lockdown(None).await?;
+
+    let moderation_case = moderation::create_moderation_case(
+        &ctx,
+        ModerationCaseAction::Unlock,
+        None,
+        &reason,
+        None,
+    ).await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Moderation")
+            .description(
+                format!(
+                    "Unlocked {} channels for:\n{}",
+                    unlocked_channel_count,
+                    create_escaped_code_block(None, &reason),
+                )
+            )
+            .footer(moderation::create_moderation_case_footer(moderation_case.get_case_id().await))
+        )
+    ).await?;
+
+    Ok(())
+}
+
+//------------------------------------------------------------//
+
+/// Locks (or unlocks) every text channel in this guild.
+#[
+    poise::command(
+        slash_command,
+        guild_only,
+        category = "Moderation",
+        install_context = "Guild",
+        interaction_context = "Guild",
+        subcommands("start_lockdown", "end_lockdown"),
+        user_cooldown = "10", // in seconds
+        required_bot_permissions = "MANAGE_CHANNELS | MANAGE_ROLES",
+    )
+]
+pub async fn lockdown(
+    _ctx: Context<'_>,
+) -> Result<(), Error> {
+    Ok(())
+}
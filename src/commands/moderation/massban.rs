@@ -0,0 +1,86 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::Context;
+
+use crate::Error;
+
+use crate::common::moderation;
+
+use crate::common::mass_moderation::{
+    self,
+    MassModerationAction,
+    MassModerationTargetSources,
+};
+
+use crate::common::database::interfaces::guild_config::GuildConfigModerationCapability;
+
+//------------------------------------------------------------//
+
+/// Bans many users at once, such as the members of a raid.
+#[
+    poise::command(
+        slash_command,
+        guild_only,
+        category = "Moderation",
+        install_context = "Guild",
+        interaction_context = "Guild",
+        user_cooldown = "30", // in seconds
+        required_bot_permissions = "BAN_MEMBERS",
+    )
+]
+pub async fn massban(
+    ctx: Context<'_>,
+
+    #[description = "User ids (or mentions) separated by spaces or commas"]
+    user_ids: Option<String>,
+
+    #[description = "A text file of user ids separated by spaces, commas, or new lines"]
+    user_ids_file: Option<serenity::Attachment>,
+
+    #[min = 1]
+    #[max = 1440] // 1 day in minutes
+    #[description = "Also ban every member that joined within this many minutes"]
+    joined_within_minutes: Option<u32>,
+
+    #[min = 0]
+    #[max = 7]
+    #[description = "How many days of their messages to delete (0-7)"]
+    delete_message_days: Option<u8>,
+
+    #[description = "Why this action was performed"]
+    reason: Option<String>,
+) -> Result<(), Error> {
+    let executing_member =
+        ctx
+        .author_member().await
+        .expect("There should be a member in this context.")
+        .clone();
+
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Ban,
+    ).await?;
+
+    let reason = reason.unwrap_or("A reason was not provided.".to_string());
+
+    mass_moderation::perform_mass_moderation_action(
+        &ctx,
+        MassModerationAction::Ban {
+            delete_message_days: delete_message_days.unwrap_or(0),
+        },
+        MassModerationTargetSources {
+            user_ids: user_ids,
+            user_ids_file: user_ids_file,
+            joined_within_minutes: joined_within_minutes,
+        },
+        &reason,
+    ).await
+}
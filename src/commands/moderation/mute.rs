@@ -15,8 +15,46 @@ use crate::common::branding;
 
 use crate::common::helpers::bot::create_escaped_code_block;
 
+use crate::common::helpers::time::{autocomplete_duration, format_duration, parse_duration_within};
+
 use crate::common::moderation;
 
+use crate::common::database::interfaces::guild_config::GuildConfigModerationCapability;
+
+use crate::common::database::interfaces::moderation_case::ModerationCaseAction;
+
+use crate::common::scheduler;
+
+//------------------------------------------------------------//
+
+/// Parses how long a temporary voice mute lasts, between 1 minute and 1 day.
+pub fn parse_voice_mute_duration(
+    value: &str,
+) -> Result<std::time::Duration, Error> {
+    parse_duration_within(
+        value,
+        std::time::Duration::from_secs(60),
+        std::time::Duration::from_secs(24 * 60 * 60),
+    )
+}
+
+/// Schedules a voice muted member to automatically be unmuted after the duration.
+pub async fn schedule_voice_unmute(
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+    duration: chrono::Duration,
+) -> Result<(), Error> {
+    scheduler::schedule_job(
+        scheduler::ScheduledJobPayload::VoiceUnmute {
+            discord_guild_id: guild_id,
+            discord_user_id: user_id,
+            reason: format!("Temporary voice mute of {} has expired.", format_duration(duration.to_std()?)),
+        },
+        chrono::Utc::now() + duration,
+        Some(scheduler::create_voice_unmute_dedupe_key(guild_id, user_id)),
+    ).await
+}
+
 //------------------------------------------------------------//
 
 /// Mutes a member in a voice channel.
@@ -28,7 +66,6 @@ use crate::common::moderation;
         install_context = "Guild",
         interaction_context = "Guild",
         user_cooldown = "5", // in seconds
-        default_member_permissions = "MUTE_MEMBERS",
         required_bot_permissions = "MUTE_MEMBERS",
     )
 ]
@@ -38,6 +75,10 @@ pub async fn mute(
     #[description = "The member to mute"]
     member: serenity::Member,
 
+    #[autocomplete = "autocomplete_duration"]
+    #[description = "How long until the member is automatically unmuted, e.g. `15m` or `2h` (indefinite if omitted)"]
+    duration: Option<String>,
+
     #[description = "Why this action was performed"]
     reason: Option<String>,
 ) -> Result<(), Error> {
@@ -49,6 +90,14 @@ pub async fn mute(
         .expect("There should be a member in this context.")
         .clone();
 
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability_with_discord_permissions(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Voice,
+        serenity::Permissions::MUTE_MEMBERS,
+    ).await?;
+
     let guild = ctx.guild().expect("There should be a guild in this context.").clone();
 
     let my_id = ctx.serenity_context().cache.current_user().id;
@@ -91,6 +140,16 @@ pub async fn mute(
 
     let reason = reason.unwrap_or("A reason was not provided.".to_string());
 
+    let duration = match duration {
+        Some(duration) => Some(chrono::Duration::from_std(parse_voice_mute_duration(&duration)?)?),
+        None => None,
+    };
+
+    let duration_string = match duration {
+        Some(duration) => format!(" for {}", format_duration(duration.to_std()?)),
+        None => String::new(),
+    };
+
     target_member.user.id.dm(
         &ctx.http(),
         serenity::CreateMessage::default()
@@ -100,8 +159,9 @@ pub async fn mute(
             .title(format!("{} - Moderation", guild.name))
             .description(
                 format!(
-                    "You were muted in {} by {} for:\n{}",
+                    "You were muted in {}{} by {} for:\n{}",
                     guild.name,
+                    duration_string,
                     executing_member.user.mention(),
                     create_escaped_code_block(None, &reason),
                 )
@@ -116,6 +176,23 @@ pub async fn mute(
         .audit_log_reason(&reason),
     ).await?;
 
+    if let Some(duration) = duration {
+        schedule_voice_unmute(guild.id, target_member.user.id, duration).await?;
+    } else {
+        // an indefinite mute supersedes any scheduled unmute
+        scheduler::cancel_job(&scheduler::create_voice_unmute_dedupe_key(guild.id, target_member.user.id)).await?;
+    }
+
+    let moderation_case = moderation::create_moderation_case(
+        &ctx,
+        ModerationCaseAction::Mute,
+        Some(target_member.user.id),
+        &reason,
+        duration.map(|duration| duration.num_seconds() as u32),
+    ).await?;
+
+    let case_id = moderation_case.get_case_id().await;
+
     ctx.send(
         poise::CreateReply::default()
         .embed(
@@ -124,12 +201,14 @@ pub async fn mute(
             .title("Moderation")
             .description(
                 format!(
-                    "{} was muted by {} for:\n{}",
+                    "{} was muted{} by {} for:\n{}",
                     target_member.user.mention(),
+                    duration_string,
                     executing_member.user.mention(),
                     create_escaped_code_block(None, &reason),
                 )
             )
+            .footer(moderation::create_moderation_case_footer(case_id))
         )
     ).await?;
 
@@ -2,6 +2,12 @@
 //                   Copyright (c) MidSpike                   //
 //------------------------------------------------------------//
 
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use serde::Serialize;
+
 use poise::serenity_prelude::Mentionable;
 use poise::serenity_prelude::{self as serenity};
 
@@ -15,68 +21,391 @@ use crate::common::branding;
 
 use crate::common::helpers::bot::create_escaped_code_block;
 
+use crate::common::helpers::html_rendering::escape_html;
+
+use crate::common::moderation;
+
+use crate::common::database::interfaces::guild_config::{
+    GuildConfig,
+    GuildConfigLoggingChannelKind,
+    GuildConfigModerationCapability,
+};
+
+use crate::common::database::interfaces::moderation_case::ModerationCaseAction;
+
 //------------------------------------------------------------//
 
 const MAX_PURGE_AMOUNT: u32 = 1000;
 
+/// The maximum amount of messages to look through when filtering, to bound the amount of requests.
+const MAX_PURGE_SCAN_AMOUNT: u32 = 5000;
+
 const DISCORD_BULK_DELETE_AMOUNT: u8 = 100;
 
+/// Discord's bulk delete rejects messages older than this.
+const DISCORD_BULK_DELETE_MAX_AGE_SECONDS: i64 = 14 * 24 * 60 * 60;
+
 //------------------------------------------------------------//
 
-async fn remove_messages_from_channel(
+static LINK_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"https?://\S+").expect("link regex should be valid")
+});
+
+static INVITE_LINK_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(discord\.gg|discord(app)?\.com/invite)/[\w-]+").expect("invite link regex should be valid")
+});
+
+//------------------------------------------------------------//
+
+#[derive(poise::ChoiceParameter, Clone, Copy)]
+enum PurgeContentFilter {
+    #[name = "Only messages from bots"]
+    Bots,
+
+    #[name = "Only messages containing links"]
+    Links,
+
+    #[name = "Only messages containing attachments"]
+    Attachments,
+
+    #[name = "Only messages containing invites"]
+    Invites,
+}
+
+/// Every specified criterion must match for a message to be purged.
+struct PurgeCriteria {
+    from_user_id: Option<serenity::UserId>,
+    content_filter: Option<PurgeContentFilter>,
+    pattern: Option<Regex>,
+}
+
+impl PurgeCriteria {
+    fn matches(
+        &self,
+        message: &serenity::Message,
+    ) -> bool {
+        if let Some(from_user_id) = self.from_user_id {
+            if message.author.id != from_user_id {
+                return false;
+            }
+        }
+
+        if let Some(content_filter) = self.content_filter {
+            let is_match = match content_filter {
+                PurgeContentFilter::Bots => message.author.bot(),
+                PurgeContentFilter::Links => LINK_REGEX.is_match(&message.content),
+                PurgeContentFilter::Attachments => !message.attachments.is_empty(),
+                PurgeContentFilter::Invites => INVITE_LINK_REGEX.is_match(&message.content),
+            };
+
+            if !is_match {
+                return false;
+            }
+        }
+
+        if let Some(pattern) = &self.pattern {
+            if !pattern.is_match(&message.content) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+//------------------------------------------------------------//
+
+/// Finds up to `total_amount` messages matching the criteria, newest first.
+/// Only messages before `before_message_id` (and after `after_message_id`, if specified) are considered.
+async fn find_messages_to_purge(
     ctx: &Context<'_>,
     channel_id: serenity::GenericChannelId,
     total_amount: u32,
     before_message_id: serenity::MessageId,
-    reason: &str,
-) -> Result<(), Error> {
-    if total_amount > MAX_PURGE_AMOUNT {
-        return Err("Purging more than 1000 messages at once is not allowed.".into());
-    }
+    after_message_id: Option<serenity::MessageId>,
+    criteria: &PurgeCriteria,
+) -> Result<Vec<serenity::Message>, Error> {
+    let mut messages_to_purge = Vec::new();
 
-    // The amount of messages to purge in each batch.
-    // Example:
-    // Total amount: 250
-    // Amount of batches: 3
-    // purge_batches: [100, 100, 50]
-    let purge_batches: Vec<u32> =
-        (0..total_amount)
-        .step_by(DISCORD_BULK_DELETE_AMOUNT as usize)
-        .map(|step_amount| {
-            // If this is the last batch, purge the remaining amount of messages.
-            if step_amount + (DISCORD_BULK_DELETE_AMOUNT as u32) > total_amount {
-                total_amount - step_amount
-            } else {
-                DISCORD_BULK_DELETE_AMOUNT as u32
-            }
-        })
-        .collect();
+    let mut cursor_message_id = before_message_id;
+    let mut scanned_amount: u32 = 0;
 
-    for expected_amount in purge_batches {
-        let messages_to_purge = channel_id.messages(
+    'scanning: while scanned_amount < MAX_PURGE_SCAN_AMOUNT {
+        let messages = channel_id.messages(
             &ctx,
             serenity::GetMessages::default()
-            .limit(expected_amount as u8)
-            .before(before_message_id)
+            .limit(DISCORD_BULK_DELETE_AMOUNT)
+            .before(cursor_message_id)
         ).await?;
 
+        let Some(oldest_message) = messages.last() else {
+            break; // the start of the channel was reached
+        };
+
+        cursor_message_id = oldest_message.id;
+
+        for message in messages {
+            scanned_amount += 1;
+
+            if after_message_id.is_some_and(|after_message_id| message.id <= after_message_id) {
+                break 'scanning;
+            }
+
+            if criteria.matches(&message) {
+                messages_to_purge.push(message);
+            }
+
+            if messages_to_purge.len() as u32 >= total_amount {
+                break 'scanning;
+            }
+        }
+
+        // throttle our requests to avoid hitting the rate limit
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+
+    Ok(messages_to_purge)
+}
+
+/// The messages that were removed by a purge, and why it stopped early (if it did).
+struct PurgeRemoval {
+    removed_messages: Vec<serenity::Message>,
+    error: Option<Error>,
+}
+
+/// Removes the specified messages, returning the messages that were actually removed.
+/// Messages that are too old to be bulk deleted are deleted individually instead.
+async fn remove_messages_from_channel(
+    ctx: &Context<'_>,
+    channel_id: serenity::GenericChannelId,
+    messages_to_purge: Vec<serenity::Message>,
+    reason: &str,
+) -> PurgeRemoval {
+    if messages_to_purge.len() as u32 > MAX_PURGE_AMOUNT {
+        return PurgeRemoval {
+            removed_messages: Vec::new(),
+            error: Some("Purging more than 1000 messages at once is not allowed.".into()),
+        };
+    }
+
+    let bulk_delete_cutoff = chrono::Utc::now().timestamp() - DISCORD_BULK_DELETE_MAX_AGE_SECONDS;
+
+    let (recent_messages, old_messages): (Vec<serenity::Message>, Vec<serenity::Message>) =
+        messages_to_purge
+        .into_iter()
+        .partition(|message| message.id.created_at().unix_timestamp() > bulk_delete_cutoff);
+
+    let mut removed_messages = Vec::new();
+
+    for batch in recent_messages.chunks(DISCORD_BULK_DELETE_AMOUNT as usize) {
         let message_ids: Vec<serenity::MessageId> =
-            messages_to_purge
+            batch
             .iter()
             .map(|message| message.id)
             .collect();
 
-        channel_id.delete_messages(&ctx.http(), &message_ids, Some(reason)).await?;
+        // bulk deletes require at least two messages
+        let delete_result =
+            if let [message_id] = message_ids.as_slice() {
+                channel_id.delete_message(&ctx.http(), *message_id, Some(reason)).await
+            } else {
+                channel_id.delete_messages(&ctx.http(), &message_ids, Some(reason)).await
+            };
+
+        // the messages that were already removed are still reported
+        if let Err(why) = delete_result {
+            return PurgeRemoval {
+                removed_messages: removed_messages,
+                error: Some(why.into()),
+            };
+        }
+
+        removed_messages.extend(batch.iter().cloned());
 
         // throttle our requests to avoid hitting the rate limit
         tokio::time::sleep(std::time::Duration::from_millis(500)).await;
     }
 
+    for message in old_messages {
+        // the message may have already been deleted by someone else, which is not fatal
+        match channel_id.delete_message(&ctx.http(), message.id, Some(reason)).await {
+            Ok(_) => removed_messages.push(message),
+            Err(why) => eprintln!("Failed to delete message {} while purging: {:?}", message.id, why),
+        }
+
+        // throttle our requests to avoid hitting the rate limit
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+
+    PurgeRemoval {
+        removed_messages: removed_messages,
+        error: None,
+    }
+}
+
+//------------------------------------------------------------//
+
+#[derive(poise::ChoiceParameter, Clone, Copy)]
+enum PurgeTranscriptFormat {
+    #[name = "HTML"]
+    Html,
+
+    #[name = "JSON"]
+    Json,
+
+    #[name = "HTML and JSON"]
+    HtmlAndJson,
+}
+
+#[derive(Serialize)]
+struct PurgeTranscriptMessage {
+    message_id: serenity::MessageId,
+    author_id: serenity::UserId,
+    author_name: String,
+    content: String,
+    attachment_urls: Vec<String>,
+    created_at: String,
+}
+
+impl PurgeTranscriptMessage {
+    fn from_message(
+        message: &serenity::Message,
+    ) -> Self {
+        Self {
+            message_id: message.id,
+            author_id: message.author.id,
+            author_name: message.author.name.to_string(),
+            content: message.content.to_string(),
+            attachment_urls: message.attachments.iter().map(|attachment| attachment.url.to_string()).collect(),
+            created_at: message.timestamp.to_string(),
+        }
+    }
+}
+
+fn generate_purge_transcript_message_html(
+    transcript_message: &PurgeTranscriptMessage,
+) -> String {
+    include_str!("../../extras/html/fragment/purge_transcript_message.html")
+    .replace("{author_name}", &escape_html(transcript_message.author_name.clone()))
+    .replace("{author_id}", &transcript_message.author_id.to_string())
+    .replace("{timestamp}", &escape_html(transcript_message.created_at.clone()))
+    .replace("{content}", &escape_html(transcript_message.content.clone()))
+    .replace("{attachments}", &escape_html(transcript_message.attachment_urls.join("\n")))
+}
+
+fn generate_purge_transcript_html(
+    channel_name: &str,
+    transcript_messages: &[PurgeTranscriptMessage],
+) -> String {
+    let transcript_messages_html =
+        transcript_messages
+        .iter()
+        .map(generate_purge_transcript_message_html)
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    include_str!("../../extras/html/pages/purge_transcript.html")
+    .replace("{channel_name}", &escape_html(channel_name.to_string()))
+    .replace("{message_count}", &transcript_messages.len().to_string())
+    .replace("{generated_at}", &chrono::Utc::now().to_rfc3339())
+    .replace("{transcript_messages}", &transcript_messages_html)
+}
+
+/// Uploads a transcript of the messages to the moderation actions logging channel.
+/// This is done before the messages are removed, so that nothing is lost if the upload fails.
+/// Finds the channel that transcripts are archived to, before any messages are scanned.
+async fn find_purge_transcript_channel_id(
+    ctx: &Context<'_>,
+) -> Result<serenity::GenericChannelId, Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let Some(logging_channel_id) = guild_config.get_logging_channels().await.get(GuildConfigLoggingChannelKind::ModerationActions) else {
+        return Err("A moderation actions logging channel must be set to archive transcripts; see `/setup logging_channels set`.".into());
+    };
+
+    Ok(logging_channel_id)
+}
+
+async fn archive_purge_transcript(
+    ctx: &Context<'_>,
+    logging_channel_id: serenity::GenericChannelId,
+    messages_to_purge: &[serenity::Message],
+    transcript_format: PurgeTranscriptFormat,
+    reason: &str,
+) -> Result<(), Error> {
+
+    let channel_name =
+        ctx.channel().await
+        .map(|channel| channel.guild()).flatten()
+        .map(|guild_channel| guild_channel.base.name.to_string())
+        .unwrap_or(ctx.channel_id().to_string());
+
+    // transcripts read from oldest to newest
+    let transcript_messages: Vec<PurgeTranscriptMessage> =
+        messages_to_purge
+        .iter()
+        .rev()
+        .map(PurgeTranscriptMessage::from_message)
+        .collect();
+
+    let unix_epoch = chrono::Utc::now().timestamp();
+
+    let mut attachments = Vec::new();
+
+    if matches!(transcript_format, PurgeTranscriptFormat::Html | PurgeTranscriptFormat::HtmlAndJson) {
+        let transcript_html = generate_purge_transcript_html(&channel_name, &transcript_messages);
+
+        attachments.push(
+            serenity::CreateAttachment::bytes(transcript_html.into_bytes(), format!("purge_transcript_{}.html", unix_epoch))
+        );
+    }
+
+    if matches!(transcript_format, PurgeTranscriptFormat::Json | PurgeTranscriptFormat::HtmlAndJson) {
+        let transcript_json = serde_json::to_string_pretty(&transcript_messages)?;
+
+        attachments.push(
+            serenity::CreateAttachment::bytes(transcript_json.into_bytes(), format!("purge_transcript_{}.json", unix_epoch))
+        );
+    }
+
+    logging_channel_id.send_message(
+        ctx.http(),
+        serenity::CreateMessage::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Moderation - Purge Transcript")
+            .description(
+                format!(
+                    "Transcript of {} messages being purged from {} by {} for:\n{}",
+                    transcript_messages.len(),
+                    ctx.channel_id().mention(),
+                    ctx.author().mention(),
+                    create_escaped_code_block(None, reason),
+                )
+            )
+        )
+        .add_files(attachments)
+    ).await?;
+
     Ok(())
 }
 
 //------------------------------------------------------------//
 
+fn parse_message_id(
+    message_id: &str,
+) -> Result<serenity::MessageId, Error> {
+    match message_id.trim().parse::<u64>() {
+        Ok(message_id) if message_id > 0 => Ok(serenity::MessageId::new(message_id)),
+        _ => Err(format!("`{}` is not a valid message id.", message_id).into()),
+    }
+}
+
+//------------------------------------------------------------//
+
 /// Purges messages from a channel.
 #[
     poise::command(
@@ -86,7 +415,6 @@ async fn remove_messages_from_channel(
         install_context = "Guild",
         interaction_context = "Guild",
         user_cooldown = "10", // in seconds
-        default_member_permissions = "MANAGE_MESSAGES",
         required_bot_permissions = "MANAGE_MESSAGES",
     )
 ]
@@ -98,6 +426,24 @@ pub async fn purge(
     #[description = "Amount of messages to remove (1-1000)"]
     amount_of_messages: u32,
 
+    #[description = "Only remove messages from this user"]
+    from_user: Option<serenity::User>,
+
+    #[description = "Only remove messages of this kind"]
+    filter: Option<PurgeContentFilter>,
+
+    #[description = "Only remove messages matching this regular expression"]
+    pattern: Option<String>,
+
+    #[description = "Only remove messages sent after this message id"]
+    after_message_id: Option<String>,
+
+    #[description = "Only remove messages sent before this message id"]
+    before_message_id: Option<String>,
+
+    #[description = "Archive a transcript to the moderation actions logging channel before removing"]
+    transcript: Option<PurgeTranscriptFormat>,
+
     #[description = "Why this action was performed"]
     reason: Option<String>,
 ) -> Result<(), Error> {
@@ -107,10 +453,39 @@ pub async fn purge(
         .expect("There should be a member in this context.")
         .clone();
 
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Purge,
+    ).await?;
+
     let _guild = ctx.guild().expect("There should be a guild in this context.").clone();
 
     let reason = reason.unwrap_or("A reason was not provided.".to_string());
 
+    let pattern = match pattern {
+        Some(pattern) => match Regex::new(&pattern) {
+            Ok(pattern) => Some(pattern),
+            Err(_) => return Err("The specified pattern is not a valid regular expression.".into()),
+        },
+        None => None,
+    };
+
+    let after_message_id = after_message_id.as_deref().map(parse_message_id).transpose()?;
+    let before_message_id = before_message_id.as_deref().map(parse_message_id).transpose()?;
+
+    let transcript = match transcript {
+        Some(transcript_format) => Some((transcript_format, find_purge_transcript_channel_id(&ctx).await?)),
+        None => None,
+    };
+
+    let criteria = PurgeCriteria {
+        from_user_id: from_user.as_ref().map(|user| user.id),
+        content_filter: filter,
+        pattern: pattern,
+    };
+
     let reply_handle = ctx.send(
         poise::CreateReply::default()
         .embed(
@@ -119,7 +494,7 @@ pub async fn purge(
             .title("Moderation")
             .description(
                 format!(
-                    "{} is purging {} messages for:\n{}",
+                    "{} is purging up to {} messages for:\n{}",
                     executing_member.user.mention(),
                     amount_of_messages,
                     create_escaped_code_block(None, &reason),
@@ -128,56 +503,101 @@ pub async fn purge(
         )
     ).await?;
 
-    let before_message_id = reply_handle.message().await?.id;
+    // the reply itself should never be purged
+    let before_message_id = before_message_id.unwrap_or(reply_handle.message().await?.id);
 
-    let removal_result = remove_messages_from_channel(
-        &ctx,
-        ctx.channel_id(),
-        amount_of_messages,
-        before_message_id,
-        &reason,
-    ).await;
-
-    if let Err(why) = removal_result {
-        println!("Failed to purge messages: {:?}", why);
-
-        reply_handle.edit(
-            poise::Context::Application(ctx),
-            poise::CreateReply::default()
-            .embed(
-                serenity::CreateEmbed::default()
-                .color(0xFF0000)
-                .title("Moderation")
-                .description(
-                    format!(
-                        "{} failed to purge {} messages for:\n{}",
-                        executing_member.user.mention(),
-                        amount_of_messages,
-                        create_escaped_code_block(None, &reason),
+    let removal_result = async {
+        let messages_to_purge = find_messages_to_purge(
+            &ctx,
+            ctx.channel_id(),
+            amount_of_messages,
+            before_message_id,
+            after_message_id,
+            &criteria,
+        ).await?;
+
+        if let Some((transcript_format, logging_channel_id)) = transcript {
+            archive_purge_transcript(&ctx, logging_channel_id, &messages_to_purge, transcript_format, &reason).await?;
+        }
+
+        Ok::<PurgeRemoval, Error>(
+            remove_messages_from_channel(
+                &ctx,
+                ctx.channel_id(),
+                messages_to_purge,
+                &reason,
+            ).await
+        )
+    }.await;
+
+    let PurgeRemoval { removed_messages, error: removal_error } = match removal_result {
+        Ok(purge_removal) => purge_removal,
+        Err(why) => PurgeRemoval {
+            removed_messages: Vec::new(),
+            error: Some(why),
+        },
+    };
+
+    if let Some(why) = &removal_error {
+        eprintln!("Failed to purge messages: {:?}", why);
+
+        if removed_messages.is_empty() {
+            reply_handle.edit(
+                poise::Context::Application(ctx),
+                poise::CreateReply::default()
+                .embed(
+                    serenity::CreateEmbed::default()
+                    .color(0xFF0000)
+                    .title("Moderation")
+                    .description(
+                        format!(
+                            "{} failed to purge {} messages for:\n{}",
+                            executing_member.user.mention(),
+                            amount_of_messages,
+                            create_escaped_code_block(None, &reason),
+                        )
                     )
+                    .field("Error", why.to_string(), false)
                 )
+            ).await?;
+
+            return Ok(()); // stop here
+        }
+    }
+
+    let moderation_case = moderation::create_moderation_case(
+        &ctx,
+        ModerationCaseAction::Purge,
+        from_user.as_ref().map(|user| user.id),
+        &reason,
+        None,
+    ).await?;
+
+    let case_id = moderation_case.get_case_id().await;
+
+    let mut summary_embed =
+        serenity::CreateEmbed::default()
+        .color(branding::color::PRIMARY)
+        .title("Moderation")
+        .description(
+            format!(
+                "{} purged {} messages{} for:\n{}",
+                executing_member.user.mention(),
+                removed_messages.len(),
+                if removal_error.is_some() { ", but failed to purge the rest," } else { "" },
+                create_escaped_code_block(None, &reason),
             )
-        ).await?;
+        )
+        .footer(moderation::create_moderation_case_footer(case_id));
 
-        return Ok(()); // stop here
+    if let Some(why) = &removal_error {
+        summary_embed = summary_embed.field("Error", why.to_string(), false);
     }
 
     reply_handle.edit(
         poise::Context::Application(ctx),
         poise::CreateReply::default()
-        .embed(
-            serenity::CreateEmbed::default()
-            .color(branding::color::PRIMARY)
-            .title("Moderation")
-            .description(
-                format!(
-                    "{} purged {} messages for:\n{}",
-                    executing_member.user.mention(),
-                    amount_of_messages,
-                    create_escaped_code_block(None, &reason),
-                )
-            )
-        )
+        .embed(summary_embed)
     ).await?;
 
     Ok(())
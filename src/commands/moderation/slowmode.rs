@@ -16,6 +16,33 @@ use crate::common::branding;
 
 use crate::common::helpers::bot::create_escaped_code_block;
 
+use crate::common::helpers::time::{autocomplete_duration, format_duration, parse_duration_within};
+
+use crate::common::moderation;
+
+use crate::common::database::interfaces::guild_config::GuildConfigModerationCapability;
+
+use crate::common::database::interfaces::moderation_case::ModerationCaseAction;
+
+//------------------------------------------------------------//
+
+/// Sets the slowmode (in seconds) of a channel, `0` disables it.
+pub async fn set_channel_slowmode(
+    http: &serenity::Http,
+    guild_channel: &mut serenity::GuildChannel,
+    duration: u16,
+    reason: &str,
+) -> Result<(), Error> {
+    guild_channel.edit(
+        http,
+        serenity::EditChannel::default()
+        .rate_limit_per_user(NonMaxU16::new(duration).unwrap_or(NonMaxU16::MAX))
+        .audit_log_reason(reason)
+    ).await?;
+
+    Ok(())
+}
+
 //------------------------------------------------------------//
 
 /// Configures slowmode for the current channel.
@@ -27,17 +54,15 @@ use crate::common::helpers::bot::create_escaped_code_block;
         install_context = "Guild",
         interaction_context = "Guild",
         user_cooldown = "10", // in seconds
-        default_member_permissions = "MANAGE_CHANNELS",
         required_bot_permissions = "MANAGE_CHANNELS",
     )
 ]
 pub async fn slowmode(
     ctx: Context<'_>,
 
-    #[min = 0]
-    #[max = 21600] // 6 hours in seconds
-    #[description = "Duration in seconds (`0` to disable)"]
-    duration: u16,
+    #[autocomplete = "autocomplete_duration"]
+    #[description = "Duration between messages, e.g. `30s` or `5m` (`0` to disable, max 6 hours)"]
+    duration: String,
 
     #[description = "Why this action was performed"]
     reason: Option<String>,
@@ -50,20 +75,30 @@ pub async fn slowmode(
         .expect("There should be a member in this context.")
         .clone();
 
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Channels,
+    ).await?;
+
     let _guild = ctx.guild().expect("There should be a guild in this context.").clone();
 
     let mut guild_channel =
         ctx.channel().await.map(|c| c.guild()).flatten()
         .expect("This channel should be in a guild.");
 
+    let duration = parse_duration_within(
+        &duration,
+        std::time::Duration::ZERO,
+        std::time::Duration::from_secs(6 * 60 * 60), // 6 hours is the maximum slowmode that discord allows
+    )?;
+
+    let duration_seconds = duration.as_secs() as u16;
+
     let reason = reason.unwrap_or("A reason was not provided.".to_string());
 
-    let edit_result = guild_channel.edit(
-        &ctx.http(),
-        serenity::EditChannel::default()
-        .rate_limit_per_user(NonMaxU16::new(duration).unwrap_or(NonMaxU16::MAX))
-        .audit_log_reason(&reason)
-    ).await;
+    let edit_result = set_channel_slowmode(ctx.http(), &mut guild_channel, duration_seconds, &reason).await;
 
     let embed = match edit_result {
         Err(why) => {
@@ -77,7 +112,17 @@ pub async fn slowmode(
             )
         },
         Ok(_) => {
-            if duration == 0 {
+            let moderation_case = moderation::create_moderation_case(
+                &ctx,
+                ModerationCaseAction::Slowmode,
+                None,
+                &reason,
+                Some(duration_seconds as u32),
+            ).await?;
+
+            let case_footer = moderation::create_moderation_case_footer(moderation_case.get_case_id().await);
+
+            if duration_seconds == 0 {
                 // slow mode disabled
                 serenity::CreateEmbed::default()
                 .color(branding::color::PRIMARY)
@@ -90,6 +135,7 @@ pub async fn slowmode(
                         create_escaped_code_block(None, &reason),
                     )
                 )
+                .footer(case_footer)
             } else {
                 // slow mode enabled with duration
                 serenity::CreateEmbed::default()
@@ -97,13 +143,14 @@ pub async fn slowmode(
                 .title("Moderation")
                 .description(
                     format!(
-                        "{} enabled slow mode of {} seconds in {} for:\n{}",
+                        "{} enabled slow mode of {} in {} for:\n{}",
                         executing_member.user.mention(),
-                        duration,
+                        format_duration(duration),
                         ctx.channel_id().mention(),
                         create_escaped_code_block(None, &reason),
                     )
                 )
+                .footer(case_footer)
             }
         },
     };
@@ -15,7 +15,87 @@ use crate::common::branding;
 
 use crate::common::helpers::bot::create_escaped_code_block;
 
-use crate::common::moderation;
+use crate::common::helpers::time::{autocomplete_duration, format_duration, parse_duration_within};
+
+use crate::common::moderation::{self, ModerationActionContext};
+
+use crate::common::moderation_appeals;
+
+use crate::common::database::interfaces::guild_config::GuildConfigModerationCapability;
+
+use crate::common::database::interfaces::moderation_case::{ModerationCase, ModerationCaseAction};
+
+//------------------------------------------------------------//
+
+/// Puts a member in timeout, notifies them, and records a moderation case.
+/// The duration is clamped to what discord allows (1 minute to 28 days).
+pub async fn timeout_member(
+    action_ctx: &ModerationActionContext<'_>,
+    target_member: &mut serenity::Member,
+    duration: chrono::Duration,
+    reason: &str,
+) -> Result<ModerationCase, Error> {
+    let max_hours = 28 * 24; // 28 days is the maximum duration that discord allows
+
+    let chrono_now = chrono::Utc::now();
+
+    let chrono_min_duration = chrono::Duration::try_minutes(1).expect("this should not fail");
+    let chrono_max_duration = chrono::Duration::try_hours(max_hours).expect("this should not fail");
+
+    // Ensure timeout duration is acceptable by discord
+    let chrono_duration =
+        duration
+        .min(chrono_max_duration)
+        .max(chrono_min_duration);
+
+    let chrono_until = chrono_now + chrono_duration;
+
+    let serenity_until_timestamp =
+        serenity::Timestamp::from_millis(chrono_until.timestamp_millis())?;
+
+    let duration_string = format_duration(chrono_duration.to_std()?);
+
+    let notification_message = target_member.user.id.dm(
+        action_ctx.http,
+        serenity::CreateMessage::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title(format!("{} - Moderation", action_ctx.guild_name))
+            .description(
+                format!(
+                    "You were timed out for {} in {} by {} for:\n{}",
+                    duration_string,
+                    action_ctx.guild_name,
+                    action_ctx.actor_user_id.mention(),
+                    create_escaped_code_block(None, reason),
+                )
+            )
+        )
+    ).await.ok(); // ignore errors
+
+    target_member.edit(
+        action_ctx.http,
+        serenity::EditMember::default()
+        .disable_communication_until(serenity_until_timestamp)
+        .audit_log_reason(reason),
+    ).await?;
+
+    let moderation_case = moderation::record_moderation_case(
+        action_ctx,
+        ModerationCaseAction::Timeout,
+        Some(target_member.user.id),
+        reason,
+        Some(chrono_duration.num_seconds() as u32),
+    ).await?;
+
+    // the action has already been performed, so failing to offer an appeal should not fail the action
+    if let Err(why) = moderation_appeals::add_appeal_button_to_notification(action_ctx.http, notification_message, &moderation_case).await {
+        eprintln!("Failed to add an appeal button to a moderation notification: {:?}", why);
+    }
+
+    Ok(moderation_case)
+}
 
 //------------------------------------------------------------//
 
@@ -28,7 +108,6 @@ use crate::common::moderation;
         install_context = "Guild",
         interaction_context = "Guild",
         user_cooldown = "5", // in seconds
-        default_member_permissions = "MODERATE_MEMBERS",
         required_bot_permissions = "MODERATE_MEMBERS",
     )
 ]
@@ -38,10 +117,9 @@ pub async fn timeout(
     #[description = "The member to timeout"]
     member: serenity::Member,
 
-    #[min = 1]
-    #[max = 672]
-    #[description = "How many hours (max 28 days) to timeout"]
-    hours: i64,
+    #[autocomplete = "autocomplete_duration"]
+    #[description = "How long to timeout, e.g. `15m`, `2h30m`, or `1d` (max 28 days)"]
+    duration: String,
 
     #[description = "Why this action was performed"]
     reason: Option<String>,
@@ -54,6 +132,13 @@ pub async fn timeout(
         .expect("There should be a member in this context.")
         .clone();
 
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Timeout,
+    ).await?;
+
     let guild = ctx.guild().expect("There should be a guild in this context.").clone();
 
     let my_id = ctx.serenity_context().cache.current_user().id;
@@ -80,53 +165,25 @@ pub async fn timeout(
         "I cannot perform an action on a member that has an equal or higher role than me.",
     ).await?;
 
-    let reason = reason.unwrap_or("A reason was not provided.".to_string());
-
-    let max_hours = 28 * 24; // 28 days is the maximum duration that discord allows
-
-    let hours = hours.min(max_hours).max(1); // Ensure timeout duration is acceptable by discord
+    let duration = parse_duration_within(
+        &duration,
+        std::time::Duration::from_secs(60), // 1 minute is the minimum duration that discord allows
+        std::time::Duration::from_secs(28 * 24 * 60 * 60), // 28 days is the maximum duration that discord allows
+    )?;
 
-    let chrono_now = chrono::Utc::now();
-
-    let chrono_min_duration = chrono::Duration::try_minutes(1).expect("this should not fail");
-    let chrono_max_duration = chrono::Duration::try_hours(max_hours).expect("this should not fail");
-
-    let chrono_duration =
-        chrono::Duration::try_hours(hours)
-        .expect("this should not fail")
-        .min(chrono_max_duration)
-        .max(chrono_min_duration);
-
-    let chrono_until = chrono_now + chrono_duration;
+    let reason = reason.unwrap_or("A reason was not provided.".to_string());
 
-    let serenity_until_timestamp =
-        serenity::Timestamp::from_millis(chrono_until.timestamp_millis())?;
+    let moderation_case = timeout_member(
+        &ModerationActionContext::from_context(&ctx),
+        &mut target_member,
+        chrono::Duration::from_std(duration)?,
+        &reason,
+    ).await?;
 
-    target_member.user.id.dm(
-        &ctx.http(),
-        serenity::CreateMessage::default()
-        .embed(
-            serenity::CreateEmbed::default()
-            .color(branding::color::PRIMARY)
-            .title(format!("{} - Moderation", guild.name))
-            .description(
-                format!(
-                    "You were timed out for {} hours in {} by {} for:\n{}",
-                    hours,
-                    guild.name,
-                    executing_member.user.mention(),
-                    create_escaped_code_block(None, &reason),
-                )
-            )
-        )
-    ).await.ok(); // ignore errors
+    let case_id = moderation_case.get_case_id().await;
 
-    target_member.edit(
-        &ctx.http(),
-        serenity::EditMember::default()
-        .disable_communication_until(serenity_until_timestamp)
-        .audit_log_reason(&reason),
-    ).await?;
+    let duration_seconds = moderation_case.get_duration_seconds().await.unwrap_or(0);
+    let duration_string = format_duration(std::time::Duration::from_secs(duration_seconds as u64));
 
     ctx.send(
         poise::CreateReply::default()
@@ -136,13 +193,14 @@ pub async fn timeout(
             .title("Moderation")
             .description(
                 format!(
-                    "{} was timed out for {} hours by {} for:\n{}",
+                    "{} was timed out for {} by {} for:\n{}",
                     target_member.user.mention(),
-                    hours,
+                    duration_string,
                     executing_member.user.mention(),
                     create_escaped_code_block(None, &reason),
                 )
             )
+            .footer(moderation::create_moderation_case_footer(case_id))
         )
     ).await?;
 
@@ -17,6 +17,47 @@ use crate::common::helpers::bot::create_escaped_code_block;
 
 use crate::common::moderation;
 
+use crate::common::database::interfaces::guild_config::GuildConfigModerationCapability;
+
+use crate::common::database::interfaces::moderation_case::ModerationCaseAction;
+
+use crate::common::scheduler;
+
+//------------------------------------------------------------//
+
+/// Unbans a user from a guild and notifies them.
+/// This does not require a command context, so it can also be used by scheduled unbans.
+pub async fn unban_user(
+    http: &serenity::Http,
+    guild_id: serenity::GuildId,
+    guild_name: &str,
+    user_id: serenity::UserId,
+    executing_user_id: serenity::UserId,
+    reason: &str,
+) -> Result<(), Error> {
+    guild_id.unban(http, user_id, Some(reason)).await?;
+
+    user_id.dm(
+        http,
+        serenity::CreateMessage::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title(format!("{} - Moderation", guild_name))
+            .description(
+                format!(
+                    "You were unbanned in {} by {} for:\n{}",
+                    guild_name,
+                    executing_user_id.mention(),
+                    create_escaped_code_block(None, reason),
+                )
+            )
+        )
+    ).await.ok(); // ignore errors
+
+    Ok(())
+}
+
 //------------------------------------------------------------//
 
 /// Unbans a user from the server.
@@ -28,20 +69,20 @@ use crate::common::moderation;
         install_context = "Guild",
         interaction_context = "Guild",
         user_cooldown = "5", // in seconds
-        default_member_permissions = "BAN_MEMBERS",
         required_bot_permissions = "BAN_MEMBERS",
     )
 ]
 pub async fn unban(
     ctx: Context<'_>,
 
-    #[description = "The member to unban"]
-    member: serenity::Member,
+    // Banned users are no longer members, so a user is expected here.
+    #[description = "The user to unban"]
+    user: serenity::User,
 
     #[description = "Why this action was performed"]
     reason: Option<String>,
 ) -> Result<(), Error> {
-    let target_member = member; // renamed to avoid confusion
+    let target_user = user; // renamed to avoid confusion
 
     let executing_member =
         ctx
@@ -49,53 +90,38 @@ pub async fn unban(
         .expect("There should be a member in this context.")
         .clone();
 
-    let guild = ctx.guild().expect("There should be a guild in this context.").clone();
-
-    let my_id = ctx.serenity_context().cache.current_user().id;
-
-    let my_guild_member =
-        guild
-        .member(&ctx.http(), my_id).await
-        .expect("I should be in this guild.")
-        .clone();
-
-    // check if executing member is above target member in the role hierarchy
-    moderation::assert_member_above_other_member(
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability(
         &ctx,
         &executing_member,
-        &target_member,
-        "You cannot perform an action on a member that has an equal or higher role than you.",
+        GuildConfigModerationCapability::Ban,
     ).await?;
 
-    // check if this bot is above target member in the role hierarchy
-    moderation::assert_member_above_other_member(
-        &ctx,
-        &my_guild_member,
-        &target_member,
-        "I cannot perform an action on a member that has an equal or higher role than me.",
-    ).await?;
+    let guild = ctx.guild().expect("There should be a guild in this context.").clone();
 
     let reason = reason.unwrap_or("A reason was not provided.".to_string());
 
-    target_member.user.id.dm(
-        &ctx.http(),
-        serenity::CreateMessage::default()
-        .embed(
-            serenity::CreateEmbed::default()
-            .color(branding::color::PRIMARY)
-            .title(format!("{} - Moderation", guild.name))
-            .description(
-                format!(
-                    "You were unbanned in {} by {} for:\n{}",
-                    guild.name,
-                    executing_member.user.mention(),
-                    create_escaped_code_block(None, &reason),
-                )
-            )
-        )
-    ).await.ok(); // ignore errors
+    unban_user(
+        ctx.http(),
+        guild.id,
+        &guild.name,
+        target_user.id,
+        executing_member.user.id,
+        &reason,
+    ).await?;
+
+    // a manual unban supersedes any scheduled unban
+    scheduler::cancel_job(&scheduler::create_unban_dedupe_key(guild.id, target_user.id)).await?;
+
+    let moderation_case = moderation::create_moderation_case(
+        &ctx,
+        ModerationCaseAction::Unban,
+        Some(target_user.id),
+        &reason,
+        None,
+    ).await?;
 
-    target_member.unban(&ctx.http(), Some(&reason)).await?;
+    let case_id = moderation_case.get_case_id().await;
 
     ctx.send(
         poise::CreateReply::default()
@@ -106,11 +132,12 @@ pub async fn unban(
             .description(
                 format!(
                     "{} was unbanned by {} for:\n{}",
-                    target_member.user.mention(),
+                    target_user.mention(),
                     executing_member.user.mention(),
                     create_escaped_code_block(None, &reason),
                 )
             )
+            .footer(moderation::create_moderation_case_footer(case_id))
         )
     ).await?;
 
@@ -17,6 +17,10 @@ use crate::common::helpers::bot::create_escaped_code_block;
 
 use crate::common::moderation;
 
+use crate::common::database::interfaces::guild_config::GuildConfigModerationCapability;
+
+use crate::common::database::interfaces::moderation_case::ModerationCaseAction;
+
 //------------------------------------------------------------//
 
 /// Removes a member from timeout.
@@ -28,7 +32,6 @@ use crate::common::moderation;
         install_context = "Guild",
         interaction_context = "Guild",
         user_cooldown = "5", // in seconds
-        default_member_permissions = "MODERATE_MEMBERS",
         required_bot_permissions = "MODERATE_MEMBERS",
     )
 ]
@@ -49,6 +52,13 @@ pub async fn untimeout(
         .expect("There should be a member in this context.")
         .clone();
 
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Timeout,
+    ).await?;
+
     let guild = ctx.guild().expect("There should be a guild in this context.").clone();
 
     let my_id = ctx.serenity_context().cache.current_user().id;
@@ -102,6 +112,16 @@ pub async fn untimeout(
         )
     ).await.ok(); // ignore errors
 
+    let moderation_case = moderation::create_moderation_case(
+        &ctx,
+        ModerationCaseAction::Untimeout,
+        Some(target_member.user.id),
+        &reason,
+        None,
+    ).await?;
+
+    let case_id = moderation_case.get_case_id().await;
+
     ctx.send(
         poise::CreateReply::default()
         .embed(
@@ -116,6 +136,7 @@ pub async fn untimeout(
                     create_escaped_code_block(None, &reason),
                 )
             )
+            .footer(moderation::create_moderation_case_footer(case_id))
         )
     ).await?;
 
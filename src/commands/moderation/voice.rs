@@ -0,0 +1,523 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::serenity_prelude::Mentionable;
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::Context;
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::common::helpers::bot::create_escaped_code_block;
+
+use crate::common::helpers::time::{autocomplete_duration, format_duration};
+
+use crate::common::moderation;
+
+use crate::commands::moderation::mute::{parse_voice_mute_duration, schedule_voice_unmute};
+
+use crate::common::database::interfaces::guild_config::GuildConfigModerationCapability;
+
+use crate::common::database::interfaces::moderation_case::ModerationCaseAction;
+
+use crate::common::scheduler;
+
+//------------------------------------------------------------//
+
+/// The maximum amount of failures to list in the summary.
+const MAX_LISTED_FAILURES: usize = 10;
+
+//------------------------------------------------------------//
+
+#[derive(Clone, Copy)]
+enum VoiceChannelAction {
+    Mute {
+        duration: Option<chrono::Duration>,
+    },
+    Unmute,
+    Move {
+        target_voice_channel_id: serenity::ChannelId,
+    },
+    Disconnect,
+}
+
+impl VoiceChannelAction {
+    fn past_tense(
+        &self,
+    ) -> &'static str {
+        match self {
+            VoiceChannelAction::Mute { .. } => "muted",
+            VoiceChannelAction::Unmute => "unmuted",
+            VoiceChannelAction::Move { .. } => "moved",
+            VoiceChannelAction::Disconnect => "disconnected",
+        }
+    }
+
+    /// The discord permission that members without a granted role need to perform this action.
+    fn discord_permissions(
+        &self,
+    ) -> serenity::Permissions {
+        match self {
+            VoiceChannelAction::Mute { .. } => serenity::Permissions::MUTE_MEMBERS,
+            VoiceChannelAction::Unmute => serenity::Permissions::MUTE_MEMBERS,
+            VoiceChannelAction::Move { .. } => serenity::Permissions::MOVE_MEMBERS,
+            VoiceChannelAction::Disconnect => serenity::Permissions::MOVE_MEMBERS,
+        }
+    }
+
+    fn moderation_case_action(
+        &self,
+    ) -> ModerationCaseAction {
+        match self {
+            VoiceChannelAction::Mute { .. } => ModerationCaseAction::MassMute,
+            VoiceChannelAction::Unmute => ModerationCaseAction::MassUnmute,
+            VoiceChannelAction::Move { .. } => ModerationCaseAction::MassMove,
+            VoiceChannelAction::Disconnect => ModerationCaseAction::MassDisconnect,
+        }
+    }
+}
+
+//------------------------------------------------------------//
+
+/// Uses the specified voice channel, or else the voice channel of the executing member.
+fn resolve_voice_channel_id(
+    ctx: &Context<'_>,
+    voice_channel: Option<serenity::GuildChannel>,
+) -> Result<serenity::ChannelId, Error> {
+    if let Some(voice_channel) = voice_channel {
+        return Ok(voice_channel.id);
+    }
+
+    let guild = ctx.guild().expect("There should be a guild in this context.");
+
+    guild.voice_states.get(&ctx.author().id)
+    .and_then(|voice_state| voice_state.channel_id)
+    .ok_or(Error::from("Specify a voice channel, or join one to use this command."))
+}
+
+/// Fetches the members connected to a voice channel.
+/// Members that could not be fetched are returned separately, along with why.
+async fn fetch_voice_channel_members(
+    ctx: &Context<'_>,
+    voice_channel_id: serenity::ChannelId,
+) -> (Vec<serenity::Member>, Vec<(serenity::UserId, String)>) {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let connected_user_ids: Vec<serenity::UserId> = {
+        let guild = ctx.guild().expect("There should be a guild in this context.");
+
+        guild.voice_states.iter()
+        .filter(|voice_state| voice_state.channel_id == Some(voice_channel_id))
+        .map(|voice_state| voice_state.user_id)
+        .collect()
+    };
+
+    let mut members = Vec::new();
+    let mut failures = Vec::new();
+
+    for user_id in connected_user_ids {
+        match guild_id.member(ctx.http(), user_id).await {
+            Ok(member) => members.push(member),
+            Err(why) => failures.push((user_id, why.to_string())),
+        }
+    }
+
+    (members, failures)
+}
+
+async fn perform_action_on_member(
+    ctx: &Context<'_>,
+    action: VoiceChannelAction,
+    executing_member: &serenity::Member,
+    my_guild_member: &serenity::Member,
+    target_member: &mut serenity::Member,
+    reason: &str,
+) -> Result<(), Error> {
+    moderation::assert_member_above_other_member(
+        ctx,
+        executing_member,
+        target_member,
+        "This member has an equal or higher role than you.",
+    ).await?;
+
+    moderation::assert_member_above_other_member(
+        ctx,
+        my_guild_member,
+        target_member,
+        "This member has an equal or higher role than me.",
+    ).await?;
+
+    match action {
+        VoiceChannelAction::Mute { duration } => {
+            target_member.edit(
+                &ctx.http(),
+                serenity::EditMember::default()
+                .mute(true)
+                .audit_log_reason(reason),
+            ).await?;
+
+            if let Some(duration) = duration {
+                schedule_voice_unmute(target_member.guild_id, target_member.user.id, duration).await?;
+            } else {
+                // an indefinite mute supersedes any scheduled unmute
+                scheduler::cancel_job(&scheduler::create_voice_unmute_dedupe_key(target_member.guild_id, target_member.user.id)).await?;
+            }
+        },
+        VoiceChannelAction::Unmute => {
+            target_member.edit(
+                &ctx.http(),
+                serenity::EditMember::default()
+                .mute(false)
+                .audit_log_reason(reason),
+            ).await?;
+
+            // a manual unmute supersedes any scheduled unmute
+            scheduler::cancel_job(&scheduler::create_voice_unmute_dedupe_key(target_member.guild_id, target_member.user.id)).await?;
+        },
+        VoiceChannelAction::Move { target_voice_channel_id } => {
+            moderation::relocate_member_in_voice_channel(
+                ctx,
+                target_member,
+                target_voice_channel_id,
+                reason,
+            ).await?;
+        },
+        VoiceChannelAction::Disconnect => {
+            target_member.edit(
+                &ctx.http(),
+                serenity::EditMember::default()
+                .disconnect_member()
+                .audit_log_reason(reason),
+            ).await?;
+        },
+    }
+
+    Ok(())
+}
+
+fn create_failures_string(
+    failures: &[(serenity::UserId, String)],
+) -> String {
+    let mut failures_string =
+        failures
+        .iter()
+        .take(MAX_LISTED_FAILURES)
+        .map(|(user_id, why)| format!("- {}: {}", user_id.mention(), why))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    if failures.len() > MAX_LISTED_FAILURES {
+        failures_string.push_str(&format!("\n...and {} more", failures.len() - MAX_LISTED_FAILURES));
+    }
+
+    failures_string
+}
+
+/// Performs the action on every member in the voice channel, except for the executing member and excluded roles.
+/// Responds with a summary of the successes and failures.
+async fn perform_action_on_voice_channel(
+    ctx: &Context<'_>,
+    action: VoiceChannelAction,
+    voice_channel_id: serenity::ChannelId,
+    excluded_role_ids: &[serenity::RoleId],
+    reason: &str,
+) -> Result<(), Error> {
+    let executing_member =
+        ctx
+        .author_member().await
+        .expect("There should be a member in this context.")
+        .clone();
+
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability_with_discord_permissions(
+        ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Voice,
+        action.discord_permissions(),
+    ).await?;
+
+    let guild = ctx.guild().expect("There should be a guild in this context.").clone();
+
+    let my_id = ctx.serenity_context().cache.current_user().id;
+
+    let my_guild_member =
+        guild
+        .member(&ctx.http(), my_id).await
+        .expect("I should be in this guild.")
+        .clone();
+
+    // acting on many members may take a while
+    ctx.defer().await?;
+
+    // members that could not be fetched are counted as failures, instead of preventing the action on everyone else
+    let (voice_channel_members, mut failures) = fetch_voice_channel_members(ctx, voice_channel_id).await;
+
+    failures.retain(|(user_id, _)| *user_id != executing_member.user.id);
+
+    let target_members: Vec<serenity::Member> =
+        voice_channel_members
+        .into_iter()
+        .filter(|member| member.user.id != executing_member.user.id)
+        .filter(|member| !member.roles.iter().any(|role_id| excluded_role_ids.contains(role_id)))
+        .collect();
+
+    if target_members.is_empty() && failures.is_empty() {
+        return Err(format!("There are no members in {} to perform this action on.", voice_channel_id.mention()).into());
+    }
+
+    let targeted_amount = target_members.len() + failures.len();
+
+    let mut succeeded_amount: usize = 0;
+
+    for mut target_member in target_members {
+        match perform_action_on_member(ctx, action, &executing_member, &my_guild_member, &mut target_member, reason).await {
+            Ok(_) => succeeded_amount += 1,
+            Err(why) => failures.push((target_member.user.id, why.to_string())),
+        }
+
+        // throttle our requests to avoid hitting the rate limit
+        tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+    }
+
+    let duration_string = match action {
+        VoiceChannelAction::Mute { duration: Some(duration) } => format!(" for {}", format_duration(duration.to_std()?)),
+        _ => String::new(),
+    };
+
+    let destination_string = match action {
+        VoiceChannelAction::Move { target_voice_channel_id } => format!(" to {}", target_voice_channel_id.mention()),
+        _ => String::new(),
+    };
+
+    let summary = format!(
+        "{} of {} members in {} were {}{}{}",
+        succeeded_amount,
+        targeted_amount,
+        voice_channel_id.mention(),
+        action.past_tense(),
+        destination_string,
+        duration_string,
+    );
+
+    let moderation_case = moderation::create_moderation_case(
+        ctx,
+        action.moderation_case_action(),
+        None,
+        &format!("{}\n\n{}.", reason, summary),
+        match action {
+            VoiceChannelAction::Mute { duration } => duration.map(|duration| duration.num_seconds() as u32),
+            _ => None,
+        },
+    ).await?;
+
+    let case_id = moderation_case.get_case_id().await;
+
+    let mut summary_embed =
+        serenity::CreateEmbed::default()
+        .color(branding::color::PRIMARY)
+        .title("Moderation")
+        .description(
+            format!(
+                "{} by {} for:\n{}",
+                summary,
+                executing_member.user.mention(),
+                create_escaped_code_block(None, reason),
+            )
+        )
+        .footer(moderation::create_moderation_case_footer(case_id));
+
+    if !failures.is_empty() {
+        summary_embed = summary_embed.field(
+            format!("Failed ({})", failures.len()),
+            create_failures_string(&failures),
+            false,
+        );
+    }
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(summary_embed)
+    ).await?;
+
+    Ok(())
+}
+
+//------------------------------------------------------------//
+
+/// Mutes everyone in a voice channel, except for you and the excluded roles.
+#[
+    poise::command(
+        slash_command,
+        rename = "mute-all",
+    )
+]
+pub async fn mute_all(
+    ctx: Context<'_>,
+
+    #[channel_types("Voice", "Stage")]
+    #[description = "The voice channel to mute (defaults to your voice channel)"]
+    channel: Option<serenity::GuildChannel>,
+
+    #[description = "Members with this role are not muted"]
+    excluded_role: Option<serenity::Role>,
+
+    #[description = "Members with this role are not muted"]
+    another_excluded_role: Option<serenity::Role>,
+
+    #[autocomplete = "autocomplete_duration"]
+    #[description = "How long until the members are automatically unmuted, e.g. `15m` or `2h` (indefinite if omitted)"]
+    duration: Option<String>,
+
+    #[description = "Why this action was performed"]
+    reason: Option<String>,
+) -> Result<(), Error> {
+    let voice_channel_id = resolve_voice_channel_id(&ctx, channel)?;
+
+    let excluded_role_ids: Vec<serenity::RoleId> =
+        [excluded_role, another_excluded_role]
+        .into_iter()
+        .flatten()
+        .map(|role| role.id)
+        .collect();
+
+    let duration = match duration {
+        Some(duration) => Some(chrono::Duration::from_std(parse_voice_mute_duration(&duration)?)?),
+        None => None,
+    };
+
+    let reason = reason.unwrap_or("A reason was not provided.".to_string());
+
+    perform_action_on_voice_channel(
+        &ctx,
+        VoiceChannelAction::Mute {
+            duration: duration,
+        },
+        voice_channel_id,
+        &excluded_role_ids,
+        &reason,
+    ).await
+}
+
+/// Unmutes everyone in a voice channel.
+#[
+    poise::command(
+        slash_command,
+        rename = "unmute-all",
+    )
+]
+pub async fn unmute_all(
+    ctx: Context<'_>,
+
+    #[channel_types("Voice", "Stage")]
+    #[description = "The voice channel to unmute (defaults to your voice channel)"]
+    channel: Option<serenity::GuildChannel>,
+
+    #[description = "Why this action was performed"]
+    reason: Option<String>,
+) -> Result<(), Error> {
+    let voice_channel_id = resolve_voice_channel_id(&ctx, channel)?;
+
+    let reason = reason.unwrap_or("A reason was not provided.".to_string());
+
+    perform_action_on_voice_channel(
+        &ctx,
+        VoiceChannelAction::Unmute,
+        voice_channel_id,
+        &[],
+        &reason,
+    ).await
+}
+
+/// Moves everyone from one voice channel to another, except for you.
+#[
+    poise::command(
+        slash_command,
+        rename = "move-all",
+    )
+]
+pub async fn move_all(
+    ctx: Context<'_>,
+
+    #[channel_types("Voice", "Stage")]
+    #[description = "The voice channel to move members from"]
+    from: serenity::GuildChannel,
+
+    #[channel_types("Voice", "Stage")]
+    #[description = "The voice channel to move members to"]
+    to: serenity::GuildChannel,
+
+    #[description = "Why this action was performed"]
+    reason: Option<String>,
+) -> Result<(), Error> {
+    if from.id == to.id {
+        return Err("The voice channels must be different.".into());
+    }
+
+    let reason = reason.unwrap_or("A reason was not provided.".to_string());
+
+    perform_action_on_voice_channel(
+        &ctx,
+        VoiceChannelAction::Move {
+            target_voice_channel_id: to.id,
+        },
+        from.id,
+        &[],
+        &reason,
+    ).await
+}
+
+/// Disconnects everyone from a voice channel, except for you.
+#[
+    poise::command(
+        slash_command,
+        rename = "disconnect-all",
+    )
+]
+pub async fn disconnect_all(
+    ctx: Context<'_>,
+
+    #[channel_types("Voice", "Stage")]
+    #[description = "The voice channel to disconnect (defaults to your voice channel)"]
+    channel: Option<serenity::GuildChannel>,
+
+    #[description = "Why this action was performed"]
+    reason: Option<String>,
+) -> Result<(), Error> {
+    let voice_channel_id = resolve_voice_channel_id(&ctx, channel)?;
+
+    let reason = reason.unwrap_or("A reason was not provided.".to_string());
+
+    perform_action_on_voice_channel(
+        &ctx,
+        VoiceChannelAction::Disconnect,
+        voice_channel_id,
+        &[],
+        &reason,
+    ).await
+}
+
+//------------------------------------------------------------//
+
+/// Moderate everyone in a voice channel at once.
+#[
+    poise::command(
+        slash_command,
+        guild_only,
+        subcommands("mute_all", "unmute_all", "move_all", "disconnect_all"),
+        category = "Moderation",
+        install_context = "Guild",
+        interaction_context = "Guild",
+        user_cooldown = "10", // in seconds
+        required_bot_permissions = "MUTE_MEMBERS | MOVE_MEMBERS",
+    )
+]
+pub async fn voice(
+    _ctx: Context<'_>,
+) -> Result<(), Error> {
+    Ok(())
+}
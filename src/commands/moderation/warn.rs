@@ -15,7 +15,181 @@ use crate::common::branding;
 
 use crate::common::helpers::bot::create_escaped_code_block;
 
-use crate::common::moderation;
+use crate::common::helpers::time::format_duration;
+
+use crate::common::moderation::{self, ModerationActionContext};
+
+use crate::common::moderation_appeals;
+
+use crate::common::database::interfaces::moderation_case::{ModerationCase, ModerationCaseAction, ModerationCaseAppealStatus};
+
+use crate::common::database::interfaces::guild_config::{
+    GuildConfig,
+    GuildConfigEscalationAction,
+    GuildConfigModerationCapability,
+};
+
+use crate::commands::moderation::ban::ban_member;
+use crate::commands::moderation::kick::kick_member;
+use crate::commands::moderation::timeout::timeout_member;
+
+//------------------------------------------------------------//
+
+/// Fallback duration for timeout escalation rules that were configured without a duration.
+const DEFAULT_ESCALATION_TIMEOUT_SECONDS: u32 = 60 * 60; // 1 hour
+
+//------------------------------------------------------------//
+
+/// Returns the amount of warnings a member has received that have not yet decayed.
+pub async fn count_active_warnings(
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+    warning_decay_days: Option<u32>,
+) -> Result<u32, Error> {
+    let warning_cases =
+        ModerationCase::fetch_by_action_for_target(guild_id, user_id, ModerationCaseAction::Warn).await?;
+
+    let decay_cutoff =
+        warning_decay_days
+        .map(|days| chrono::Utc::now() - chrono::Duration::days(days as i64));
+
+    let mut active_warnings = 0;
+
+    for warning_case in warning_cases {
+        // warnings with an accepted appeal no longer count
+        let is_appeal_accepted = matches!(
+            warning_case.get_appeal().await,
+            Some(appeal) if appeal.status == ModerationCaseAppealStatus::Accepted
+        );
+
+        if is_appeal_accepted {
+            continue;
+        }
+
+        let is_active = match decay_cutoff {
+            Some(decay_cutoff) => warning_case.get_created_at().await > decay_cutoff,
+            None => true,
+        };
+
+        if is_active {
+            active_warnings += 1;
+        }
+    }
+
+    Ok(active_warnings)
+}
+
+/// Applies the guild's escalation rule for the member's current amount of active warnings.
+/// Returns a short summary of the escalation if one was applied.
+async fn apply_warning_escalation(
+    action_ctx: &ModerationActionContext<'_>,
+    target_member: &mut serenity::Member,
+) -> Result<Option<String>, Error> {
+    let guild_id = action_ctx.guild_id;
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let moderation_escalation = guild_config.get_moderation_escalation().await;
+
+    if moderation_escalation.rules.is_empty() {
+        return Ok(None);
+    }
+
+    let active_warnings = count_active_warnings(
+        guild_id,
+        target_member.user.id,
+        moderation_escalation.warning_decay_days,
+    ).await?;
+
+    let Some(escalation_rule) = moderation_escalation.find_rule_for_warning_count(active_warnings) else {
+        return Ok(None);
+    };
+
+    let escalation_reason = format!("Automatic escalation after reaching {} active warnings.", active_warnings);
+
+    let (escalation_summary, escalation_case) = match escalation_rule.action {
+        GuildConfigEscalationAction::Timeout => {
+            let duration_seconds =
+                escalation_rule.duration_seconds
+                .unwrap_or(DEFAULT_ESCALATION_TIMEOUT_SECONDS);
+
+            let escalation_case = timeout_member(
+                action_ctx,
+                target_member,
+                chrono::Duration::seconds(duration_seconds as i64),
+                &escalation_reason,
+            ).await?;
+
+            let duration_string =
+                format_duration(std::time::Duration::from_secs(duration_seconds as u64));
+
+            (format!("timed out for {}", duration_string), escalation_case)
+        },
+        GuildConfigEscalationAction::Kick => {
+            let escalation_case = kick_member(action_ctx, target_member, &escalation_reason).await?;
+
+            ("kicked".to_string(), escalation_case)
+        },
+        GuildConfigEscalationAction::Ban => {
+            let escalation_case = ban_member(action_ctx, target_member, None, &escalation_reason).await?;
+
+            ("banned".to_string(), escalation_case)
+        },
+    };
+
+    Ok(Some(
+        format!(
+            "{} reached {} active warnings and was automatically {} (case #{}).",
+            target_member.user.mention(),
+            active_warnings,
+            escalation_summary,
+            escalation_case.get_case_id().await,
+        )
+    ))
+}
+
+/// Warns a member, notifies them, records a moderation case, and applies any escalation.
+/// Returns the warning's case and a short summary of the escalation if one was applied.
+pub async fn warn_member(
+    action_ctx: &ModerationActionContext<'_>,
+    target_member: &mut serenity::Member,
+    reason: &str,
+) -> Result<(ModerationCase, Option<String>), Error> {
+    let notification_message = target_member.user.id.dm(
+        action_ctx.http,
+        serenity::CreateMessage::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title(format!("{} - Moderation", action_ctx.guild_name))
+            .description(
+                format!(
+                    "You were warned in {} by {} for:\n{}",
+                    action_ctx.guild_name,
+                    action_ctx.actor_user_id.mention(),
+                    create_escaped_code_block(None, reason),
+                )
+            )
+        )
+    ).await.ok(); // ignore errors
+
+    let moderation_case = moderation::record_moderation_case(
+        action_ctx,
+        ModerationCaseAction::Warn,
+        Some(target_member.user.id),
+        reason,
+        None,
+    ).await?;
+
+    // the action has already been performed, so failing to offer an appeal should not fail the action
+    if let Err(why) = moderation_appeals::add_appeal_button_to_notification(action_ctx.http, notification_message, &moderation_case).await {
+        eprintln!("Failed to add an appeal button to a moderation notification: {:?}", why);
+    }
+
+    let escalation_summary = apply_warning_escalation(action_ctx, target_member).await?;
+
+    Ok((moderation_case, escalation_summary))
+}
 
 //------------------------------------------------------------//
 
@@ -28,7 +202,6 @@ use crate::common::moderation;
         install_context = "Guild",
         interaction_context = "Guild",
         user_cooldown = "3", // in seconds
-        default_member_permissions = "MODERATE_MEMBERS",
         required_bot_permissions = "MODERATE_MEMBERS",
     )
 ]
@@ -41,7 +214,7 @@ pub async fn warn(
     #[description = "Why this action was performed"]
     reason: Option<String>,
 ) -> Result<(), Error> {
-    let target_member = member; // renamed to avoid confusion
+    let mut target_member = member; // renamed to avoid confusion
 
     let executing_member =
         ctx
@@ -49,6 +222,13 @@ pub async fn warn(
         .expect("There should be a member in this context.")
         .clone();
 
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Warn,
+    ).await?;
+
     let guild = ctx.guild().expect("There should be a guild in this context.").clone();
 
     let my_id = ctx.serenity_context().cache.current_user().id;
@@ -77,23 +257,27 @@ pub async fn warn(
 
     let reason = reason.unwrap_or("A reason was not provided.".to_string());
 
-    target_member.user.id.dm(
-        &ctx.http(),
-        serenity::CreateMessage::default()
-        .embed(
-            serenity::CreateEmbed::default()
-            .color(branding::color::PRIMARY)
-            .title(format!("{} - Moderation", guild.name))
-            .description(
-                format!(
-                    "You were warned in {} by {} for:\n{}",
-                    guild.name,
-                    executing_member.user.mention(),
-                    create_escaped_code_block(None, &reason),
-                )
-            )
-        )
-    ).await.ok(); // ignore errors
+    let action_ctx = ModerationActionContext::from_context(&ctx);
+
+    let (moderation_case, escalation_summary) = warn_member(&action_ctx, &mut target_member, &reason).await?;
+
+    let case_id = moderation_case.get_case_id().await;
+
+    let description = match escalation_summary {
+        Some(escalation_summary) => format!(
+            "{} was warned by {} for:\n{}\n{}",
+            target_member.user.mention(),
+            executing_member.user.mention(),
+            create_escaped_code_block(None, &reason),
+            escalation_summary,
+        ),
+        None => format!(
+            "{} was warned by {} for:\n{}",
+            target_member.user.mention(),
+            executing_member.user.mention(),
+            create_escaped_code_block(None, &reason),
+        ),
+    };
 
     ctx.send(
         poise::CreateReply::default()
@@ -101,14 +285,8 @@ pub async fn warn(
             serenity::CreateEmbed::default()
             .color(branding::color::PRIMARY)
             .title("Moderation")
-            .description(
-                format!(
-                    "{} was warned by {} for:\n{}",
-                    target_member.user.mention(),
-                    executing_member.user.mention(),
-                    create_escaped_code_block(None, &reason),
-                )
-            )
+            .description(description)
+            .footer(moderation::create_moderation_case_footer(case_id))
         )
     ).await?;
 
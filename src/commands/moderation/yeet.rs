@@ -21,6 +21,10 @@ use crate::common::helpers::bot::create_escaped_code_block;
 
 use crate::common::moderation;
 
+use crate::common::database::interfaces::guild_config::GuildConfigModerationCapability;
+
+use crate::common::database::interfaces::moderation_case::ModerationCaseAction;
+
 //------------------------------------------------------------//
 
 type GuildVoiceStates = HashMap::<poise::serenity_prelude::UserId, serenity::VoiceState>;
@@ -59,22 +63,6 @@ fn find_suitable_voice_channel(
     Ok(suitable_voice_channel_id_option)
 }
 
-async fn relocate_member_in_voice_channel(
-    ctx: &Context<'_>,
-    member: &mut serenity::Member,
-    target_voice_channel_id: serenity::ChannelId,
-    audit_log_reason: &str,
-) -> Result<(), Error> {
-    member.edit(
-        &ctx.http(),
-        serenity::EditMember::default()
-        .voice_channel(target_voice_channel_id)
-        .audit_log_reason(audit_log_reason)
-    ).await?;
-
-    Ok(())
-}
-
 //------------------------------------------------------------//
 
 /// Yeets a user from their voice channel.
@@ -96,6 +84,14 @@ pub async fn someone(
         .expect("There should be a member in this context.")
         .clone();
 
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability_with_discord_permissions(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Voice,
+        serenity::Permissions::MOVE_MEMBERS,
+    ).await?;
+
     let guild = ctx.guild().expect("There should be a guild in this context.").clone();
 
     let my_id = ctx.serenity_context().cache.current_user().id;
@@ -176,13 +172,23 @@ pub async fn someone(
         )
     ).await.ok(); // ignore errors
 
-    relocate_member_in_voice_channel(
+    moderation::relocate_member_in_voice_channel(
         &ctx,
         &mut target_member,
         suitable_voice_channel,
         &format!("Yeeted by {} for: {}", executing_member.user.mention(), reason),
     ).await?;
 
+    let moderation_case = moderation::create_moderation_case(
+        &ctx,
+        ModerationCaseAction::Yeet,
+        Some(target_member.user.id),
+        &reason,
+        None,
+    ).await?;
+
+    let case_id = moderation_case.get_case_id().await;
+
     ctx.send(
         poise::CreateReply::default()
         .embed(
@@ -197,6 +203,7 @@ pub async fn someone(
                     create_escaped_code_block(None, &reason),
                 )
             )
+            .footer(moderation::create_moderation_case_footer(case_id))
         )
     ).await?;
 
@@ -214,7 +221,6 @@ pub async fn someone(
         install_context = "Guild",
         interaction_context = "Guild",
         user_cooldown = "5", // in seconds
-        default_member_permissions = "MOVE_MEMBERS",
         required_bot_permissions = "MOVE_MEMBERS",
     )
 ]
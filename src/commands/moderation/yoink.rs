@@ -21,27 +21,13 @@ use crate::common::helpers::bot::create_escaped_code_block;
 
 use crate::common::moderation;
 
-//------------------------------------------------------------//
+use crate::common::database::interfaces::guild_config::GuildConfigModerationCapability;
 
-type GuildVoiceStates = HashMap::<poise::serenity_prelude::UserId, serenity::VoiceState>;
+use crate::common::database::interfaces::moderation_case::ModerationCaseAction;
 
 //------------------------------------------------------------//
 
-async fn relocate_member_in_voice_channel(
-    ctx: &Context<'_>,
-    member: &mut serenity::Member,
-    target_voice_channel_id: serenity::ChannelId,
-    audit_log_reason: &str,
-) -> Result<(), Error> {
-    member.edit(
-        &ctx.http(),
-        serenity::EditMember::default()
-        .voice_channel(target_voice_channel_id)
-        .audit_log_reason(audit_log_reason)
-    ).await?;
-
-    Ok(())
-}
+type GuildVoiceStates = HashMap::<poise::serenity_prelude::UserId, serenity::VoiceState>;
 
 //------------------------------------------------------------//
 
@@ -64,6 +50,14 @@ pub async fn someone(
         .expect("There should be a member in this context.")
         .clone();
 
+    // check if executing member may use this moderation capability
+    moderation::assert_member_has_moderation_capability_with_discord_permissions(
+        &ctx,
+        &executing_member,
+        GuildConfigModerationCapability::Voice,
+        serenity::Permissions::MOVE_MEMBERS,
+    ).await?;
+
     let guild = ctx.guild().expect("There should be a guild in this context.").clone();
 
     let my_id = ctx.serenity_context().cache.current_user().id;
@@ -140,13 +134,23 @@ pub async fn someone(
         )
     ).await.ok(); // ignore errors
 
-    relocate_member_in_voice_channel(
+    moderation::relocate_member_in_voice_channel(
         &ctx,
         &mut target_member,
         executing_member_voice_channel_id,
         &format!("Yoinked by {} for: {}", executing_member.user.mention(), reason),
     ).await?;
 
+    let moderation_case = moderation::create_moderation_case(
+        &ctx,
+        ModerationCaseAction::Yoink,
+        Some(target_member.user.id),
+        &reason,
+        None,
+    ).await?;
+
+    let case_id = moderation_case.get_case_id().await;
+
     ctx.send(
         poise::CreateReply::default()
         .embed(
@@ -161,6 +165,7 @@ pub async fn someone(
                     create_escaped_code_block(None, &reason),
                 )
             )
+            .footer(moderation::create_moderation_case_footer(case_id))
         )
     ).await?;
 
@@ -178,7 +183,6 @@ pub async fn someone(
         install_context = "Guild",
         interaction_context = "Guild",
         user_cooldown = "5", // in seconds
-        default_member_permissions = "MOVE_MEMBERS",
         required_bot_permissions = "MOVE_MEMBERS",
     )
 ]
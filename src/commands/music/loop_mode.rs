@@ -0,0 +1,68 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use crate::Context;
+
+use crate::Error;
+
+use crate::common::music::{self, LoopMode};
+
+//------------------------------------------------------------//
+
+/// Loop the current song or the whole queue.
+#[
+    poise::command(
+        slash_command,
+        guild_only,
+        rename = "loop",
+        category = "Music",
+        install_context = "Guild",
+        interaction_context = "Guild",
+        guild_cooldown = "3", // in seconds
+        user_cooldown = "5", // in seconds
+    )
+]
+pub async fn loop_mode(
+    ctx: Context<'_>,
+
+    #[description = "What should be looped"]
+    mode: LoopMode,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let Some(guild_id) = ctx.guild_id() else {
+        ctx.say("This command can only be used in a server.").await?;
+
+        return Ok(());
+    };
+
+    let context_data = ctx.data();
+
+    let lavalink_client = match &context_data.lavalink {
+        Some(client) => client,
+        None => {
+            ctx.say("Lavalink client is not initialized.").await?;
+
+            return Ok(());
+        }
+    };
+
+    let Some(player_context) = lavalink_client.get_player_context(guild_id.get()) else {
+        ctx.say("Join the bot to a voice channel first.").await?;
+
+        return Ok(());
+    };
+
+    music::set_loop_mode(&player_context, mode).await?;
+
+    let message = match mode {
+        LoopMode::Off => "Looping is now off.",
+        LoopMode::Track => "Now looping the current track.",
+        LoopMode::Queue => "Now looping the queue.",
+    };
+
+    ctx.say(message).await?;
+
+    Ok(())
+}
@@ -4,13 +4,21 @@
 
 use std::collections::HashMap;
 
+use std::sync::Arc;
+
 //------------------------------------------------------------//
 
 use lavalink_rs::prelude::*;
 
+use lavalink_rs::model::track::{Playlist, TrackData};
+
+use rand::seq::SliceRandom;
+
+use poise::serenity_prelude::ComponentInteractionCollector;
 use poise::serenity_prelude::{self as serenity};
 
 use serenity::Mentionable;
+use serenity::futures::stream::StreamExt;
 
 //------------------------------------------------------------//
 
@@ -18,7 +26,11 @@ use crate::Context;
 
 use crate::Error;
 
-use crate::common::music;
+use crate::common::music::{self, autocomplete_search_source};
+
+use crate::common::helpers::time::format_duration;
+
+use crate::common::database::interfaces::guild_config::{GuildConfig, GuildConfigMusic, GuildConfigMusicSearchSource};
 
 //------------------------------------------------------------//
 
@@ -26,18 +38,241 @@ type GuildVoiceStates = HashMap::<poise::serenity_prelude::UserId, serenity::Voi
 
 //------------------------------------------------------------//
 
+/// The maximum amount of search results that can be picked from, discord allows up to 25.
+const MAX_SEARCH_RESULTS: usize = 10;
+
+/// Discord limits the labels and descriptions of select menu options to this many characters.
+const SELECT_MENU_TEXT_MAX_LENGTH: usize = 100;
+
+//------------------------------------------------------------//
+
+/// Options for how the tracks loaded by a query are added to the queue.
+#[derive(Default)]
+pub struct EnqueueOptions {
+    /// The source that plain queries are searched on, otherwise the guild's default source is used.
+    pub search_source: Option<GuildConfigMusicSearchSource>,
+
+    /// Whether the requester picks one of the search results, instead of the first result being used.
+    pub pick_search_result: bool,
+
+    /// The (1-based) track of a playlist to start at, otherwise the playlist's selected track is used.
+    pub start_at: Option<usize>,
+
+    /// Whether a playlist's tracks should be shuffled before they are added to the queue.
+    pub shuffle: bool,
+}
+
+/// Selects the tracks of a playlist that should be added to the queue.
+/// Returns the selected tracks and the amount of tracks that were left out due to the limit.
+fn select_playlist_tracks(
+    playlist: Playlist,
+    enqueue_options: &EnqueueOptions,
+    max_playlist_tracks: usize,
+) -> Result<(Vec<TrackData>, usize), Error> {
+    let mut tracks = playlist.tracks;
+
+    if tracks.is_empty() {
+        return Err("The playlist has no tracks.".into());
+    }
+
+    let start_index = match enqueue_options.start_at {
+        Some(start_at) => start_at.saturating_sub(1),
+        None => usize::try_from(playlist.info.selected_track).unwrap_or(0), // negative when no track is selected
+    };
+
+    if start_index >= tracks.len() {
+        return Err(format!("The playlist only has {} tracks.", tracks.len()).into());
+    }
+
+    tracks.drain(..start_index);
+
+    if enqueue_options.shuffle {
+        tracks.shuffle(&mut rand::rng());
+    }
+
+    let truncated_track_count = tracks.len().saturating_sub(max_playlist_tracks);
+
+    tracks.truncate(max_playlist_tracks);
+
+    Ok((tracks, truncated_track_count))
+}
+
+/// Adds the tracks of a playlist to the queue, continuing past tracks that fail to be added.
+async fn enqueue_playlist_tracks(
+    ctx: Context<'_>,
+    player_context: &PlayerContext,
+    playlist_name: &str,
+    tracks: Vec<TrackData>,
+    truncated_track_count: usize,
+    max_playlist_tracks: usize,
+) -> Result<(), Error> {
+    let mut enqueued_track_count = 0;
+    let mut failed_track_count = 0;
+    let mut enqueued_duration_millis = 0;
+
+    for track in tracks {
+        let track_length = if track.info.is_stream { 0 } else { track.info.length };
+
+        if let Err(why) = player_context.queue(track) {
+            eprintln!("Failed to enqueue playlist track:\n{}", why);
+
+            failed_track_count += 1;
+
+            continue;
+        }
+
+        enqueued_track_count += 1;
+        enqueued_duration_millis += track_length;
+    }
+
+    if enqueued_track_count == 0 {
+        return Err("Failed to enqueue the playlist.".into());
+    }
+
+    let mut summary = format!(
+        "Added **{}** tracks ({}) from **{}** to the queue.",
+        enqueued_track_count,
+        format_duration(std::time::Duration::from_millis(enqueued_duration_millis)),
+        playlist_name,
+    );
+
+    if truncated_track_count > 0 {
+        summary.push_str(
+            &format!(
+                "\nSkipped **{}** tracks, as at most **{}** tracks are added from a playlist.",
+                truncated_track_count,
+                max_playlist_tracks,
+            )
+        );
+    }
+
+    if failed_track_count > 0 {
+        summary.push_str(&format!("\nFailed to add **{}** tracks.", failed_track_count));
+    }
+
+    ctx.say(summary).await?;
+
+    Ok(())
+}
+
+/// Lets the requester pick one of the top search results from a select menu.
+/// Falls back to the first result if nothing is picked in time.
+async fn pick_search_result(
+    ctx: Context<'_>,
+    tracks: &[TrackData],
+) -> Result<Option<TrackData>, Error> {
+    let Some(first_track) = tracks.first() else {
+        return Ok(None);
+    };
+
+    let select_menu_id = format!("{}-search-result-select", ctx.id());
+
+    let select_menu_options =
+        tracks
+        .iter()
+        .take(MAX_SEARCH_RESULTS)
+        .enumerate()
+        .map(|(index, track)| {
+            let duration_string =
+                if track.info.is_stream { "Live".to_string() }
+                else { format_duration(std::time::Duration::from_millis(track.info.length)) };
+
+            serenity::CreateSelectMenuOption::new(
+                track.info.title.chars().take(SELECT_MENU_TEXT_MAX_LENGTH).collect::<String>(),
+                index.to_string(),
+            )
+            .description(
+                format!("{} | {}", track.info.author, duration_string)
+                .chars().take(SELECT_MENU_TEXT_MAX_LENGTH).collect::<String>()
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let reply_handle = ctx.send(
+        poise::CreateReply::default()
+        .content("Pick a search result to add to the queue.")
+        .components(vec![
+            serenity::CreateComponent::ActionRow(
+                serenity::CreateActionRow::SelectMenu(
+                    serenity::CreateSelectMenu::new(
+                        &select_menu_id,
+                        serenity::CreateSelectMenuKind::String {
+                            options: select_menu_options.into(),
+                        },
+                    )
+                    .placeholder("Search results")
+                )
+            )
+        ])
+    ).await?;
+
+    let message = reply_handle.message().await?;
+
+    let component_interaction =
+        ComponentInteractionCollector::new(&ctx.serenity_context())
+        .author_id(ctx.author().id)
+        .message_id(message.id)
+        .timeout(std::time::Duration::from_secs(60))
+        .stream()
+        .next().await;
+
+    let picked_track = match &component_interaction {
+        Some(component_interaction) => match &component_interaction.data.kind {
+            serenity::ComponentInteractionDataKind::StringSelect { values } => {
+                values
+                .first()
+                .and_then(|value| value.parse::<usize>().ok())
+                .and_then(|index| tracks.get(index))
+            },
+            _ => None,
+        },
+        None => None, // nothing was picked in time
+    };
+
+    let (picked_track, reply_content) = match picked_track {
+        Some(picked_track) => (picked_track, "A search result was picked."),
+        None => (first_track, "No search result was picked in time, so the first result was used."),
+    };
+
+    if let Some(component_interaction) = component_interaction {
+        component_interaction.defer(&ctx.http()).await?;
+    }
+
+    reply_handle.edit(
+        poise::Context::Application(ctx),
+        poise::CreateReply::default()
+        .content(reply_content)
+        .components(vec![])
+    ).await?;
+
+    Ok(Some(picked_track.clone()))
+}
+
 pub async fn query_and_enqueue_track(
     ctx: Context<'_>,
     lava_client: &LavalinkClient,
     player_context: &PlayerContext,
     guild_id: serenity::GuildId,
     query: String,
+    enqueue_options: EnqueueOptions,
 ) -> Result<(), Error> {
+    let music_config = match GuildConfig::fetch(guild_id).await? {
+        Some(guild_config) => guild_config.get_music().await,
+        None => GuildConfigMusic::default(),
+    };
+
     let query =
         if query.starts_with("http:") { query }
         else if query.starts_with("https:") { query }
         else if query.starts_with("speak:") { query }
-        else { SearchEngines::YouTube.to_query(&query)? };
+        else if let Some((search_source, query)) = music::split_search_source_prefix(&query) {
+            music::create_search_query(search_source, query)
+        }
+        else {
+            let search_source = enqueue_options.search_source.unwrap_or(music_config.default_search_source);
+
+            music::create_search_query(search_source, &query)
+        };
 
     let loaded_tracks = match lava_client.load_tracks(guild_id.get(), &query).await {
         Ok(load_tracks) => load_tracks,
@@ -52,16 +287,34 @@ pub async fn query_and_enqueue_track(
         Some(TrackLoadData::Track(track)) => vec![track.into()],
 
         Some(TrackLoadData::Search(tracks)) => {
-            match tracks.first() {
-                Some(track) => vec![track.to_owned().into()],
+            let track =
+                if enqueue_options.pick_search_result { pick_search_result(ctx, &tracks).await? }
+                else { tracks.first().cloned() };
+
+            match track {
+                Some(track) => vec![track.into()],
                 None => vec![],
             }
         },
 
         Some(TrackLoadData::Playlist(playlist)) => {
-            ctx.say(format!("Added playlist to queue: {}", playlist.info.name)).await?;
+            let max_playlist_tracks = music_config.max_playlist_tracks as usize;
+
+            let playlist_name = playlist.info.name.clone();
+
+            let (tracks, truncated_track_count) =
+                select_playlist_tracks(playlist, &enqueue_options, max_playlist_tracks)?;
 
-            playlist.tracks.into_iter().map(|x| x.into()).collect()
+            enqueue_playlist_tracks(
+                ctx,
+                player_context,
+                &playlist_name,
+                tracks,
+                truncated_track_count,
+                max_playlist_tracks,
+            ).await?;
+
+            vec![] // already enqueued
         },
 
         Some(TrackLoadData::Error(why)) => {
@@ -77,12 +330,6 @@ pub async fn query_and_enqueue_track(
         },
     };
 
-    if queued_tracks.len() > 1 {
-        eprintln!("Playlists are not yet supported.");
-
-        return Err("Playlists are not yet supported.".into());
-    }
-
     for track_to_enqueue in queued_tracks {
         if let Err(why) = player_context.queue(track_to_enqueue.track.clone()) {
             eprintln!("Failed to enqueue track:\n{}", why);
@@ -112,29 +359,34 @@ pub async fn query_and_enqueue_track(
         }
     }
 
-    match player_context.get_queue().get_track(0).await {
-        Ok(Some(track)) => {
-            // Attempt to jump-start the player by playing the first track in the queue.
-            // This should not cause issues if the player is already playing.
-            player_context.play(&track.track).await?;
-        },
-        Ok(None) => {}, // NOOP - queue is empty
+    let player = match player_context.get_player().await {
+        Ok(player) => player,
         Err(why) => {
-            eprintln!("Failed to get first queued track:\n{}", why);
+            eprintln!("Failed to get player:\n{}", why);
 
-            return Err("Failed to get first queued track.".into());
+            return Err("Failed to get player.".into());
         },
     };
 
-    let player = match player_context.get_player().await {
-        Ok(player) => player,
+    let first_queued_track = match player_context.get_queue().get_track(0).await {
+        Ok(first_queued_track) => first_queued_track,
         Err(why) => {
-            eprintln!("Failed to get player:\n{}", why);
+            eprintln!("Failed to get first queued track:\n{}", why);
 
-            return Err("Failed to get player.".into());
+            return Err("Failed to get first queued track.".into());
         },
     };
 
+    // Start the first queued track if nothing is playing.
+    // Afterwards, lavalink-rs advances the queue by itself whenever a track ends.
+    if player.track.is_none() && first_queued_track.is_some() {
+        if let Err(why) = player_context.skip() {
+            eprintln!("Failed to start the queue:\n{}", why);
+
+            return Err("Failed to start the queue.".into());
+        };
+    }
+
     let default_volume = music::Volume::from_normal_volume(music::NORMAL_VOLUME_DEFAULT);
     let maximum_volume = music::Volume::from_normal_volume(music::NORMAL_VOLUME_MAXIMUM);
     let current_volume = music::Volume::from_lavalink_volume(player.volume);
@@ -178,7 +430,26 @@ pub async fn play(
 
     #[description = "Search query or url to play"]
     query: String,
+
+    #[autocomplete = "autocomplete_search_source"]
+    #[description = "Where to search for the query (defaults to this server's default source)"]
+    source: Option<String>,
+
+    #[description = "Whether to pick from the top search results, instead of using the first result"]
+    pick: Option<bool>,
+
+    #[min = 1]
+    #[description = "The track of a playlist to start at (defaults to the track selected by the url)"]
+    start_at: Option<u32>,
+
+    #[description = "Whether the tracks of a playlist should be shuffled when added"]
+    shuffle: Option<bool>,
 ) -> Result<(), Error> {
+    let search_source = match source {
+        Some(source) => Some(music::find_search_source(&source).ok_or("Unknown search source.")?),
+        None => None,
+    };
+
     ctx.defer().await?;
 
     let Some(guild_id) = ctx.guild_id() else {
@@ -260,16 +531,30 @@ pub async fn play(
         return Ok(());
     };
 
+    // announce the tracks of this player in the channel that music was last requested in
+    music::get_player_data(&player_context)?.set_announcement_target(
+        music::MusicAnnouncementTarget {
+            http: Arc::clone(&ctx.serenity_context().http),
+            channel_id: ctx.channel_id(),
+        }
+    );
+
     if let Err(why) = query_and_enqueue_track(
         ctx,
         &lavalink_client,
         &player_context,
         guild_id,
         query,
+        EnqueueOptions {
+            search_source: search_source,
+            pick_search_result: pick.unwrap_or(false),
+            start_at: start_at.map(|start_at| start_at as usize),
+            shuffle: shuffle.unwrap_or(false),
+        },
     ).await {
         eprintln!("Failed to query and enqueue track:\n{}", why);
 
-        return Err("Failed to query and enqueue track.".into());
+        return Err(format!("Failed to query and enqueue track: {}", why).into());
     };
 
     Ok(())
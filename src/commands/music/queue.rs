@@ -4,6 +4,8 @@
 
 use itertools::Itertools;
 
+use poise::ChoiceParameter;
+
 // use lavalink_rs::prelude::*;
 
 //------------------------------------------------------------//
@@ -12,6 +14,8 @@ use crate::Context;
 
 use crate::Error;
 
+use crate::common::music;
+
 //------------------------------------------------------------//
 
 /// Clear the current queue.
@@ -132,7 +136,11 @@ pub async fn items(
         "Now playing: nothing".to_string()
     };
 
-    ctx.say(format!("{}\n\n{}", now_playing_message, queue_message)).await?;
+    let loop_mode = music::get_player_data(&player_context)?.get_loop_mode();
+
+    let loop_mode_message = format!("Loop: {}", loop_mode.name());
+
+    ctx.say(format!("{}\n{}\n\n{}", now_playing_message, loop_mode_message, queue_message)).await?;
 
     Ok(())
 }
@@ -6,6 +6,8 @@ use crate::Context;
 
 use crate::Error;
 
+use crate::common::helpers::time::{format_duration, parse_duration};
+
 //------------------------------------------------------------//
 
 /// Seek to a specific point in the current song.
@@ -23,10 +25,11 @@ use crate::Error;
 pub async fn seek(
     ctx: Context<'_>,
 
-    #[min = 0]
-    #[description = "Time to jump to (in seconds)"]
-    to: u64,
+    #[description = "Time to jump to, e.g. `90s`, `1m30s`, or `1:30`"]
+    to: String,
 ) -> Result<(), Error> {
+    let to = parse_duration(&to)?;
+
     ctx.defer().await?;
 
     let Some(guild_id) = ctx.guild_id() else {
@@ -55,9 +58,9 @@ pub async fn seek(
     let now_playing = player.get_player().await?.track;
 
     if now_playing.is_some() {
-        player.set_position(std::time::Duration::from_secs(to)).await?;
+        player.set_position(to).await?;
 
-        ctx.say(format!("Jumped to {}s", to)).await?;
+        ctx.say(format!("Jumped to {}", format_duration(to))).await?;
     } else {
         ctx.say("Nothing is playing").await?;
     }
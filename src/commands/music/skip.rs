@@ -6,6 +6,8 @@ use crate::Context;
 
 use crate::Error;
 
+use crate::common::music;
+
 //------------------------------------------------------------//
 
 /// Skip the current song and play the next one.
@@ -53,7 +55,7 @@ pub async fn skip(
     let now_playing = player.track;
 
     if let Some(track) = now_playing {
-        player_context.finish(true)?;
+        music::skip_track(&player_context, &track).await?;
 
         let message = if let Some(uri) = &track.info.uri {
             format!(
@@ -0,0 +1,94 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::serenity_prelude::{self as serenity, Mentionable, FormattedTimestamp, FormattedTimestampStyle};
+
+//------------------------------------------------------------//
+
+use crate::Context;
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::common::helpers::bot::create_escaped_code_block;
+
+use crate::common::message_cache;
+
+use crate::common::database::interfaces::guild_config::GuildConfig;
+
+//------------------------------------------------------------//
+
+/// Displays the most recently deleted message in this channel.
+#[
+    poise::command(
+        slash_command,
+        guild_only,
+        category = "Utility",
+        install_context = "Guild",
+        interaction_context = "Guild",
+        user_cooldown = "5", // in seconds
+    )
+]
+pub async fn snipe(
+    ctx: Context<'_>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    if !guild_config.get_message_retention_mode().await.is_snipe_enabled() {
+        return Err("Snipe is not enabled in this guild; it can be enabled using `/setup message_retention_mode`.".into());
+    }
+
+    let Some(cached_message) = message_cache::get_last_deleted_message(guild_id, ctx.channel_id()) else {
+        ctx.send(
+            poise::CreateReply::default()
+            .embed(
+                serenity::CreateEmbed::default()
+                .color(branding::color::PRIMARY)
+                .title("Snipe")
+                .description("There are no recently deleted messages in this channel.")
+            )
+        ).await?;
+
+        return Ok(());
+    };
+
+    let deleted_at = cached_message.deleted_at.expect("Sniped messages should have been deleted.");
+
+    let deleted_at_timestamp = serenity::Timestamp::from_millis(deleted_at.timestamp_millis())?;
+    let deleted_at_relative_format =
+        FormattedTimestamp::new(deleted_at_timestamp, Some(FormattedTimestampStyle::RelativeTime));
+
+    let content_string =
+        if cached_message.content.is_empty() {
+            "*No text content*".to_string()
+        } else {
+            create_escaped_code_block(None, &cached_message.content)
+        };
+
+    let mut description = format!(
+        "Sent by {}, deleted {}:\n{}",
+        cached_message.author_id.mention(),
+        deleted_at_relative_format,
+        content_string,
+    );
+
+    if !cached_message.attachment_urls.is_empty() {
+        description.push_str(&format!("\nAttachments:\n{}", cached_message.attachment_urls.join("\n")));
+    }
+
+    ctx.send(
+        poise::CreateReply::default()
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Snipe")
+            .description(description)
+        )
+    ).await?;
+
+    Ok(())
+}
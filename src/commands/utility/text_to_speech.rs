@@ -20,7 +20,7 @@ use crate::common::music;
 
 use crate::common::helpers::bot::create_escaped_code_block;
 
-use crate::commands::music::play::query_and_enqueue_track;
+use crate::commands::music::play::{query_and_enqueue_track, EnqueueOptions};
 
 //------------------------------------------------------------//
 
@@ -172,6 +172,7 @@ pub async fn text_to_speech(
         &player_context,
         guild_id,
         query,
+        EnqueueOptions::default(),
     ).await;
 
     if let Err(why) = result {
@@ -8,8 +8,16 @@ pub mod ai {
     pub mod user_ai_usage;
 }
 
+pub mod anti_raid;
+
+pub mod automod;
+
 pub mod branding;
 
+pub mod command_access;
+
+pub mod command_cooldowns;
+
 pub mod entitlements;
 
 pub mod helpers {
@@ -22,16 +30,30 @@ pub mod helpers {
     pub mod time;
 }
 
+pub mod mass_moderation;
+
+pub mod message_cache;
+
 pub mod moderation;
 
+pub mod moderation_appeals;
+
 pub mod music;
 
+pub mod scheduler;
+
+pub mod spam_protection;
+
 pub mod database {
     pub mod adapter;
 
     pub mod interfaces {
         pub mod guild_config;
 
+        pub mod moderation_case;
+
+        pub mod scheduled_job;
+
         pub mod user_config;
     }
 }
@@ -0,0 +1,231 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use std::sync::{LazyLock, Mutex};
+
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::common::moderation;
+
+use crate::common::scheduler::{self, ScheduledJobPayload};
+
+use crate::common::database::interfaces::guild_config::{
+    GuildConfig,
+    GuildConfigAntiRaid,
+    GuildConfigRaidMode,
+    GuildConfigSlowedChannel,
+};
+
+use crate::commands::moderation::slowmode::set_channel_slowmode;
+
+//------------------------------------------------------------//
+
+/// Recent join times per guild, used to detect join raids.
+static RECENT_JOINS: LazyLock<Mutex<HashMap<serenity::GuildId, VecDeque<chrono::DateTime<chrono::Utc>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Guilds that are currently starting raid mode, so that a burst of joins only starts it once.
+static RAID_MODE_STARTING_GUILDS: LazyLock<Mutex<HashSet<serenity::GuildId>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+//------------------------------------------------------------//
+
+/// Records a member joining a guild and returns how many members joined within the window.
+pub fn record_member_join(
+    guild_id: serenity::GuildId,
+    window_seconds: u32,
+) -> u32 {
+    let mut recent_joins = RECENT_JOINS.lock().expect("recent joins lock should not be poisoned");
+
+    let now = chrono::Utc::now();
+    let cutoff = now - chrono::Duration::seconds(window_seconds as i64);
+
+    // forget guilds that have not had any recent joins
+    recent_joins.retain(|_, guild_joins| guild_joins.back().is_some_and(|joined_at| *joined_at > cutoff));
+
+    let guild_joins = recent_joins.entry(guild_id).or_default();
+
+    guild_joins.retain(|joined_at| *joined_at > cutoff);
+
+    guild_joins.push_back(now);
+
+    guild_joins.len() as u32
+}
+
+//------------------------------------------------------------//
+
+/// Starts raid mode for a guild: raises slowmode across text channels and alerts the moderators.
+/// Raid mode automatically ends after the configured duration.
+pub async fn start_raid_mode(
+    http: &serenity::Http,
+    guild_id: serenity::GuildId,
+    anti_raid: &GuildConfigAntiRaid,
+    join_count: u32,
+) -> Result<(), Error> {
+    let is_already_starting = !RAID_MODE_STARTING_GUILDS.lock().expect("raid mode lock should not be poisoned").insert(guild_id);
+
+    if is_already_starting {
+        return Ok(());
+    }
+
+    let result = start_raid_mode_inner(http, guild_id, anti_raid, join_count).await;
+
+    RAID_MODE_STARTING_GUILDS.lock().expect("raid mode lock should not be poisoned").remove(&guild_id);
+
+    result
+}
+
+async fn start_raid_mode_inner(
+    http: &serenity::Http,
+    guild_id: serenity::GuildId,
+    anti_raid: &GuildConfigAntiRaid,
+    join_count: u32,
+) -> Result<(), Error> {
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    if guild_config.get_raid_mode().await.is_some() {
+        return Ok(());
+    }
+
+    let reason = format!("Anti-raid: {} members joined within {} seconds.", join_count, anti_raid.join_window_seconds);
+
+    let guild_channels = guild_id.channels(http).await?;
+
+    let mut slowed_channels = Vec::new();
+
+    for mut guild_channel in guild_channels {
+        if guild_channel.base.kind != serenity::ChannelType::Text {
+            continue;
+        }
+
+        let previous_rate_limit_per_user =
+            guild_channel.base.rate_limit_per_user
+            .map(|rate_limit| rate_limit.get())
+            .unwrap_or(0);
+
+        // never lower an existing slowmode
+        if previous_rate_limit_per_user >= anti_raid.raid_slowmode_seconds {
+            continue;
+        }
+
+        // a channel that cannot be slowed should not prevent slowing the others
+        if let Err(why) = set_channel_slowmode(http, &mut guild_channel, anti_raid.raid_slowmode_seconds, &reason).await {
+            eprintln!("Failed to raise slowmode in channel {}: {:?}", guild_channel.id, why);
+
+            continue;
+        }
+
+        slowed_channels.push(
+            GuildConfigSlowedChannel {
+                channel_id: guild_channel.id,
+                previous_rate_limit_per_user: previous_rate_limit_per_user,
+            }
+        );
+    }
+
+    let slowed_channel_count = slowed_channels.len();
+
+    guild_config.set_raid_mode(
+        Some(
+            GuildConfigRaidMode {
+                started_at: chrono::Utc::now(),
+                slowed_channels: slowed_channels,
+            }
+        )
+    ).await?;
+
+    let ends_at = chrono::Utc::now() + chrono::Duration::minutes(anti_raid.raid_duration_minutes as i64);
+
+    scheduler::schedule_job(
+        ScheduledJobPayload::EndRaidMode {
+            discord_guild_id: guild_id,
+        },
+        ends_at,
+        Some(scheduler::create_end_raid_mode_dedupe_key(guild_id)),
+    ).await?;
+
+    let ends_at_timestamp = serenity::Timestamp::from_unix_timestamp(ends_at.timestamp())?;
+
+    moderation::send_moderation_actions_log_embed(
+        http,
+        guild_id,
+        serenity::CreateEmbed::default()
+        .color(branding::color::PRIMARY)
+        .title("Moderation - Raid Mode Started")
+        .description(
+            format!(
+                "{}\n\nRaised slowmode to {} seconds in {} channels.\nNew accounts younger than {} days will be actioned on join.\nRaid mode ends {}, or when `/lockdown end` is used.",
+                reason,
+                anti_raid.raid_slowmode_seconds,
+                slowed_channel_count,
+                anti_raid.min_account_age_days,
+                serenity::FormattedTimestamp::new(ends_at_timestamp, Some(serenity::FormattedTimestampStyle::RelativeTime)),
+            )
+        )
+    ).await?;
+
+    Ok(())
+}
+
+/// Ends raid mode for a guild, restoring the slowmode of each channel that was slowed.
+/// Returns whether raid mode was active.
+pub async fn end_raid_mode(
+    http: &serenity::Http,
+    guild_id: serenity::GuildId,
+) -> Result<bool, Error> {
+    let Some(guild_config) = GuildConfig::fetch(guild_id).await? else {
+        return Ok(false);
+    };
+
+    let Some(raid_mode) = guild_config.get_raid_mode().await else {
+        return Ok(false);
+    };
+
+    let reason = "Anti-raid: raid mode ended.";
+
+    let guild_channels = guild_id.channels(http).await?;
+
+    for mut guild_channel in guild_channels {
+        let Some(slowed_channel) =
+            raid_mode.slowed_channels
+            .iter()
+            .find(|slowed_channel| slowed_channel.channel_id == guild_channel.id)
+        else {
+            continue;
+        };
+
+        // a channel that cannot be restored should not prevent restoring the others
+        if let Err(why) = set_channel_slowmode(http, &mut guild_channel, slowed_channel.previous_rate_limit_per_user, reason).await {
+            eprintln!("Failed to restore slowmode in channel {}: {:?}", guild_channel.id, why);
+        }
+    }
+
+    guild_config.set_raid_mode(None).await?;
+
+    scheduler::cancel_job(&scheduler::create_end_raid_mode_dedupe_key(guild_id)).await?;
+
+    moderation::send_moderation_actions_log_embed(
+        http,
+        guild_id,
+        serenity::CreateEmbed::default()
+        .color(branding::color::PRIMARY)
+        .title("Moderation - Raid Mode Ended")
+        .description(
+            format!(
+                "Restored slowmode in {} channels.",
+                raid_mode.slowed_channels.len(),
+            )
+        )
+    ).await?;
+
+    Ok(true)
+}
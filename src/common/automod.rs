@@ -0,0 +1,335 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use std::collections::{HashMap, VecDeque};
+
+use std::sync::{Arc, LazyLock, Mutex};
+
+use regex::{Regex, RegexSet};
+
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::common::database::interfaces::guild_config::{
+    GuildConfigAutomod,
+    GuildConfigAutomodRule,
+    GuildConfigAutomodRuleKind,
+};
+
+//------------------------------------------------------------//
+
+const DEFAULT_MASS_MENTIONS_THRESHOLD: u32 = 5;
+
+const DEFAULT_EXCESSIVE_CAPS_PERCENTAGE_THRESHOLD: u32 = 70;
+
+/// Short messages (e.g. "OK") are not considered for excessive caps.
+const EXCESSIVE_CAPS_MIN_LETTERS: usize = 10;
+
+const DEFAULT_REPEATED_MESSAGES_THRESHOLD: u32 = 3;
+
+/// Identical messages sent within this window count as repeats.
+const REPEATED_MESSAGES_WINDOW_SECONDS: i64 = 60;
+
+/// Beyond this many tracked members, members without recent messages are forgotten.
+const RECENT_MESSAGES_MAX_TRACKED_MEMBERS: usize = 10_000;
+
+const DEFAULT_ZALGO_COMBINING_CHARACTERS_THRESHOLD: u32 = 10;
+
+//------------------------------------------------------------//
+
+static INVITE_LINK_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(discord\.gg|discord(app)?\.com/invite)/[\w-]+").expect("invite link regex should be valid")
+});
+
+static LINK_DOMAIN_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)https?://([^/\s:?#]+)").expect("link domain regex should be valid")
+});
+
+/// The compiled banned patterns of each guild, along with the patterns they were compiled from.
+/// Entries are rebuilt whenever a guild's patterns no longer match the ones that were compiled.
+static BANNED_PATTERN_SETS: LazyLock<Mutex<HashMap<serenity::GuildId, (Vec<String>, Arc<RegexSet>)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Recent message contents per guild member, used to detect repeated messages.
+static RECENT_MESSAGES: LazyLock<Mutex<HashMap<(serenity::GuildId, serenity::UserId), VecDeque<(String, chrono::DateTime<chrono::Utc>)>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+//------------------------------------------------------------//
+
+/// Records a message and returns how many times the same content was recently sent by its author.
+fn record_and_count_repeated_message(
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+    content: &str,
+) -> u32 {
+    let mut recent_messages = RECENT_MESSAGES.lock().expect("recent messages lock should not be poisoned");
+
+    let now = chrono::Utc::now();
+    let cutoff = now - chrono::Duration::seconds(REPEATED_MESSAGES_WINDOW_SECONDS);
+
+    // occasionally forget members that have not sent anything recently
+    if recent_messages.len() > RECENT_MESSAGES_MAX_TRACKED_MEMBERS {
+        recent_messages.retain(|_, member_messages| member_messages.back().is_some_and(|(_, sent_at)| *sent_at > cutoff));
+    }
+
+    let member_messages = recent_messages.entry((guild_id, user_id)).or_default();
+
+    member_messages.retain(|(_, sent_at)| *sent_at > cutoff);
+
+    let normalized_content = content.trim().to_lowercase();
+
+    // messages without content (e.g. only attachments or stickers) are not considered repeats
+    if normalized_content.is_empty() {
+        return 0;
+    }
+
+    member_messages.push_back((normalized_content.clone(), now));
+
+    member_messages.iter().filter(|(recent_content, _)| *recent_content == normalized_content).count() as u32
+}
+
+/// Returns the compiled banned patterns of a guild, compiling them if they changed since they were last used.
+/// Patterns are validated when configured, so any invalid ones are reported and left out.
+/// If the valid patterns can't be compiled together, they are reported and nothing is matched.
+fn get_banned_pattern_set(
+    guild_id: serenity::GuildId,
+    patterns: &[String],
+) -> Arc<RegexSet> {
+    let mut banned_pattern_sets = BANNED_PATTERN_SETS.lock().expect("banned pattern sets lock should not be poisoned");
+
+    if let Some((compiled_patterns, banned_pattern_set)) = banned_pattern_sets.get(&guild_id) {
+        if compiled_patterns.as_slice() == patterns {
+            return Arc::clone(banned_pattern_set);
+        }
+    }
+
+    let valid_patterns =
+        patterns
+        .iter()
+        .filter(|pattern| {
+            let is_valid = Regex::new(pattern).is_ok();
+
+            if !is_valid {
+                eprintln!("Ignoring invalid automod pattern in guild {}: {}", guild_id, pattern);
+            }
+
+            is_valid
+        })
+        .collect::<Vec<&String>>();
+
+    // patterns that compile on their own may still exceed the size limit when combined
+    let banned_pattern_set = match RegexSet::new(valid_patterns) {
+        Ok(banned_pattern_set) => Arc::new(banned_pattern_set),
+        Err(why) => {
+            eprintln!("Ignoring automod patterns in guild {} that failed to compile together: {:?}", guild_id, why);
+
+            Arc::new(RegexSet::empty())
+        },
+    };
+
+    banned_pattern_sets.insert(guild_id, (patterns.to_vec(), Arc::clone(&banned_pattern_set)));
+
+    banned_pattern_set
+}
+
+fn is_combining_character(
+    character: char,
+) -> bool {
+    matches!(
+        character as u32,
+        0x0300..=0x036F | 0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x20D0..=0x20FF | 0xFE20..=0xFE2F
+    )
+}
+
+/// Whether a domain is, or is a subdomain of, one of the listed domains.
+fn is_domain_listed(
+    domain: &str,
+    listed_domains: &[String],
+) -> bool {
+    listed_domains.iter().any(|listed_domain| {
+        let listed_domain = listed_domain.trim().trim_start_matches("www.").to_lowercase();
+
+        domain == listed_domain || domain.ends_with(&format!(".{}", listed_domain))
+    })
+}
+
+fn get_link_domains(
+    content: &str,
+) -> Vec<String> {
+    LINK_DOMAIN_REGEX
+    .captures_iter(content)
+    .filter_map(|captures| captures.get(1))
+    .map(|domain| domain.as_str().trim_start_matches("www.").to_lowercase())
+    .collect()
+}
+
+//------------------------------------------------------------//
+
+/// Checks a message against a single rule, returning why it was violated (if it was).
+fn check_rule(
+    rule: &GuildConfigAutomodRule,
+    guild_id: serenity::GuildId,
+    message: &serenity::Message,
+    repeated_message_count: u32,
+) -> Option<String> {
+    let content = message.content.as_str();
+
+    match rule.kind {
+        GuildConfigAutomodRuleKind::BannedWords => {
+            let lowercase_content = content.to_lowercase();
+
+            let words: Vec<&str> =
+                lowercase_content
+                .split(|character: char| !character.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .collect();
+
+            let banned_word = rule.values.iter().find(|banned_word| {
+                let banned_word = banned_word.trim().to_lowercase();
+
+                // phrases are matched anywhere, single words are matched as whole words
+                if banned_word.contains(char::is_whitespace) {
+                    lowercase_content.contains(&banned_word)
+                } else {
+                    words.contains(&banned_word.as_str())
+                }
+            })?;
+
+            Some(format!("Contained a banned word or phrase (`{}`).", banned_word))
+        },
+        GuildConfigAutomodRuleKind::BannedPatterns => {
+            let banned_pattern_set = get_banned_pattern_set(guild_id, &rule.values);
+
+            let banned_pattern_index = banned_pattern_set.matches(content).into_iter().next()?;
+
+            Some(format!("Matched a banned pattern (`{}`).", banned_pattern_set.patterns()[banned_pattern_index]))
+        },
+        GuildConfigAutomodRuleKind::InviteLinks => {
+            INVITE_LINK_REGEX.is_match(content).then(|| "Contained an invite link.".to_string())
+        },
+        GuildConfigAutomodRuleKind::MassMentions => {
+            let threshold = rule.threshold.unwrap_or(DEFAULT_MASS_MENTIONS_THRESHOLD);
+
+            let mention_count =
+                message.mentions.len() +
+                message.mention_roles.len() +
+                if message.mention_everyone { 1 } else { 0 };
+
+            (mention_count as u32 >= threshold).then(|| format!("Contained {} mentions.", mention_count))
+        },
+        GuildConfigAutomodRuleKind::ExcessiveCaps => {
+            let threshold = rule.threshold.unwrap_or(DEFAULT_EXCESSIVE_CAPS_PERCENTAGE_THRESHOLD);
+
+            let letters: Vec<char> = content.chars().filter(|character| character.is_alphabetic()).collect();
+
+            if letters.len() < EXCESSIVE_CAPS_MIN_LETTERS {
+                return None;
+            }
+
+            let uppercase_count = letters.iter().filter(|character| character.is_uppercase()).count();
+            let uppercase_percentage = (uppercase_count * 100 / letters.len()) as u32;
+
+            (uppercase_percentage >= threshold).then(|| format!("Contained {}% capital letters.", uppercase_percentage))
+        },
+        GuildConfigAutomodRuleKind::RepeatedMessages => {
+            let threshold = rule.threshold.unwrap_or(DEFAULT_REPEATED_MESSAGES_THRESHOLD);
+
+            (repeated_message_count >= threshold).then(|| format!("Sent the same message {} times.", repeated_message_count))
+        },
+        GuildConfigAutomodRuleKind::ZalgoText => {
+            let threshold = rule.threshold.unwrap_or(DEFAULT_ZALGO_COMBINING_CHARACTERS_THRESHOLD);
+
+            let combining_count = content.chars().filter(|character| is_combining_character(*character)).count();
+
+            (combining_count as u32 >= threshold).then(|| "Contained zalgo text.".to_string())
+        },
+        GuildConfigAutomodRuleKind::LinkDomainAllowList => {
+            let disallowed_domain =
+                get_link_domains(content)
+                .into_iter()
+                .find(|domain| !is_domain_listed(domain, &rule.values))?;
+
+            Some(format!("Contained a link to a domain that is not allowed (`{}`).", disallowed_domain))
+        },
+        GuildConfigAutomodRuleKind::LinkDomainDenyList => {
+            let denied_domain =
+                get_link_domains(content)
+                .into_iter()
+                .find(|domain| is_domain_listed(domain, &rule.values))?;
+
+            Some(format!("Contained a link to a denied domain (`{}`).", denied_domain))
+        },
+    }
+}
+
+/// Describes a violation of a rule without repeating what was matched (e.g. the banned word),
+/// for notices that everyone in the channel can see.
+pub fn get_public_violation_description(
+    rule: &GuildConfigAutomodRule,
+) -> &'static str {
+    match rule.kind {
+        GuildConfigAutomodRuleKind::BannedWords => "Contained a banned word or phrase.",
+        GuildConfigAutomodRuleKind::BannedPatterns => "Matched a banned pattern.",
+        GuildConfigAutomodRuleKind::InviteLinks => "Contained an invite link.",
+        GuildConfigAutomodRuleKind::MassMentions => "Contained too many mentions.",
+        GuildConfigAutomodRuleKind::ExcessiveCaps => "Contained too many capital letters.",
+        GuildConfigAutomodRuleKind::RepeatedMessages => "Sent the same message too many times.",
+        GuildConfigAutomodRuleKind::ZalgoText => "Contained zalgo text.",
+        GuildConfigAutomodRuleKind::LinkDomainAllowList => "Contained a link to a domain that is not allowed.",
+        GuildConfigAutomodRuleKind::LinkDomainDenyList => "Contained a link to a denied domain.",
+    }
+}
+
+/// Whether a message is exempt from automod and spam protection.
+/// Messages in exempt channels or by members with exempt roles are exempt,
+/// as are messages by the guild owner and members that may manage messages.
+pub fn is_message_exempt(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    automod: &GuildConfigAutomod,
+    message: &serenity::Message,
+) -> bool {
+    if automod.exempt_channel_ids.contains(&message.channel_id) {
+        return true;
+    }
+
+    let Some(member) = &message.member else {
+        return false;
+    };
+
+    if member.roles.iter().any(|role_id| automod.exempt_role_ids.contains(role_id)) {
+        return true;
+    }
+
+    let Some(guild) = ctx.cache.guild(guild_id) else {
+        return false; // permissions can't be determined without the cached guild
+    };
+
+    if guild.owner_id == message.author.id {
+        return true;
+    }
+
+    let member_permissions =
+        std::iter::once(guild_id.everyone_role())
+        .chain(member.roles.iter().copied())
+        .filter_map(|role_id| guild.roles.get(&role_id))
+        .fold(serenity::Permissions::empty(), |permissions, role| permissions | role.permissions);
+
+    member_permissions.administrator() || member_permissions.manage_messages()
+}
+
+/// Checks a message against every automod rule, returning the first violated rule and why.
+/// Exemptions are expected to be checked by the caller, see `is_message_exempt`.
+pub fn find_violated_rule<'a>(
+    automod: &'a GuildConfigAutomod,
+    guild_id: serenity::GuildId,
+    message: &serenity::Message,
+) -> Option<(&'a GuildConfigAutomodRule, String)> {
+    let repeated_message_count = record_and_count_repeated_message(guild_id, message.author.id, &message.content);
+
+    automod.rules.iter().find_map(|rule| {
+        check_rule(rule, guild_id, message, repeated_message_count).map(|violation| (rule, violation))
+    })
+}
@@ -0,0 +1,156 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::serenity_prelude::{self as serenity, Mentionable};
+
+//------------------------------------------------------------//
+
+use crate::Context;
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::common::command_cooldowns::check_command_cooldowns;
+
+use crate::common::database::interfaces::guild_config::GuildConfig;
+
+//------------------------------------------------------------//
+
+/// Commands that can never be disabled or restricted, so that a guild cannot lock itself out.
+const ALWAYS_ACCESSIBLE_COMMANDS: &[&str] = &["setup", "help"];
+
+/// Marks an invocation that has already passed the command checks.
+struct CommandInvocationChecked;
+
+//------------------------------------------------------------//
+
+/// Determines why the author of this context may not use the command, if they may not.
+async fn find_command_access_denial(
+    ctx: Context<'_>,
+) -> Result<Option<String>, Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(None); // restrictions only apply in guilds
+    };
+
+    // restrictions are configured on top-level commands (and their categories)
+    let root_command = ctx.parent_commands().first().copied().unwrap_or(ctx.command());
+
+    let command_name = root_command.name.to_lowercase();
+    let command_category = root_command.category.as_deref();
+
+    if ALWAYS_ACCESSIBLE_COMMANDS.contains(&command_name.as_str()) {
+        return Ok(None);
+    }
+
+    // attempt to fetch the guild config, if it doesn't exist, there is nothing to enforce
+    let Some(guild_config) = GuildConfig::fetch(guild_id).await? else {
+        return Ok(None);
+    };
+
+    let command_access = guild_config.get_command_access().await;
+
+    if !command_access.is_command_enabled(&command_name, command_category) {
+        return Ok(Some("This command is disabled in this guild.".to_string()));
+    }
+
+    // guild administrators are not subject to channel and role restrictions
+    let is_administrator =
+        ctx.interaction.member.as_ref()
+        .and_then(|member| member.permissions)
+        .is_some_and(|permissions| permissions.administrator());
+
+    if is_administrator {
+        return Ok(None);
+    }
+
+    let member_role_ids =
+        ctx.author_member().await
+        .map(|member| member.roles.to_vec())
+        .unwrap_or_default();
+
+    for restriction in command_access.find_restrictions(&command_name, command_category) {
+        if
+            !restriction.allowed_channel_ids.is_empty() &&
+            !restriction.allowed_channel_ids.contains(&ctx.channel_id())
+        {
+            let channels_string =
+                restriction.allowed_channel_ids
+                .iter()
+                .map(|channel_id| channel_id.mention().to_string())
+                .collect::<Vec<String>>()
+                .join(", ");
+
+            return Ok(Some(format!("This command can only be used in: {}", channels_string)));
+        }
+
+        if
+            !restriction.allowed_role_ids.is_empty() &&
+            !restriction.allowed_role_ids.iter().any(|role_id| member_role_ids.contains(role_id))
+        {
+            let roles_string =
+                restriction.allowed_role_ids
+                .iter()
+                .map(|role_id| role_id.mention().to_string())
+                .collect::<Vec<String>>()
+                .join(", ");
+
+            return Ok(Some(format!("This command can only be used by members with: {}", roles_string)));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Enforces the guild's command access configuration.
+/// When access is denied, the author is told why.
+pub async fn check_command_access(
+    ctx: Context<'_>,
+) -> Result<bool, Error> {
+    let Some(denial) = find_command_access_denial(ctx).await? else {
+        return Ok(true);
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+        .ephemeral(true)
+        .embed(
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Command Unavailable")
+            .description(denial)
+        )
+    ).await?;
+
+    Ok(false)
+}
+
+/// Performs every check that must pass before a command is invoked, intended to be used as the framework's command check.
+pub async fn check_command_invocation(
+    ctx: Context<'_>,
+) -> Result<bool, Error> {
+    // the framework also runs its command check for autocomplete interactions, which can't be
+    // responded to with a message, and which must not start cooldowns for the actual invocation
+    if matches!(ctx.interaction_type, poise::CommandInteractionType::Autocomplete) {
+        return Ok(true);
+    }
+
+    // the framework runs its command check once for each parent command as well,
+    // but the checks (and starting cooldowns) should only happen once per invocation
+    if ctx.invocation_data::<CommandInvocationChecked>().await.is_some() {
+        return Ok(true);
+    }
+
+    if !check_command_access(ctx).await? {
+        return Ok(false);
+    }
+
+    if !check_command_cooldowns(ctx).await? {
+        return Ok(false);
+    }
+
+    ctx.set_invocation_data(CommandInvocationChecked).await;
+
+    Ok(true)
+}
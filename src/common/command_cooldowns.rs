@@ -0,0 +1,124 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::Context;
+
+use crate::Data;
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::common::helpers::time::format_duration;
+
+use crate::common::database::interfaces::guild_config::{GuildConfig, GuildConfigCooldownOverride};
+
+//------------------------------------------------------------//
+
+/// Applies a guild's override to the cooldowns declared on a command.
+/// Only the user, channel and guild cooldowns are overridden, so that the bot-wide global cooldown still applies.
+fn create_cooldown_config(
+    declared_cooldown_config: &poise::CooldownConfig,
+    cooldown_override: &GuildConfigCooldownOverride,
+) -> poise::CooldownConfig {
+    let to_duration = |seconds: Option<u32>| seconds.map(|seconds| std::time::Duration::from_secs(seconds as u64));
+
+    poise::CooldownConfig {
+        user: to_duration(cooldown_override.user_seconds),
+        channel: to_duration(cooldown_override.channel_seconds),
+        guild: to_duration(cooldown_override.guild_seconds),
+        ..declared_cooldown_config.clone()
+    }
+}
+
+/// Enforces command cooldowns, preferring the guild's overrides over the cooldowns declared on each command.
+/// Requires the framework's `manual_cooldowns` option, otherwise the declared cooldowns are also enforced.
+/// When a cooldown is active, the author is told how long to wait.
+pub async fn check_command_cooldowns(
+    ctx: Context<'_>,
+) -> Result<bool, Error> {
+    let guild_cooldowns = match ctx.guild_id() {
+        Some(guild_id) => match GuildConfig::fetch(guild_id).await? {
+            Some(guild_config) => Some(guild_config.get_cooldowns().await),
+            None => None,
+        },
+        None => None,
+    };
+
+    if let Some(guild_cooldowns) = &guild_cooldowns {
+        if !guild_cooldowns.bypass_role_ids.is_empty() {
+            let member_role_ids =
+                ctx.author_member().await
+                .map(|member| member.roles.to_vec())
+                .unwrap_or_default();
+
+            let has_bypass_role = guild_cooldowns.bypass_role_ids.iter().any(|role_id| member_role_ids.contains(role_id));
+
+            if has_bypass_role {
+                return Ok(true);
+            }
+        }
+    }
+
+    // cooldowns of parent commands also apply to their subcommands
+    let commands: Vec<&poise::Command<Data, Error>> =
+        ctx.parent_commands()
+        .iter()
+        .copied()
+        .chain(std::iter::once(ctx.command()))
+        .collect();
+
+    for command in &commands {
+        let cooldown_override =
+            guild_cooldowns.as_ref()
+            .and_then(|guild_cooldowns| guild_cooldowns.find_override(&command.qualified_name));
+
+        let declared_cooldown_config = command.cooldown_config.read().expect("cooldown config lock should not be poisoned").clone();
+
+        let cooldown_config = match cooldown_override {
+            Some(cooldown_override) => create_cooldown_config(&declared_cooldown_config, cooldown_override),
+            None => declared_cooldown_config,
+        };
+
+        let remaining_cooldown =
+            command.cooldowns.lock().expect("cooldowns lock should not be poisoned")
+            .remaining_cooldown(ctx.cooldown_context(), &cooldown_config);
+
+        let Some(remaining_cooldown) = remaining_cooldown else {
+            continue;
+        };
+
+        // round up, so that "try again in 0s" is never shown
+        let remaining_seconds = remaining_cooldown.as_secs() + 1;
+
+        ctx.send(
+            poise::CreateReply::default()
+            .ephemeral(true)
+            .embed(
+                serenity::CreateEmbed::default()
+                .color(branding::color::PRIMARY)
+                .title("Slow Down")
+                .description(
+                    format!(
+                        "You're using `/{}` too quickly, try again in {}.",
+                        ctx.command().qualified_name,
+                        format_duration(std::time::Duration::from_secs(remaining_seconds)),
+                    )
+                )
+            )
+        ).await?;
+
+        return Ok(false);
+    }
+
+    for command in &commands {
+        command.cooldowns.lock().expect("cooldowns lock should not be poisoned").start_cooldown(ctx.cooldown_context());
+    }
+
+    Ok(true)
+}
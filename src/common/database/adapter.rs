@@ -37,6 +37,16 @@ pub fn get_users_collection_name() -> String {
     .expect("MONGODB_USERS_COLLECTION_NAME must be set")
 }
 
+pub fn get_moderation_cases_collection_name() -> String {
+    std::env::var("MONGODB_MODERATION_CASES_COLLECTION_NAME")
+    .expect("MONGODB_MODERATION_CASES_COLLECTION_NAME must be set")
+}
+
+pub fn get_scheduled_jobs_collection_name() -> String {
+    std::env::var("MONGODB_SCHEDULED_JOBS_COLLECTION_NAME")
+    .expect("MONGODB_SCHEDULED_JOBS_COLLECTION_NAME must be set")
+}
+
 //------------------------------------------------------------//
 
 static CLIENT: OnceCell<mongodb::Client> = OnceCell::const_new();
@@ -86,6 +96,34 @@ impl CollectionHelper {
         cursor.next().await.transpose()
     }
 
+    pub async fn get_many<Item> (
+        &self,
+        filter: mongodb::bson::Document,
+        sort: Option<mongodb::bson::Document>,
+        limit: Option<i64>,
+    ) -> Result<Vec<Item>, mongodb::error::Error>
+    where
+        Item: serde::de::DeserializeOwned + Unpin + Send + Sync,
+    {
+        let client = get_client().await;
+        let db = client.database(&self.database_name);
+        let collection = db.collection::<Item>(&self.collection_name);
+
+        let mut find_action = collection.find(filter);
+
+        if let Some(sort) = sort {
+            find_action = find_action.sort(sort);
+        }
+
+        if let Some(limit) = limit {
+            find_action = find_action.limit(limit);
+        }
+
+        let cursor = find_action.await?;
+
+        cursor.collect::<Vec<_>>().await.into_iter().collect()
+    }
+
     pub async fn set<Item>(
         &self,
         item: Item,
@@ -119,6 +157,25 @@ impl CollectionHelper {
         Ok(())
     }
 
+    /// Atomically updates a single item and returns it as it is after the update.
+    pub async fn find_one_and_update<Item>(
+        &self,
+        filter: mongodb::bson::Document,
+        update_document: mongodb::bson::Document,
+    ) -> Result<Option<Item>, mongodb::error::Error>
+    where
+        Item: serde::de::DeserializeOwned + serde::Serialize + Send + Sync,
+    {
+        let client = get_client().await;
+        let db = client.database(&self.database_name);
+        let collection = db.collection::<Item>(&self.collection_name);
+
+        collection
+        .find_one_and_update(filter, update_document)
+        .return_document(mongodb::options::ReturnDocument::After)
+        .await
+    }
+
     pub async fn delete<Item>(
         &self,
         filter: mongodb::bson::Document,
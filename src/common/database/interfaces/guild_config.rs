@@ -20,7 +20,7 @@ use crate::common::database::adapter::CollectionHelper;
 
 //------------------------------------------------------------//
 
-#[derive(Default, Debug, Deserialize, Serialize)]
+#[derive(Default, Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
 pub enum GuildConfigModerationMode {
     #[default]
     #[serde(rename = "0")]
@@ -43,6 +43,74 @@ impl GuildConfigModerationMode {
 
 //------------------------------------------------------------//
 
+/// A moderation capability that can be granted to roles.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum GuildConfigModerationCapability {
+    #[serde(rename = "0")]
+    Warn,
+
+    #[serde(rename = "1")]
+    Timeout,
+
+    #[serde(rename = "2")]
+    Kick,
+
+    #[serde(rename = "3")]
+    Ban,
+
+    #[serde(rename = "4")]
+    Purge,
+
+    /// Muting, deafening, disconnecting, and moving members in voice channels.
+    #[serde(rename = "5")]
+    Voice,
+
+    /// Slowmode and lockdowns.
+    #[serde(rename = "6")]
+    Channels,
+
+    /// Viewing and editing moderation cases.
+    #[serde(rename = "7")]
+    Cases,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GuildConfigModerationRole {
+    pub role_id: serenity::RoleId,
+
+    #[serde(default)]
+    pub capabilities: Vec<GuildConfigModerationCapability>,
+}
+
+#[derive(Default, Debug, Deserialize, Serialize, Clone)]
+pub struct GuildConfigModerationRoles {
+    #[serde(default)]
+    pub roles: Vec<GuildConfigModerationRole>,
+}
+
+impl GuildConfigModerationRoles {
+    /// Whether any of the specified roles have been granted the capability.
+    pub fn is_capability_granted(
+        &self,
+        role_ids: &[serenity::RoleId],
+        capability: GuildConfigModerationCapability,
+    ) -> bool {
+        self.roles.iter().any(|role| {
+            role_ids.contains(&role.role_id) && role.capabilities.contains(&capability)
+        })
+    }
+
+    /// Whether the capability has been granted to any role at all.
+    pub fn is_capability_granted_to_any_role(
+        &self,
+        capability: GuildConfigModerationCapability,
+    ) -> bool {
+        self.roles.iter().any(|role| role.capabilities.contains(&capability))
+    }
+}
+
+//------------------------------------------------------------//
+
 #[derive(Default, Debug, Deserialize, Serialize, Clone)]
 pub enum GuildConfigAiChatMode {
     #[default]
@@ -56,38 +124,677 @@ pub enum GuildConfigAiChatMode {
     AiChatChannelsAlwaysRespond,
 }
 
-impl GuildConfigAiChatMode {
-    pub fn should_respond_in_channel(
+impl GuildConfigAiChatMode {
+    pub fn should_respond_in_channel(
+        &self,
+        guild_config: &GuildConfig,
+        channel_id: serenity::GenericChannelId,
+        was_mentioned: bool,
+    ) -> bool {
+        let is_ai_chat_channel = guild_config.ai_chat_channels.contains(&channel_id);
+
+        match self {
+            GuildConfigAiChatMode::Disabled => false,
+            GuildConfigAiChatMode::AiChatChannelsRequireMention => {
+                was_mentioned && is_ai_chat_channel
+            },
+            GuildConfigAiChatMode::AiChatChannelsAlwaysRespond => {
+                was_mentioned || is_ai_chat_channel
+            },
+        }
+    }
+}
+
+type GuildConfigAiChatChannels = Vec<serenity::GenericChannelId>;
+
+//------------------------------------------------------------//
+
+#[derive(Default, Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum GuildConfigMessageRetentionMode {
+    #[serde(rename = "0")]
+    Disabled,
+
+    #[default]
+    #[serde(rename = "1")]
+    LoggingOnly,
+
+    #[serde(rename = "2")]
+    LoggingAndSnipe,
+}
+
+impl GuildConfigMessageRetentionMode {
+    pub fn is_snipe_enabled(
+        &self,
+    ) -> bool {
+        match self {
+            GuildConfigMessageRetentionMode::Disabled => false,
+            GuildConfigMessageRetentionMode::LoggingOnly => false,
+            GuildConfigMessageRetentionMode::LoggingAndSnipe => true,
+        }
+    }
+
+    /// Messages are only retained when something in the guild will make use of them.
+    pub fn should_retain_messages(
+        &self,
+        logging_channels: &GuildConfigLoggingChannels,
+    ) -> bool {
+        let is_logging_messages =
+            logging_channels.message_edit.is_some() ||
+            logging_channels.message_delete.is_some();
+
+        match self {
+            GuildConfigMessageRetentionMode::Disabled => false,
+            GuildConfigMessageRetentionMode::LoggingOnly => is_logging_messages,
+            GuildConfigMessageRetentionMode::LoggingAndSnipe => true,
+        }
+    }
+}
+
+//------------------------------------------------------------//
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuildConfigLoggingChannelKind {
+    GuildMemberJoin,
+    GuildMemberLeave,
+    MessageEdit,
+    MessageDelete,
+    MessageBulkDelete,
+    MemberRoleUpdate,
+    MemberNicknameUpdate,
+    VoiceActivity,
+    ChannelCreate,
+    ChannelDelete,
+    GuildBanAdd,
+    GuildBanRemove,
+    ModerationActions,
+    ModerationAppeals,
+}
+
+#[derive(Default, Debug, Deserialize, Serialize, Clone)]
+pub struct GuildConfigLoggingChannels {
+    #[serde(default)]
+    pub guild_member_join: Option<serenity::GenericChannelId>,
+
+    #[serde(default)]
+    pub guild_member_leave: Option<serenity::GenericChannelId>,
+
+    #[serde(default)]
+    pub message_edit: Option<serenity::GenericChannelId>,
+
+    #[serde(default)]
+    pub message_delete: Option<serenity::GenericChannelId>,
+
+    #[serde(default)]
+    pub message_bulk_delete: Option<serenity::GenericChannelId>,
+
+    #[serde(default)]
+    pub member_role_update: Option<serenity::GenericChannelId>,
+
+    #[serde(default)]
+    pub member_nickname_update: Option<serenity::GenericChannelId>,
+
+    #[serde(default)]
+    pub voice_activity: Option<serenity::GenericChannelId>,
+
+    #[serde(default)]
+    pub channel_create: Option<serenity::GenericChannelId>,
+
+    #[serde(default)]
+    pub channel_delete: Option<serenity::GenericChannelId>,
+
+    #[serde(default)]
+    pub guild_ban_add: Option<serenity::GenericChannelId>,
+
+    #[serde(default)]
+    pub guild_ban_remove: Option<serenity::GenericChannelId>,
+
+    #[serde(default)]
+    pub moderation_actions: Option<serenity::GenericChannelId>,
+
+    #[serde(default)]
+    pub moderation_appeals: Option<serenity::GenericChannelId>,
+}
+
+impl GuildConfigLoggingChannels {
+    fn channel_mut(
+        &mut self,
+        kind: GuildConfigLoggingChannelKind,
+    ) -> &mut Option<serenity::GenericChannelId> {
+        match kind {
+            GuildConfigLoggingChannelKind::GuildMemberJoin => &mut self.guild_member_join,
+            GuildConfigLoggingChannelKind::GuildMemberLeave => &mut self.guild_member_leave,
+            GuildConfigLoggingChannelKind::MessageEdit => &mut self.message_edit,
+            GuildConfigLoggingChannelKind::MessageDelete => &mut self.message_delete,
+            GuildConfigLoggingChannelKind::MessageBulkDelete => &mut self.message_bulk_delete,
+            GuildConfigLoggingChannelKind::MemberRoleUpdate => &mut self.member_role_update,
+            GuildConfigLoggingChannelKind::MemberNicknameUpdate => &mut self.member_nickname_update,
+            GuildConfigLoggingChannelKind::VoiceActivity => &mut self.voice_activity,
+            GuildConfigLoggingChannelKind::ChannelCreate => &mut self.channel_create,
+            GuildConfigLoggingChannelKind::ChannelDelete => &mut self.channel_delete,
+            GuildConfigLoggingChannelKind::GuildBanAdd => &mut self.guild_ban_add,
+            GuildConfigLoggingChannelKind::GuildBanRemove => &mut self.guild_ban_remove,
+            GuildConfigLoggingChannelKind::ModerationActions => &mut self.moderation_actions,
+            GuildConfigLoggingChannelKind::ModerationAppeals => &mut self.moderation_appeals,
+        }
+    }
+
+    /// Returns the channel configured for a kind of logging, if any.
+    pub fn get(
+        &self,
+        kind: GuildConfigLoggingChannelKind,
+    ) -> Option<serenity::GenericChannelId> {
+        *self.clone().channel_mut(kind)
+    }
+
+    /// Returns a copy of these logging channels with a kind of logging (un)set.
+    pub fn with(
+        mut self,
+        kind: GuildConfigLoggingChannelKind,
+        channel_id: Option<serenity::GenericChannelId>,
+    ) -> Self {
+        *self.channel_mut(kind) = channel_id;
+
+        self
+    }
+}
+
+//------------------------------------------------------------//
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum GuildConfigEscalationAction {
+    #[serde(rename = "0")]
+    Timeout,
+
+    #[serde(rename = "1")]
+    Kick,
+
+    #[serde(rename = "2")]
+    Ban,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GuildConfigEscalationRule {
+    /// The amount of active warnings that triggers this rule.
+    /// Members with more warnings trigger this rule too, unless a rule with a higher warning count applies.
+    pub warning_count: u32,
+
+    pub action: GuildConfigEscalationAction,
+
+    /// Only used by actions that have a duration (e.g. timeouts).
+    #[serde(default)]
+    pub duration_seconds: Option<u32>,
+}
+
+#[derive(Default, Debug, Deserialize, Serialize, Clone)]
+pub struct GuildConfigModerationEscalation {
+    #[serde(default)]
+    pub rules: Vec<GuildConfigEscalationRule>,
+
+    /// Warnings older than this many days no longer count towards escalation.
+    /// Warnings never expire when this is `None`.
+    #[serde(default)]
+    pub warning_decay_days: Option<u32>,
+}
+
+impl GuildConfigModerationEscalation {
+    /// Returns the rule that should be applied when a member reaches the given amount of warnings,
+    /// which is the rule with the highest warning count that was reached.
+    pub fn find_rule_for_warning_count(
+        &self,
+        warning_count: u32,
+    ) -> Option<&GuildConfigEscalationRule> {
+        self.rules.iter()
+        .filter(|rule| rule.warning_count <= warning_count)
+        .max_by_key(|rule| rule.warning_count)
+    }
+}
+
+//------------------------------------------------------------//
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum GuildConfigAutomodRuleKind {
+    #[serde(rename = "0")]
+    BannedWords,
+
+    #[serde(rename = "1")]
+    BannedPatterns,
+
+    #[serde(rename = "2")]
+    InviteLinks,
+
+    #[serde(rename = "3")]
+    MassMentions,
+
+    #[serde(rename = "4")]
+    ExcessiveCaps,
+
+    #[serde(rename = "5")]
+    RepeatedMessages,
+
+    #[serde(rename = "6")]
+    ZalgoText,
+
+    #[serde(rename = "7")]
+    LinkDomainAllowList,
+
+    #[serde(rename = "8")]
+    LinkDomainDenyList,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum GuildConfigAutomodAction {
+    #[serde(rename = "0")]
+    Delete,
+
+    #[serde(rename = "1")]
+    Warn,
+
+    #[serde(rename = "2")]
+    Timeout,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GuildConfigAutomodRule {
+    pub kind: GuildConfigAutomodRuleKind,
+
+    /// The offending message is always deleted, the action determines what else happens.
+    pub action: GuildConfigAutomodAction,
+
+    /// Only used by timeouts.
+    #[serde(default)]
+    pub duration_seconds: Option<u32>,
+
+    /// The words, patterns, or domains used by the rule (if applicable).
+    #[serde(default)]
+    pub values: Vec<String>,
+
+    /// The mentions, caps percentage, repeats, or combining characters that trigger the rule (if applicable).
+    #[serde(default)]
+    pub threshold: Option<u32>,
+}
+
+#[derive(Default, Debug, Deserialize, Serialize, Clone)]
+pub struct GuildConfigAutomod {
+    #[serde(default)]
+    pub rules: Vec<GuildConfigAutomodRule>,
+
+    #[serde(default)]
+    pub exempt_role_ids: Vec<serenity::RoleId>,
+
+    #[serde(default)]
+    pub exempt_channel_ids: Vec<serenity::GenericChannelId>,
+}
+
+//------------------------------------------------------------//
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum GuildConfigCommandTargetKind {
+    #[serde(rename = "0")]
+    Command,
+
+    #[serde(rename = "1")]
+    Category,
+}
+
+/// A top-level command or a command category, identified by its lowercase name.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct GuildConfigCommandTarget {
+    pub kind: GuildConfigCommandTargetKind,
+
+    pub name: String,
+}
+
+impl GuildConfigCommandTarget {
+    pub fn matches(
+        &self,
+        command_name: &str,
+        command_category: Option<&str>,
+    ) -> bool {
+        match self.kind {
+            GuildConfigCommandTargetKind::Command => self.name.eq_ignore_ascii_case(command_name),
+            GuildConfigCommandTargetKind::Category => command_category.is_some_and(|category| self.name.eq_ignore_ascii_case(category)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GuildConfigCommandListMode {
+    /// Every command is enabled, except for the listed commands and categories.
+    #[default]
+    #[serde(rename = "0")]
+    DenyList,
+
+    /// Only the listed commands and categories are enabled.
+    #[serde(rename = "1")]
+    AllowList,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GuildConfigCommandRestriction {
+    pub target: GuildConfigCommandTarget,
+
+    /// When not empty, the target may only be used in these channels.
+    #[serde(default)]
+    pub allowed_channel_ids: Vec<serenity::GenericChannelId>,
+
+    /// When not empty, the target may only be used by members with one of these roles.
+    #[serde(default)]
+    pub allowed_role_ids: Vec<serenity::RoleId>,
+}
+
+#[derive(Default, Debug, Deserialize, Serialize, Clone)]
+pub struct GuildConfigCommandAccess {
+    #[serde(default)]
+    pub list_mode: GuildConfigCommandListMode,
+
+    #[serde(default)]
+    pub listed_targets: Vec<GuildConfigCommandTarget>,
+
+    #[serde(default)]
+    pub restrictions: Vec<GuildConfigCommandRestriction>,
+}
+
+impl GuildConfigCommandAccess {
+    pub fn is_command_enabled(
+        &self,
+        command_name: &str,
+        command_category: Option<&str>,
+    ) -> bool {
+        let is_listed = self.listed_targets.iter().any(|target| target.matches(command_name, command_category));
+
+        match self.list_mode {
+            GuildConfigCommandListMode::DenyList => !is_listed,
+            GuildConfigCommandListMode::AllowList => is_listed,
+        }
+    }
+
+    /// Returns the restrictions that apply to a command, either directly or through its category.
+    pub fn find_restrictions<'a>(
+        &'a self,
+        command_name: &'a str,
+        command_category: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a GuildConfigCommandRestriction> {
+        self.restrictions.iter().filter(move |restriction| restriction.target.matches(command_name, command_category))
+    }
+}
+
+//------------------------------------------------------------//
+
+/// Replaces the built-in cooldowns of a command, cooldowns that are not set are disabled.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GuildConfigCooldownOverride {
+    /// The lowercase qualified name of a command (e.g. `play` or `setup automod`).
+    pub command_name: String,
+
+    #[serde(default)]
+    pub user_seconds: Option<u32>,
+
+    #[serde(default)]
+    pub channel_seconds: Option<u32>,
+
+    #[serde(default)]
+    pub guild_seconds: Option<u32>,
+}
+
+#[derive(Default, Debug, Deserialize, Serialize, Clone)]
+pub struct GuildConfigCooldowns {
+    #[serde(default)]
+    pub overrides: Vec<GuildConfigCooldownOverride>,
+
+    /// Members with any of these roles are not subject to cooldowns.
+    #[serde(default)]
+    pub bypass_role_ids: Vec<serenity::RoleId>,
+}
+
+impl GuildConfigCooldowns {
+    pub fn find_override(
+        &self,
+        command_name: &str,
+    ) -> Option<&GuildConfigCooldownOverride> {
+        self.overrides.iter().find(|cooldown_override| cooldown_override.command_name.eq_ignore_ascii_case(command_name))
+    }
+}
+
+//------------------------------------------------------------//
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct GuildConfigSpamProtection {
+    pub enabled: bool,
+
+    /// Sending more than this many messages in one channel within the message window is spam.
+    pub max_messages_per_channel: u32,
+
+    pub message_window_seconds: u32,
+
+    /// Sending the same message this many times (in any channels) within the duplicate window is spam.
+    pub max_duplicate_messages: u32,
+
+    pub duplicate_window_seconds: u32,
+
+    /// Spam is only deleted until a member has this many recent violations, after which they are timed out.
+    pub violations_before_timeout: u32,
+
+    /// The first timeout lasts this long, doubling for each further violation.
+    pub base_timeout_seconds: u32,
+}
+
+impl Default for GuildConfigSpamProtection {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_messages_per_channel: 5,
+            message_window_seconds: 5,
+            max_duplicate_messages: 3,
+            duplicate_window_seconds: 30,
+            violations_before_timeout: 1,
+            base_timeout_seconds: 5 * 60,
+        }
+    }
+}
+
+//------------------------------------------------------------//
+
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GuildConfigAntiRaidAction {
+    #[default]
+    #[serde(rename = "0")]
+    Timeout,
+
+    #[serde(rename = "1")]
+    Kick,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct GuildConfigAntiRaid {
+    pub enabled: bool,
+
+    /// Raid mode starts when this many members join within the join window.
+    pub join_threshold: u32,
+
+    pub join_window_seconds: u32,
+
+    /// While raid mode is active, members whose accounts are younger than this are actioned.
+    pub min_account_age_days: u32,
+
+    pub action: GuildConfigAntiRaidAction,
+
+    /// The slowmode applied to text channels while raid mode is active.
+    pub raid_slowmode_seconds: u16,
+
+    /// How long raid mode lasts before it is automatically ended.
+    pub raid_duration_minutes: u32,
+}
+
+impl Default for GuildConfigAntiRaid {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            join_threshold: 10,
+            join_window_seconds: 10,
+            min_account_age_days: 7,
+            action: GuildConfigAntiRaidAction::default(),
+            raid_slowmode_seconds: 30,
+            raid_duration_minutes: 15,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GuildConfigSlowedChannel {
+    pub channel_id: serenity::ChannelId,
+
+    /// The slowmode (in seconds) before raid mode, restored when it ends.
+    pub previous_rate_limit_per_user: u16,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GuildConfigRaidMode {
+    pub started_at: chrono::DateTime<chrono::Utc>,
+
+    pub slowed_channels: Vec<GuildConfigSlowedChannel>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GuildConfigLockedChannel {
+    pub channel_id: serenity::ChannelId,
+
+    /// The `@everyone` overwrite before the lockdown, restored when it ends.
+    pub previous_everyone_overwrite: Option<serenity::PermissionOverwrite>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GuildConfigLockdown {
+    pub started_at: chrono::DateTime<chrono::Utc>,
+
+    pub locked_channels: Vec<GuildConfigLockedChannel>,
+}
+
+//------------------------------------------------------------//
+
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GuildConfigMusicSearchSource {
+    #[default]
+    #[serde(rename = "0")]
+    YouTube,
+
+    #[serde(rename = "1")]
+    YouTubeMusic,
+
+    #[serde(rename = "2")]
+    SoundCloud,
+
+    #[serde(rename = "3")]
+    Bandcamp,
+
+    #[serde(rename = "4")]
+    Spotify,
+
+    #[serde(rename = "5")]
+    AppleMusic,
+
+    #[serde(rename = "6")]
+    Deezer,
+
+    #[serde(rename = "7")]
+    YandexMusic,
+}
+
+impl GuildConfigMusicSearchSource {
+    pub const ALL: [GuildConfigMusicSearchSource; 8] = [
+        GuildConfigMusicSearchSource::YouTube,
+        GuildConfigMusicSearchSource::YouTubeMusic,
+        GuildConfigMusicSearchSource::SoundCloud,
+        GuildConfigMusicSearchSource::Bandcamp,
+        GuildConfigMusicSearchSource::Spotify,
+        GuildConfigMusicSearchSource::AppleMusic,
+        GuildConfigMusicSearchSource::Deezer,
+        GuildConfigMusicSearchSource::YandexMusic,
+    ];
+
+    pub fn name(
+        &self,
+    ) -> &'static str {
+        match self {
+            GuildConfigMusicSearchSource::YouTube => "YouTube",
+            GuildConfigMusicSearchSource::YouTubeMusic => "YouTube Music",
+            GuildConfigMusicSearchSource::SoundCloud => "SoundCloud",
+            GuildConfigMusicSearchSource::Bandcamp => "Bandcamp",
+            GuildConfigMusicSearchSource::Spotify => "Spotify",
+            GuildConfigMusicSearchSource::AppleMusic => "Apple Music",
+            GuildConfigMusicSearchSource::Deezer => "Deezer",
+            GuildConfigMusicSearchSource::YandexMusic => "Yandex Music",
+        }
+    }
+
+    /// The short prefix that users can type before a query, e.g. `sc:never gonna give you up`.
+    pub fn shorthand(
+        &self,
+    ) -> &'static str {
+        match self {
+            GuildConfigMusicSearchSource::YouTube => "yt",
+            GuildConfigMusicSearchSource::YouTubeMusic => "ytm",
+            GuildConfigMusicSearchSource::SoundCloud => "sc",
+            GuildConfigMusicSearchSource::Bandcamp => "bc",
+            GuildConfigMusicSearchSource::Spotify => "sp",
+            GuildConfigMusicSearchSource::AppleMusic => "am",
+            GuildConfigMusicSearchSource::Deezer => "dz",
+            GuildConfigMusicSearchSource::YandexMusic => "ym",
+        }
+    }
+
+    /// The prefix that lavalink uses to search this source.
+    pub fn search_prefix(
         &self,
-        guild_config: &GuildConfig,
-        channel_id: serenity::GenericChannelId,
-        was_mentioned: bool,
-    ) -> bool {
-        let is_ai_chat_channel = guild_config.ai_chat_channels.contains(&channel_id);
+    ) -> &'static str {
+        match self {
+            GuildConfigMusicSearchSource::YouTube => "ytsearch",
+            GuildConfigMusicSearchSource::YouTubeMusic => "ytmsearch",
+            GuildConfigMusicSearchSource::SoundCloud => "scsearch",
+            GuildConfigMusicSearchSource::Bandcamp => "bcsearch",
+            GuildConfigMusicSearchSource::Spotify => "spsearch",
+            GuildConfigMusicSearchSource::AppleMusic => "amsearch",
+            GuildConfigMusicSearchSource::Deezer => "dzsearch",
+            GuildConfigMusicSearchSource::YandexMusic => "ymsearch",
+        }
+    }
 
+    /// The name of the lavalink source manager that searches this source, as reported by the node's info.
+    /// Some of these are only provided by lavalink plugins (e.g. LavaSrc).
+    pub fn source_manager_name(
+        &self,
+    ) -> &'static str {
         match self {
-            GuildConfigAiChatMode::Disabled => false,
-            GuildConfigAiChatMode::AiChatChannelsRequireMention => {
-                was_mentioned && is_ai_chat_channel
-            },
-            GuildConfigAiChatMode::AiChatChannelsAlwaysRespond => {
-                was_mentioned || is_ai_chat_channel
-            },
+            GuildConfigMusicSearchSource::YouTube => "youtube",
+            GuildConfigMusicSearchSource::YouTubeMusic => "youtube",
+            GuildConfigMusicSearchSource::SoundCloud => "soundcloud",
+            GuildConfigMusicSearchSource::Bandcamp => "bandcamp",
+            GuildConfigMusicSearchSource::Spotify => "spotify",
+            GuildConfigMusicSearchSource::AppleMusic => "applemusic",
+            GuildConfigMusicSearchSource::Deezer => "deezer",
+            GuildConfigMusicSearchSource::YandexMusic => "yandexmusic",
         }
     }
 }
 
-type GuildConfigAiChatChannels = Vec<serenity::GenericChannelId>;
-
-//------------------------------------------------------------//
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct GuildConfigMusic {
+    /// Playlists are truncated to this many tracks when they are added to the queue.
+    pub max_playlist_tracks: u32,
 
-#[derive(Default, Debug, Deserialize, Serialize, Clone)]
-pub struct GuildConfigLoggingChannels {
-    #[serde(default)]
-    pub guild_member_join: Option<serenity::GenericChannelId>,
+    /// The source that plain queries are searched on, unless another source is requested.
+    pub default_search_source: GuildConfigMusicSearchSource,
+}
 
-    #[serde(default)]
-    pub guild_member_leave: Option<serenity::GenericChannelId>,
+impl Default for GuildConfigMusic {
+    fn default() -> Self {
+        Self {
+            max_playlist_tracks: 100,
+            default_search_source: GuildConfigMusicSearchSource::default(),
+        }
+    }
 }
 
 //------------------------------------------------------------//
@@ -99,6 +806,9 @@ pub struct GuildConfig {
     #[serde(default)]
     moderation_mode: GuildConfigModerationMode,
 
+    #[serde(default)]
+    moderation_roles: GuildConfigModerationRoles,
+
     #[serde(default)]
     ai_chat_mode: GuildConfigAiChatMode,
 
@@ -107,6 +817,41 @@ pub struct GuildConfig {
 
     #[serde(default)]
     logging_channels: GuildConfigLoggingChannels,
+
+    #[serde(default)]
+    moderation_case_counter: u32,
+
+    #[serde(default)]
+    moderation_escalation: GuildConfigModerationEscalation,
+
+    #[serde(default)]
+    message_retention_mode: GuildConfigMessageRetentionMode,
+
+    #[serde(default)]
+    automod: GuildConfigAutomod,
+
+    #[serde(default)]
+    command_access: GuildConfigCommandAccess,
+
+    #[serde(default)]
+    cooldowns: GuildConfigCooldowns,
+
+    #[serde(default)]
+    spam_protection: GuildConfigSpamProtection,
+
+    #[serde(default)]
+    anti_raid: GuildConfigAntiRaid,
+
+    #[serde(default)]
+    music: GuildConfigMusic,
+
+    /// Present while raid mode is active.
+    #[serde(default)]
+    raid_mode: Option<GuildConfigRaidMode>,
+
+    /// Present while a lockdown is active.
+    #[serde(default)]
+    lockdown: Option<GuildConfigLockdown>,
 }
 
 impl GuildConfig {
@@ -137,9 +882,21 @@ impl GuildConfig {
             GuildConfig {
                 discord_guild_id: discord_guild_id,
                 moderation_mode: GuildConfigModerationMode::default(),
+                moderation_roles: GuildConfigModerationRoles::default(),
                 ai_chat_mode: GuildConfigAiChatMode::default(),
                 ai_chat_channels: GuildConfigAiChatChannels::default(),
                 logging_channels: GuildConfigLoggingChannels::default(),
+                moderation_case_counter: u32::default(),
+                moderation_escalation: GuildConfigModerationEscalation::default(),
+                message_retention_mode: GuildConfigMessageRetentionMode::default(),
+                automod: GuildConfigAutomod::default(),
+                command_access: GuildConfigCommandAccess::default(),
+                cooldowns: GuildConfigCooldowns::default(),
+                spam_protection: GuildConfigSpamProtection::default(),
+                anti_raid: GuildConfigAntiRaid::default(),
+                music: GuildConfigMusic::default(),
+                raid_mode: None,
+                lockdown: None,
             }
         ).await?;
 
@@ -196,6 +953,48 @@ impl GuildConfig {
         self.discord_guild_id
     }
 
+    pub async fn get_moderation_mode(
+        &self,
+    ) -> GuildConfigModerationMode {
+        self.moderation_mode
+    }
+
+    pub async fn set_moderation_mode(
+        &self,
+        moderation_mode: GuildConfigModerationMode,
+    ) -> Result<(), Error> {
+        self.update(
+            mongodb::bson::doc! {
+                "$set": {
+                    "moderation_mode": to_bson(&moderation_mode)?,
+                },
+            }
+        ).await?;
+
+        Ok(())
+    }
+
+    pub async fn get_moderation_roles(
+        &self,
+    ) -> GuildConfigModerationRoles {
+        self.moderation_roles.clone()
+    }
+
+    pub async fn set_moderation_roles(
+        &self,
+        moderation_roles: GuildConfigModerationRoles,
+    ) -> Result<(), Error> {
+        self.update(
+            mongodb::bson::doc! {
+                "$set": {
+                    "moderation_roles": to_bson(&moderation_roles)?,
+                },
+            }
+        ).await?;
+
+        Ok(())
+    }
+
     pub async fn get_ai_chat_mode(
         &self,
     ) -> GuildConfigAiChatMode {
@@ -258,4 +1057,244 @@ impl GuildConfig {
 
         Ok(())
     }
+
+    pub async fn get_moderation_escalation(
+        &self,
+    ) -> GuildConfigModerationEscalation {
+        self.moderation_escalation.clone()
+    }
+
+    pub async fn set_moderation_escalation(
+        &self,
+        moderation_escalation: GuildConfigModerationEscalation,
+    ) -> Result<(), Error> {
+        self.update(
+            mongodb::bson::doc! {
+                "$set": {
+                    "moderation_escalation": to_bson(&moderation_escalation)?,
+                },
+            }
+        ).await?;
+
+        Ok(())
+    }
+
+    pub async fn get_message_retention_mode(
+        &self,
+    ) -> GuildConfigMessageRetentionMode {
+        self.message_retention_mode
+    }
+
+    pub async fn set_message_retention_mode(
+        &self,
+        message_retention_mode: GuildConfigMessageRetentionMode,
+    ) -> Result<(), Error> {
+        self.update(
+            mongodb::bson::doc! {
+                "$set": {
+                    "message_retention_mode": to_bson(&message_retention_mode)?,
+                },
+            }
+        ).await?;
+
+        Ok(())
+    }
+
+    pub async fn get_automod(
+        &self,
+    ) -> GuildConfigAutomod {
+        self.automod.clone()
+    }
+
+    pub async fn set_automod(
+        &self,
+        automod: GuildConfigAutomod,
+    ) -> Result<(), Error> {
+        self.update(
+            mongodb::bson::doc! {
+                "$set": {
+                    "automod": to_bson(&automod)?,
+                },
+            }
+        ).await?;
+
+        Ok(())
+    }
+
+    pub async fn get_command_access(
+        &self,
+    ) -> GuildConfigCommandAccess {
+        self.command_access.clone()
+    }
+
+    pub async fn set_command_access(
+        &self,
+        command_access: GuildConfigCommandAccess,
+    ) -> Result<(), Error> {
+        self.update(
+            mongodb::bson::doc! {
+                "$set": {
+                    "command_access": to_bson(&command_access)?,
+                },
+            }
+        ).await?;
+
+        Ok(())
+    }
+
+    pub async fn get_cooldowns(
+        &self,
+    ) -> GuildConfigCooldowns {
+        self.cooldowns.clone()
+    }
+
+    pub async fn set_cooldowns(
+        &self,
+        cooldowns: GuildConfigCooldowns,
+    ) -> Result<(), Error> {
+        self.update(
+            mongodb::bson::doc! {
+                "$set": {
+                    "cooldowns": to_bson(&cooldowns)?,
+                },
+            }
+        ).await?;
+
+        Ok(())
+    }
+
+    pub async fn get_spam_protection(
+        &self,
+    ) -> GuildConfigSpamProtection {
+        self.spam_protection.clone()
+    }
+
+    pub async fn set_spam_protection(
+        &self,
+        spam_protection: GuildConfigSpamProtection,
+    ) -> Result<(), Error> {
+        self.update(
+            mongodb::bson::doc! {
+                "$set": {
+                    "spam_protection": to_bson(&spam_protection)?,
+                },
+            }
+        ).await?;
+
+        Ok(())
+    }
+
+    pub async fn get_anti_raid(
+        &self,
+    ) -> GuildConfigAntiRaid {
+        self.anti_raid.clone()
+    }
+
+    pub async fn set_anti_raid(
+        &self,
+        anti_raid: GuildConfigAntiRaid,
+    ) -> Result<(), Error> {
+        self.update(
+            mongodb::bson::doc! {
+                "$set": {
+                    "anti_raid": to_bson(&anti_raid)?,
+                },
+            }
+        ).await?;
+
+        Ok(())
+    }
+
+    pub async fn get_music(
+        &self,
+    ) -> GuildConfigMusic {
+        self.music.clone()
+    }
+
+    pub async fn set_music(
+        &self,
+        music: GuildConfigMusic,
+    ) -> Result<(), Error> {
+        self.update(
+            mongodb::bson::doc! {
+                "$set": {
+                    "music": to_bson(&music)?,
+                },
+            }
+        ).await?;
+
+        Ok(())
+    }
+
+    pub async fn get_raid_mode(
+        &self,
+    ) -> Option<GuildConfigRaidMode> {
+        self.raid_mode.clone()
+    }
+
+    pub async fn set_raid_mode(
+        &self,
+        raid_mode: Option<GuildConfigRaidMode>,
+    ) -> Result<(), Error> {
+        self.update(
+            mongodb::bson::doc! {
+                "$set": {
+                    "raid_mode": to_bson(&raid_mode)?,
+                },
+            }
+        ).await?;
+
+        Ok(())
+    }
+
+    pub async fn get_lockdown(
+        &self,
+    ) -> Option<GuildConfigLockdown> {
+        self.lockdown.clone()
+    }
+
+    pub async fn set_lockdown(
+        &self,
+        lockdown: Option<GuildConfigLockdown>,
+    ) -> Result<(), Error> {
+        self.update(
+            mongodb::bson::doc! {
+                "$set": {
+                    "lockdown": to_bson(&lockdown)?,
+                },
+            }
+        ).await?;
+
+        Ok(())
+    }
+
+    /// Atomically increments the moderation case counter for this guild.
+    /// Returns the incremented value, which should be used as the next case id.
+    pub async fn next_moderation_case_id(
+        &self,
+    ) -> Result<u32, Error> {
+        let discord_guild_id: String = self.discord_guild_id.get().to_string();
+
+        let database_name = get_database_name();
+        let collection_name = get_guilds_collection_name();
+        let collection_helper = CollectionHelper::new(database_name, collection_name);
+        let filter = mongodb::bson::doc! {
+            "discord_guild_id": discord_guild_id,
+        };
+
+        let updated_guild_config = collection_helper.find_one_and_update::<GuildConfig>(
+            filter,
+            mongodb::bson::doc! {
+                "$inc": {
+                    "moderation_case_counter": 1,
+                },
+            }
+        ).await?;
+
+        let Some(updated_guild_config) = updated_guild_config else {
+            return Err(Error::from("Failed to increment the moderation case counter."));
+        };
+
+        Ok(updated_guild_config.moderation_case_counter)
+    }
 }
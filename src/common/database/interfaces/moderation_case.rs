@@ -0,0 +1,470 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use mongodb::bson::to_bson;
+
+use serde::{Deserialize, Serialize};
+
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::Error;
+
+use crate::common::database::adapter::get_database_name;
+
+use crate::common::database::adapter::get_moderation_cases_collection_name;
+
+use crate::common::database::adapter::CollectionHelper;
+
+use crate::common::database::interfaces::guild_config::GuildConfig;
+
+//------------------------------------------------------------//
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum ModerationCaseAction {
+    #[serde(rename = "0")]
+    Warn,
+
+    #[serde(rename = "1")]
+    Timeout,
+
+    #[serde(rename = "2")]
+    Untimeout,
+
+    #[serde(rename = "3")]
+    Kick,
+
+    #[serde(rename = "4")]
+    Ban,
+
+    #[serde(rename = "5")]
+    Unban,
+
+    #[serde(rename = "6")]
+    Mute,
+
+    #[serde(rename = "7")]
+    Unmute,
+
+    #[serde(rename = "8")]
+    Deafen,
+
+    #[serde(rename = "9")]
+    Undeafen,
+
+    #[serde(rename = "10")]
+    Disconnect,
+
+    #[serde(rename = "11")]
+    Yeet,
+
+    #[serde(rename = "12")]
+    Yoink,
+
+    #[serde(rename = "13")]
+    Purge,
+
+    #[serde(rename = "14")]
+    Slowmode,
+
+    #[serde(rename = "15")]
+    Lockdown,
+
+    #[serde(rename = "16")]
+    Unlock,
+
+    #[serde(rename = "17")]
+    MassBan,
+
+    #[serde(rename = "18")]
+    MassKick,
+
+    #[serde(rename = "19")]
+    MassMute,
+
+    #[serde(rename = "20")]
+    MassUnmute,
+
+    #[serde(rename = "21")]
+    MassMove,
+
+    #[serde(rename = "22")]
+    MassDisconnect,
+}
+
+impl ModerationCaseAction {
+    pub fn name(
+        &self,
+    ) -> &'static str {
+        match self {
+            ModerationCaseAction::Warn => "Warn",
+            ModerationCaseAction::Timeout => "Timeout",
+            ModerationCaseAction::Untimeout => "Untimeout",
+            ModerationCaseAction::Kick => "Kick",
+            ModerationCaseAction::Ban => "Ban",
+            ModerationCaseAction::Unban => "Unban",
+            ModerationCaseAction::Mute => "Mute",
+            ModerationCaseAction::Unmute => "Unmute",
+            ModerationCaseAction::Deafen => "Deafen",
+            ModerationCaseAction::Undeafen => "Undeafen",
+            ModerationCaseAction::Disconnect => "Disconnect",
+            ModerationCaseAction::Yeet => "Yeet",
+            ModerationCaseAction::Yoink => "Yoink",
+            ModerationCaseAction::Purge => "Purge",
+            ModerationCaseAction::Slowmode => "Slowmode",
+            ModerationCaseAction::Lockdown => "Lockdown",
+            ModerationCaseAction::Unlock => "Unlock",
+            ModerationCaseAction::MassBan => "Mass Ban",
+            ModerationCaseAction::MassKick => "Mass Kick",
+            ModerationCaseAction::MassMute => "Mass Mute",
+            ModerationCaseAction::MassUnmute => "Mass Unmute",
+            ModerationCaseAction::MassMove => "Mass Move",
+            ModerationCaseAction::MassDisconnect => "Mass Disconnect",
+        }
+    }
+}
+
+//------------------------------------------------------------//
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum ModerationCaseAppealStatus {
+    #[serde(rename = "0")]
+    Pending,
+
+    #[serde(rename = "1")]
+    Accepted,
+
+    #[serde(rename = "2")]
+    Denied,
+}
+
+impl ModerationCaseAppealStatus {
+    pub fn name(
+        &self,
+    ) -> &'static str {
+        match self {
+            ModerationCaseAppealStatus::Pending => "Pending",
+            ModerationCaseAppealStatus::Accepted => "Accepted",
+            ModerationCaseAppealStatus::Denied => "Denied",
+        }
+    }
+}
+
+/// An appeal submitted by the target of a moderation case.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ModerationCaseAppeal {
+    pub content: String,
+
+    pub status: ModerationCaseAppealStatus,
+
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    #[serde(default)]
+    pub reviewer_user_id: Option<serenity::UserId>,
+
+    #[serde(default)]
+    pub reviewed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+//------------------------------------------------------------//
+
+/// The details required to record a new moderation case.
+pub struct ModerationCaseOptions {
+    pub discord_guild_id: serenity::GuildId,
+    pub action: ModerationCaseAction,
+    pub actor_user_id: serenity::UserId,
+    pub target_user_id: Option<serenity::UserId>,
+    pub channel_id: Option<serenity::GenericChannelId>,
+    pub reason: String,
+    pub duration_seconds: Option<u32>,
+}
+
+//------------------------------------------------------------//
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ModerationCase {
+    discord_guild_id: serenity::GuildId,
+
+    case_id: u32,
+
+    action: ModerationCaseAction,
+
+    actor_user_id: serenity::UserId,
+
+    #[serde(default)]
+    target_user_id: Option<serenity::UserId>,
+
+    #[serde(default)]
+    channel_id: Option<serenity::GenericChannelId>,
+
+    reason: String,
+
+    #[serde(default)]
+    duration_seconds: Option<u32>,
+
+    created_at: chrono::DateTime<chrono::Utc>,
+
+    #[serde(default)]
+    appeal: Option<ModerationCaseAppeal>,
+}
+
+impl ModerationCase {
+    fn collection_helper() -> CollectionHelper {
+        let database_name = get_database_name();
+        let collection_name = get_moderation_cases_collection_name();
+
+        CollectionHelper::new(database_name, collection_name)
+    }
+
+    pub async fn fetch(
+        discord_guild_id: serenity::GuildId,
+        case_id: u32,
+    ) -> Result<Option<ModerationCase>, Error> {
+        let discord_guild_id: String = discord_guild_id.get().to_string();
+
+        let moderation_case = ModerationCase::collection_helper().get(
+            mongodb::bson::doc! {
+                "discord_guild_id": discord_guild_id,
+                "case_id": case_id,
+            }
+        ).await?;
+
+        Ok(moderation_case)
+    }
+
+    /// Returns the most recent cases targeting a user, newest first.
+    pub async fn fetch_for_target(
+        discord_guild_id: serenity::GuildId,
+        target_user_id: serenity::UserId,
+        limit: i64,
+    ) -> Result<Vec<ModerationCase>, Error> {
+        let discord_guild_id: String = discord_guild_id.get().to_string();
+        let target_user_id: String = target_user_id.get().to_string();
+
+        let moderation_cases = ModerationCase::collection_helper().get_many(
+            mongodb::bson::doc! {
+                "discord_guild_id": discord_guild_id,
+                "target_user_id": target_user_id,
+            },
+            Some(mongodb::bson::doc! { "case_id": -1 }),
+            Some(limit),
+        ).await?;
+
+        Ok(moderation_cases)
+    }
+
+    /// Returns every case of a specific action targeting a user, newest first.
+    pub async fn fetch_by_action_for_target(
+        discord_guild_id: serenity::GuildId,
+        target_user_id: serenity::UserId,
+        action: ModerationCaseAction,
+    ) -> Result<Vec<ModerationCase>, Error> {
+        let discord_guild_id: String = discord_guild_id.get().to_string();
+        let target_user_id: String = target_user_id.get().to_string();
+
+        let moderation_cases = ModerationCase::collection_helper().get_many(
+            mongodb::bson::doc! {
+                "discord_guild_id": discord_guild_id,
+                "target_user_id": target_user_id,
+                "action": to_bson(&action)?,
+            },
+            Some(mongodb::bson::doc! { "case_id": -1 }),
+            None,
+        ).await?;
+
+        Ok(moderation_cases)
+    }
+
+    /// Records a new case using the next sequential case id for the guild.
+    pub async fn create(
+        options: ModerationCaseOptions,
+    ) -> Result<ModerationCase, Error> {
+        let guild_config = GuildConfig::ensure(options.discord_guild_id).await?;
+
+        let case_id = guild_config.next_moderation_case_id().await?;
+
+        let moderation_case = ModerationCase::collection_helper().set(
+            ModerationCase {
+                discord_guild_id: options.discord_guild_id,
+                case_id: case_id,
+                action: options.action,
+                actor_user_id: options.actor_user_id,
+                target_user_id: options.target_user_id,
+                channel_id: options.channel_id,
+                reason: options.reason,
+                duration_seconds: options.duration_seconds,
+                created_at: chrono::Utc::now(),
+                appeal: None,
+            }
+        ).await?;
+
+        Ok(moderation_case)
+    }
+
+    pub async fn update(
+        &self,
+        update_document: mongodb::bson::Document,
+    ) -> Result<(), Error> {
+        let discord_guild_id: String = self.discord_guild_id.get().to_string();
+
+        let filter = mongodb::bson::doc! {
+            "discord_guild_id": discord_guild_id,
+            "case_id": self.case_id,
+        };
+
+        ModerationCase::collection_helper().update::<ModerationCase>(filter, update_document).await?;
+
+        Ok(())
+    }
+
+    /// Atomically updates the case, but only while it also matches the condition.
+    /// Returns whether the case was updated.
+    async fn update_if(
+        &self,
+        condition: mongodb::bson::Document,
+        update_document: mongodb::bson::Document,
+    ) -> Result<bool, Error> {
+        let discord_guild_id: String = self.discord_guild_id.get().to_string();
+
+        let mut filter = mongodb::bson::doc! {
+            "discord_guild_id": discord_guild_id,
+            "case_id": self.case_id,
+        };
+
+        filter.extend(condition);
+
+        let updated_moderation_case =
+            ModerationCase::collection_helper()
+            .find_one_and_update::<ModerationCase>(filter, update_document).await?;
+
+        Ok(updated_moderation_case.is_some())
+    }
+
+    pub async fn get_discord_guild_id(
+        &self,
+    ) -> serenity::GuildId {
+        self.discord_guild_id
+    }
+
+    pub async fn get_case_id(
+        &self,
+    ) -> u32 {
+        self.case_id
+    }
+
+    pub async fn get_action(
+        &self,
+    ) -> ModerationCaseAction {
+        self.action
+    }
+
+    pub async fn get_actor_user_id(
+        &self,
+    ) -> serenity::UserId {
+        self.actor_user_id
+    }
+
+    pub async fn get_target_user_id(
+        &self,
+    ) -> Option<serenity::UserId> {
+        self.target_user_id
+    }
+
+    pub async fn get_channel_id(
+        &self,
+    ) -> Option<serenity::GenericChannelId> {
+        self.channel_id
+    }
+
+    pub async fn get_reason(
+        &self,
+    ) -> String {
+        self.reason.clone()
+    }
+
+    pub async fn set_reason(
+        &self,
+        reason: String,
+    ) -> Result<(), Error> {
+        self.update(
+            mongodb::bson::doc! {
+                "$set": {
+                    "reason": to_bson(&reason)?,
+                },
+            }
+        ).await?;
+
+        Ok(())
+    }
+
+    pub async fn get_duration_seconds(
+        &self,
+    ) -> Option<u32> {
+        self.duration_seconds
+    }
+
+    pub async fn get_created_at(
+        &self,
+    ) -> chrono::DateTime<chrono::Utc> {
+        self.created_at
+    }
+
+    pub async fn get_appeal(
+        &self,
+    ) -> Option<ModerationCaseAppeal> {
+        self.appeal.clone()
+    }
+
+    pub async fn set_appeal(
+        &self,
+        appeal: Option<ModerationCaseAppeal>,
+    ) -> Result<(), Error> {
+        self.update(
+            mongodb::bson::doc! {
+                "$set": {
+                    "appeal": to_bson(&appeal)?,
+                },
+            }
+        ).await?;
+
+        Ok(())
+    }
+
+    /// Submits an appeal, unless the case was already appealed.
+    /// Returns whether the appeal was submitted.
+    pub async fn submit_appeal(
+        &self,
+        appeal: ModerationCaseAppeal,
+    ) -> Result<bool, Error> {
+        self.update_if(
+            mongodb::bson::doc! {
+                "appeal": null,
+            },
+            mongodb::bson::doc! {
+                "$set": {
+                    "appeal": to_bson(&appeal)?,
+                },
+            }
+        ).await
+    }
+
+    /// Records the review of an appeal, unless it is no longer pending.
+    /// Returns whether the review was recorded.
+    pub async fn review_appeal(
+        &self,
+        appeal: ModerationCaseAppeal,
+    ) -> Result<bool, Error> {
+        self.update_if(
+            mongodb::bson::doc! {
+                "appeal.status": to_bson(&ModerationCaseAppealStatus::Pending)?,
+            },
+            mongodb::bson::doc! {
+                "$set": {
+                    "appeal": to_bson(&appeal)?,
+                },
+            }
+        ).await
+    }
+}
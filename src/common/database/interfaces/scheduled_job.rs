@@ -0,0 +1,235 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use mongodb::bson::oid::ObjectId;
+
+use serde::{Deserialize, Serialize};
+
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::Error;
+
+use crate::common::database::adapter::get_database_name;
+
+use crate::common::database::adapter::get_scheduled_jobs_collection_name;
+
+use crate::common::database::adapter::CollectionHelper;
+
+//------------------------------------------------------------//
+
+/// The work to perform once a scheduled job is due.
+/// New kinds of jobs should be added as new variants.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(tag = "kind")]
+pub enum ScheduledJobPayload {
+    #[serde(rename = "unban")]
+    Unban {
+        discord_guild_id: serenity::GuildId,
+        discord_user_id: serenity::UserId,
+        reason: String,
+    },
+
+    #[serde(rename = "end_raid_mode")]
+    EndRaidMode {
+        discord_guild_id: serenity::GuildId,
+    },
+
+    #[serde(rename = "voice_unmute")]
+    VoiceUnmute {
+        discord_guild_id: serenity::GuildId,
+        discord_user_id: serenity::UserId,
+        reason: String,
+    },
+}
+
+//------------------------------------------------------------//
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ScheduledJob {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    id: Option<ObjectId>,
+
+    /// Used to find (and replace) a job for the same subject, e.g. one unban per user per guild.
+    #[serde(default)]
+    dedupe_key: Option<String>,
+
+    payload: ScheduledJobPayload,
+
+    /// Stored as a native bson date so that due jobs can be queried.
+    run_at: mongodb::bson::DateTime,
+
+    /// When the job was first due, since `run_at` is moved back whenever the job is retried.
+    #[serde(default)]
+    first_run_at: Option<mongodb::bson::DateTime>,
+
+    #[serde(default)]
+    attempts: u32,
+
+    /// While set to a future date, the job is claimed by a running scheduler.
+    #[serde(default)]
+    locked_until: Option<mongodb::bson::DateTime>,
+
+    #[serde(default)]
+    last_error: Option<String>,
+}
+
+impl ScheduledJob {
+    fn collection_helper() -> CollectionHelper {
+        let database_name = get_database_name();
+        let collection_name = get_scheduled_jobs_collection_name();
+
+        CollectionHelper::new(database_name, collection_name)
+    }
+
+    fn id_filter(
+        &self,
+    ) -> Result<mongodb::bson::Document, Error> {
+        let Some(id) = self.id else {
+            return Err(Error::from("Scheduled job does not have an id."));
+        };
+
+        Ok(mongodb::bson::doc! { "_id": id })
+    }
+
+    /// Stores a new job, replacing any existing job with the same dedupe key.
+    pub async fn create(
+        payload: ScheduledJobPayload,
+        run_at: chrono::DateTime<chrono::Utc>,
+        dedupe_key: Option<String>,
+    ) -> Result<ScheduledJob, Error> {
+        if let Some(dedupe_key) = &dedupe_key {
+            ScheduledJob::delete_by_dedupe_key(dedupe_key).await?;
+        }
+
+        let scheduled_job = ScheduledJob::collection_helper().set(
+            ScheduledJob {
+                id: None,
+                dedupe_key: dedupe_key,
+                payload: payload,
+                run_at: mongodb::bson::DateTime::from_millis(run_at.timestamp_millis()),
+                first_run_at: Some(mongodb::bson::DateTime::from_millis(run_at.timestamp_millis())),
+                attempts: 0,
+                locked_until: None,
+                last_error: None,
+            }
+        ).await?;
+
+        Ok(scheduled_job)
+    }
+
+    /// Atomically claims a single due job that is not claimed by another scheduler.
+    /// Claiming increments the job's attempts and locks it until the lease expires.
+    pub async fn claim_due(
+        lease: chrono::Duration,
+    ) -> Result<Option<ScheduledJob>, Error> {
+        let now = chrono::Utc::now();
+        let now_bson = mongodb::bson::DateTime::from_millis(now.timestamp_millis());
+        let lease_expiry_bson = mongodb::bson::DateTime::from_millis((now + lease).timestamp_millis());
+
+        let scheduled_job = ScheduledJob::collection_helper().find_one_and_update::<ScheduledJob>(
+            mongodb::bson::doc! {
+                "run_at": { "$lte": now_bson },
+                "$or": [
+                    { "locked_until": null },
+                    { "locked_until": { "$lte": now_bson } },
+                ],
+            },
+            mongodb::bson::doc! {
+                "$set": {
+                    "locked_until": lease_expiry_bson,
+                },
+                "$inc": {
+                    "attempts": 1,
+                },
+            }
+        ).await?;
+
+        Ok(scheduled_job)
+    }
+
+    /// Releases the job so that it runs again at a later time.
+    pub async fn reschedule(
+        &self,
+        run_at: chrono::DateTime<chrono::Utc>,
+        last_error: String,
+    ) -> Result<(), Error> {
+        ScheduledJob::collection_helper().update::<ScheduledJob>(
+            self.id_filter()?,
+            mongodb::bson::doc! {
+                "$set": {
+                    "run_at": mongodb::bson::DateTime::from_millis(run_at.timestamp_millis()),
+                    "locked_until": null,
+                    "last_error": last_error,
+                },
+            }
+        ).await?;
+
+        Ok(())
+    }
+
+    /// Makes the job with the dedupe key due immediately, but only if it has already failed since it was due.
+    pub async fn expedite_failed_by_dedupe_key(
+        dedupe_key: &str,
+    ) -> Result<(), Error> {
+        ScheduledJob::collection_helper().update::<ScheduledJob>(
+            mongodb::bson::doc! {
+                "dedupe_key": dedupe_key,
+                "attempts": { "$gt": 0 },
+            },
+            mongodb::bson::doc! {
+                "$set": {
+                    "run_at": mongodb::bson::DateTime::from_millis(chrono::Utc::now().timestamp_millis()),
+                },
+            }
+        ).await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(
+        self,
+    ) -> Result<(), Error> {
+        ScheduledJob::collection_helper().delete::<ScheduledJob>(self.id_filter()?).await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_by_dedupe_key(
+        dedupe_key: &str,
+    ) -> Result<(), Error> {
+        ScheduledJob::collection_helper().delete::<ScheduledJob>(
+            mongodb::bson::doc! {
+                "dedupe_key": dedupe_key,
+            }
+        ).await?;
+
+        Ok(())
+    }
+
+    pub async fn get_payload(
+        &self,
+    ) -> ScheduledJobPayload {
+        self.payload.clone()
+    }
+
+    pub async fn get_attempts(
+        &self,
+    ) -> u32 {
+        self.attempts
+    }
+
+    /// When the job was first due, or when it was created for jobs that didn't record it.
+    pub async fn get_first_run_at(
+        &self,
+    ) -> chrono::DateTime<chrono::Utc> {
+        let first_run_at =
+            self.first_run_at
+            .or(self.id.map(|id| id.timestamp()))
+            .unwrap_or(self.run_at);
+
+        chrono::DateTime::from_timestamp_millis(first_run_at.timestamp_millis()).unwrap_or_default()
+    }
+}
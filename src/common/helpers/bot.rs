@@ -43,6 +43,28 @@ pub fn create_escaped_code_block(
 
 //------------------------------------------------------------//
 
+/// Whether discord responded that the requested resource (such as a guild member) doesn't exist.
+pub fn is_unknown_resource_error(
+    error: &serenity::Error,
+) -> bool {
+    match error {
+        serenity::Error::Http(http_error) => http_error.status_code().is_some_and(|status_code| status_code.as_u16() == 404),
+        _ => false,
+    }
+}
+
+/// Whether discord responded that the bot lacks access or permissions (e.g. after being removed from a guild).
+pub fn is_forbidden_error(
+    error: &serenity::Error,
+) -> bool {
+    match error {
+        serenity::Error::Http(http_error) => http_error.status_code().is_some_and(|status_code| status_code.as_u16() == 403),
+        _ => false,
+    }
+}
+
+//------------------------------------------------------------//
+
 pub fn generate_bot_invite_url(
     bot_id: u64,
 ) -> String {
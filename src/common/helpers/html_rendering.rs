@@ -22,9 +22,9 @@ pub fn escape_html(
 ) -> String {
     raw_html
     .replace("<script>", "")
+    .replace("&", "&amp;") // must be first, to avoid escaping the other entities
     .replace("<", "&lt;")
     .replace(">", "&gt;")
-    .replace("&", "&amp;")
     .replace("\"", "&quot;")
     .replace("'", "&apos;")
     .replace("`", "&grave;")
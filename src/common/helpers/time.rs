@@ -2,6 +2,16 @@
 //                   Copyright (c) MidSpike                   //
 //------------------------------------------------------------//
 
+use poise::serenity_prelude::{self as serenity, CreateAutocompleteResponse};
+
+//------------------------------------------------------------//
+
+use crate::Context;
+
+use crate::Error;
+
+//------------------------------------------------------------//
+
 pub fn format_duration(
     duration: std::time::Duration,
 ) -> String {
@@ -44,3 +54,158 @@ pub fn format_duration(
 
     formatted_duration.trim().into()
 }
+
+//------------------------------------------------------------//
+
+/// Durations suggested while typing, from shortest to longest.
+const SUGGESTED_DURATIONS: &[&str] = &[
+    "30s", "1m", "5m", "10m", "15m", "30m", "1h", "2h", "6h", "12h", "1d", "3d", "1w", "2w",
+];
+
+fn create_invalid_duration_error(
+    value: &str,
+) -> Error {
+    Error::from(format!("`{}` is not a valid duration, try something like `90s`, `15m`, `2h30m`, `1d`, or `4:20`.", value))
+}
+
+fn get_duration_unit_seconds(
+    unit: &str,
+) -> Option<u64> {
+    match unit {
+        "s" | "sec" | "secs" | "second" | "seconds" => Some(1),
+        "m" | "min" | "mins" | "minute" | "minutes" => Some(60),
+        "h" | "hr" | "hrs" | "hour" | "hours" => Some(60 * 60),
+        "d" | "day" | "days" => Some(24 * 60 * 60),
+        "w" | "wk" | "wks" | "week" | "weeks" => Some(7 * 24 * 60 * 60),
+        _ => None,
+    }
+}
+
+/// Parses clock notation, e.g. `4:20` (minutes and seconds) or `1:04:20` (hours, minutes, and seconds).
+fn parse_clock_duration_seconds(
+    value: &str,
+) -> Option<u64> {
+    let parts = value.split(':').map(|part| part.parse::<u64>().ok()).collect::<Option<Vec<u64>>>()?;
+
+    // every part after the first must fit within a clock
+    if parts.iter().skip(1).any(|part| *part >= 60) {
+        return None;
+    }
+
+    match parts.as_slice() {
+        [minutes, seconds] => minutes.checked_mul(60)?.checked_add(*seconds),
+        [hours, minutes, seconds] => hours.checked_mul(60 * 60)?.checked_add(minutes * 60 + seconds),
+        _ => None,
+    }
+}
+
+/// Parses unit notation, e.g. `90s`, `15m`, `2h30m`, `1d`, or `1w`.
+fn parse_unit_duration_seconds(
+    value: &str,
+) -> Option<u64> {
+    let mut total_seconds: u64 = 0;
+
+    let mut chars = value.chars().peekable();
+
+    while chars.peek().is_some() {
+        let mut number = String::new();
+        while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+            number.push(c);
+        }
+
+        let mut unit = String::new();
+        while let Some(c) = chars.next_if(|c| c.is_ascii_alphabetic()) {
+            unit.push(c);
+        }
+
+        let number = number.parse::<u64>().ok()?;
+        let unit_seconds = get_duration_unit_seconds(&unit)?;
+
+        total_seconds = total_seconds.checked_add(number.checked_mul(unit_seconds)?)?;
+    }
+
+    Some(total_seconds)
+}
+
+/// Parses a human-friendly duration, the counterpart of `format_duration`.
+///
+/// Understands unit notation (`90s`, `15m`, `2h30m`, `1d`, `1w`) and clock notation (`mm:ss`, `hh:mm:ss`).
+/// A number without a unit is treated as seconds.
+pub fn parse_duration(
+    value: &str,
+) -> Result<std::time::Duration, Error> {
+    let normalized_value =
+        value
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase();
+
+    if normalized_value.is_empty() {
+        return Err(create_invalid_duration_error(value));
+    }
+
+    let seconds =
+        if normalized_value.contains(':') {
+            parse_clock_duration_seconds(&normalized_value)
+        } else if normalized_value.chars().all(|c| c.is_ascii_digit()) {
+            normalized_value.parse::<u64>().ok()
+        } else {
+            parse_unit_duration_seconds(&normalized_value)
+        };
+
+    match seconds {
+        Some(seconds) => Ok(std::time::Duration::from_secs(seconds)),
+        None => Err(create_invalid_duration_error(value)),
+    }
+}
+
+/// Parses a human-friendly duration (see `parse_duration`) that must be within the bounds (inclusive).
+pub fn parse_duration_within(
+    value: &str,
+    min: std::time::Duration,
+    max: std::time::Duration,
+) -> Result<std::time::Duration, Error> {
+    let duration = parse_duration(value)?;
+
+    if duration < min || duration > max {
+        let bounds_string =
+            if min == std::time::Duration::ZERO {
+                format!("at most {}", format_duration(max))
+            } else {
+                format!("between {} and {}", format_duration(min), format_duration(max))
+            };
+
+        return Err(format!("The duration must be {}.", bounds_string).into());
+    }
+
+    Ok(duration)
+}
+
+/// Suggests durations for a command parameter, starting with the interpretation of what was typed.
+pub async fn autocomplete_duration<'a>(
+    _ctx: Context<'_>,
+    partial: &'a str,
+) -> CreateAutocompleteResponse<'a> {
+    let lowercase_user_input = partial.trim().to_lowercase();
+
+    let mut choices = Vec::new();
+
+    if let Ok(duration) = parse_duration(&lowercase_user_input) {
+        choices.push(serenity::AutocompleteChoice::new(format_duration(duration), lowercase_user_input.clone()));
+    }
+
+    let suggested_choices =
+        SUGGESTED_DURATIONS
+        .iter()
+        .filter(|suggestion| suggestion.starts_with(&lowercase_user_input) && **suggestion != lowercase_user_input)
+        .filter_map(|suggestion| {
+            let duration = parse_duration(suggestion).ok()?;
+
+            Some(serenity::AutocompleteChoice::new(format_duration(duration), suggestion.to_string()))
+        });
+
+    choices.extend(suggested_choices);
+
+    CreateAutocompleteResponse::new().set_choices(choices)
+}
@@ -0,0 +1,483 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::serenity_prelude::nonmax::NonMaxU16;
+use poise::serenity_prelude::{self as serenity, Mentionable};
+
+//------------------------------------------------------------//
+
+use crate::Context;
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::common::helpers::bot::{create_escaped_code_block, is_unknown_resource_error, simple_confirmation_embed};
+
+use crate::common::moderation;
+
+use crate::common::database::interfaces::moderation_case::ModerationCaseAction;
+
+use crate::common::scheduler;
+
+//------------------------------------------------------------//
+
+const MAX_MASS_MODERATION_TARGETS: usize = 500;
+
+/// The largest file of user ids that will be read.
+const MAX_USER_IDS_FILE_SIZE: u32 = 256 * 1024;
+
+const DISCORD_MEMBERS_PAGE_SIZE: u16 = 1000;
+
+/// The progress message is updated after each batch.
+const MASS_MODERATION_BATCH_SIZE: usize = 10;
+
+/// The maximum amount of failures to list in the summary.
+const MAX_LISTED_FAILURES: usize = 10;
+
+//------------------------------------------------------------//
+
+#[derive(Clone, Copy)]
+pub enum MassModerationAction {
+    Ban {
+        delete_message_days: u8,
+    },
+    Kick,
+}
+
+impl MassModerationAction {
+    fn past_tense(
+        &self,
+    ) -> &'static str {
+        match self {
+            MassModerationAction::Ban { .. } => "banned",
+            MassModerationAction::Kick => "kicked",
+        }
+    }
+
+    fn present_participle(
+        &self,
+    ) -> &'static str {
+        match self {
+            MassModerationAction::Ban { .. } => "banning",
+            MassModerationAction::Kick => "kicking",
+        }
+    }
+
+    fn moderation_case_action(
+        &self,
+    ) -> ModerationCaseAction {
+        match self {
+            MassModerationAction::Ban { .. } => ModerationCaseAction::MassBan,
+            MassModerationAction::Kick => ModerationCaseAction::MassKick,
+        }
+    }
+}
+
+/// Where the targets of a mass moderation action come from, every source is combined.
+pub struct MassModerationTargetSources {
+    pub user_ids: Option<String>,
+    pub user_ids_file: Option<serenity::Attachment>,
+    pub joined_within_minutes: Option<u32>,
+}
+
+//------------------------------------------------------------//
+
+/// Parses user ids (or user mentions) separated by whitespace, commas, or semicolons.
+/// Returns the parsed user ids along with the values that could not be parsed.
+fn parse_user_ids(
+    value: &str,
+) -> (Vec<serenity::UserId>, Vec<String>) {
+    let mut user_ids = Vec::new();
+    let mut invalid_values = Vec::new();
+
+    let values =
+        value
+        .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+        .filter(|value| !value.is_empty());
+
+    for value in values {
+        let trimmed_value = value.trim_start_matches("<@").trim_start_matches('!').trim_end_matches('>');
+
+        match trimmed_value.parse::<u64>() {
+            Ok(user_id) if user_id > 0 => user_ids.push(serenity::UserId::new(user_id)),
+            _ => invalid_values.push(value.to_string()),
+        }
+    }
+
+    (user_ids, invalid_values)
+}
+
+/// Fetches every member of the guild that joined within the specified amount of minutes.
+async fn fetch_recently_joined_user_ids(
+    http: &serenity::Http,
+    guild_id: serenity::GuildId,
+    joined_within_minutes: u32,
+) -> Result<Vec<serenity::UserId>, Error> {
+    let joined_after = chrono::Utc::now() - chrono::Duration::minutes(joined_within_minutes as i64);
+
+    let mut user_ids = Vec::new();
+
+    let mut cursor_user_id = None;
+
+    loop {
+        let members = guild_id.members(
+            http,
+            Some(NonMaxU16::new(DISCORD_MEMBERS_PAGE_SIZE).unwrap_or(NonMaxU16::MAX)),
+            cursor_user_id,
+        ).await?;
+
+        let Some(last_member) = members.last() else {
+            break; // every member was fetched
+        };
+
+        cursor_user_id = Some(last_member.user.id);
+
+        for member in &members {
+            let Some(joined_at) = member.joined_at else {
+                continue;
+            };
+
+            if joined_at.unix_timestamp() >= joined_after.timestamp() {
+                user_ids.push(member.user.id);
+            }
+        }
+
+        if members.len() < DISCORD_MEMBERS_PAGE_SIZE as usize {
+            break; // this was the last page
+        }
+
+        // throttle our requests to avoid hitting the rate limit
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+
+    Ok(user_ids)
+}
+
+/// Combines the user ids from every source, without duplicates.
+async fn resolve_target_user_ids(
+    ctx: &Context<'_>,
+    sources: MassModerationTargetSources,
+) -> Result<Vec<serenity::UserId>, Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    let mut user_ids = Vec::new();
+    let mut invalid_values = Vec::new();
+
+    if let Some(value) = &sources.user_ids {
+        let (parsed_user_ids, parsed_invalid_values) = parse_user_ids(value);
+
+        user_ids.extend(parsed_user_ids);
+        invalid_values.extend(parsed_invalid_values);
+    }
+
+    if let Some(user_ids_file) = &sources.user_ids_file {
+        if user_ids_file.size > MAX_USER_IDS_FILE_SIZE {
+            return Err("The file of user ids must not be larger than 256 KiB.".into());
+        }
+
+        let file_bytes = user_ids_file.download().await?;
+
+        let Ok(file_content) = String::from_utf8(file_bytes) else {
+            return Err("The file of user ids must be a text file.".into());
+        };
+
+        let (parsed_user_ids, parsed_invalid_values) = parse_user_ids(&file_content);
+
+        user_ids.extend(parsed_user_ids);
+        invalid_values.extend(parsed_invalid_values);
+    }
+
+    if let Some(joined_within_minutes) = sources.joined_within_minutes {
+        user_ids.extend(fetch_recently_joined_user_ids(ctx.http(), guild_id, joined_within_minutes).await?);
+    }
+
+    if let Some(invalid_value) = invalid_values.first() {
+        return Err(format!("`{}` is not a valid user id.", invalid_value).into());
+    }
+
+    let mut unique_user_ids = Vec::new();
+
+    for user_id in user_ids {
+        if !unique_user_ids.contains(&user_id) {
+            unique_user_ids.push(user_id);
+        }
+    }
+
+    Ok(unique_user_ids)
+}
+
+//------------------------------------------------------------//
+
+/// Ensures that both the executing member and this bot are above the target member in the role hierarchy.
+async fn assert_may_moderate_member(
+    ctx: &Context<'_>,
+    executing_member: &serenity::Member,
+    my_guild_member: &serenity::Member,
+    target_member: &serenity::Member,
+) -> Result<(), Error> {
+    moderation::assert_member_above_other_member(
+        ctx,
+        executing_member,
+        target_member,
+        "This member has an equal or higher role than you.",
+    ).await?;
+
+    moderation::assert_member_above_other_member(
+        ctx,
+        my_guild_member,
+        target_member,
+        "This member has an equal or higher role than me.",
+    ).await?;
+
+    Ok(())
+}
+
+/// Performs the action on a single user.
+/// Users are not notified, since mass actions are mostly used against raids.
+async fn perform_action_on_user(
+    ctx: &Context<'_>,
+    action: MassModerationAction,
+    executing_member: &serenity::Member,
+    my_guild_member: &serenity::Member,
+    user_id: serenity::UserId,
+    reason: &str,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().expect("There should be a guild in this context.");
+
+    // users that are not members can still be banned, but not kicked
+    // any other failure counts against this user, since the hierarchy checks couldn't be performed
+    let target_member = match guild_id.member(ctx.http(), user_id).await {
+        Ok(target_member) => Some(target_member),
+        Err(why) if is_unknown_resource_error(&why) => None,
+        Err(why) => return Err(why.into()),
+    };
+
+    if let Some(target_member) = &target_member {
+        assert_may_moderate_member(ctx, executing_member, my_guild_member, target_member).await?;
+    }
+
+    match action {
+        MassModerationAction::Ban { delete_message_days } => {
+            guild_id.ban(ctx.http(), user_id, delete_message_days, Some(reason)).await?;
+
+            // a permanent ban supersedes any scheduled unban of a temporary ban
+            scheduler::cancel_job(&scheduler::create_unban_dedupe_key(guild_id, user_id)).await?;
+        },
+        MassModerationAction::Kick => {
+            if target_member.is_none() {
+                return Err("This user is not a member of this guild.".into());
+            }
+
+            guild_id.kick(ctx.http(), user_id, Some(reason)).await?;
+        },
+    }
+
+    Ok(())
+}
+
+fn create_progress_embed<'a>(
+    action: MassModerationAction,
+    executing_member: &serenity::Member,
+    processed_amount: usize,
+    total_amount: usize,
+    reason: &str,
+) -> serenity::CreateEmbed<'a> {
+    serenity::CreateEmbed::default()
+    .color(branding::color::PRIMARY)
+    .title("Moderation")
+    .description(
+        format!(
+            "{} is {} {} users ({}/{} processed) for:\n{}",
+            executing_member.user.mention(),
+            action.present_participle(),
+            total_amount,
+            processed_amount,
+            total_amount,
+            create_escaped_code_block(None, reason),
+        )
+    )
+}
+
+fn create_failures_string(
+    failures: &[(serenity::UserId, String)],
+) -> String {
+    let mut failures_string =
+        failures
+        .iter()
+        .take(MAX_LISTED_FAILURES)
+        .map(|(user_id, why)| format!("- {} (`{}`): {}", user_id.mention(), user_id, why))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    if failures.len() > MAX_LISTED_FAILURES {
+        failures_string.push_str(&format!("\n...and {} more", failures.len() - MAX_LISTED_FAILURES));
+    }
+
+    failures_string
+}
+
+//------------------------------------------------------------//
+
+/// Resolves the targets from the sources, and then performs the action on them.
+pub async fn perform_mass_moderation_action(
+    ctx: &Context<'_>,
+    action: MassModerationAction,
+    sources: MassModerationTargetSources,
+    reason: &str,
+) -> Result<(), Error> {
+    if
+        sources.user_ids.is_none() &&
+        sources.user_ids_file.is_none() &&
+        sources.joined_within_minutes.is_none()
+    {
+        return Err("At least one of the user ids, the file of user ids, or the join window must be specified.".into());
+    }
+
+    // resolving the targets may take a while
+    ctx.defer().await?;
+
+    let target_user_ids = resolve_target_user_ids(ctx, sources).await?;
+
+    perform_mass_moderation_action_on_users(ctx, action, &target_user_ids, MAX_MASS_MODERATION_TARGETS, reason).await
+}
+
+/// Asks for confirmation, and then performs the action on each target, refusing more than `max_targets` targets.
+/// Progress is reported while the action is performed, followed by a summary of the successes and failures.
+pub async fn perform_mass_moderation_action_on_users(
+    ctx: &Context<'_>,
+    action: MassModerationAction,
+    target_user_ids: &[serenity::UserId],
+    max_targets: usize,
+    reason: &str,
+) -> Result<(), Error> {
+    let executing_member =
+        ctx
+        .author_member().await
+        .expect("There should be a member in this context.")
+        .clone();
+
+    let guild = ctx.guild().expect("There should be a guild in this context.").clone();
+
+    let my_id = ctx.serenity_context().cache.current_user().id;
+
+    let my_guild_member =
+        guild
+        .member(&ctx.http(), my_id).await
+        .expect("I should be in this guild.")
+        .clone();
+
+    if target_user_ids.is_empty() {
+        return Err("No users matched the specified criteria.".into());
+    }
+
+    if target_user_ids.len() > max_targets {
+        return Err(format!("At most {} users can be targeted at once.", max_targets).into());
+    }
+
+    let is_confirmed = simple_confirmation_embed(
+        ctx,
+        &format!(
+            "Are you sure that you want to {} {} users for:\n{}",
+            match action {
+                MassModerationAction::Ban { .. } => "ban",
+                MassModerationAction::Kick => "kick",
+            },
+            target_user_ids.len(),
+            create_escaped_code_block(None, reason),
+        ),
+    ).await?;
+
+    if !is_confirmed {
+        ctx.send(
+            poise::CreateReply::default()
+            .embed(
+                serenity::CreateEmbed::default()
+                .color(branding::color::PRIMARY)
+                .title("Moderation")
+                .description("Cancelled, no users were affected.")
+            )
+        ).await?;
+
+        return Ok(());
+    }
+
+    let reply_handle = ctx.send(
+        poise::CreateReply::default()
+        .embed(create_progress_embed(action, &executing_member, 0, target_user_ids.len(), reason))
+    ).await?;
+
+    let mut succeeded_user_ids = Vec::new();
+    let mut failures = Vec::new();
+
+    for (batch_index, batch) in target_user_ids.chunks(MASS_MODERATION_BATCH_SIZE).enumerate() {
+        for user_id in batch {
+            match perform_action_on_user(ctx, action, &executing_member, &my_guild_member, *user_id, reason).await {
+                Ok(_) => succeeded_user_ids.push(*user_id),
+                Err(why) => failures.push((*user_id, why.to_string())),
+            }
+
+            // throttle our requests to avoid hitting the rate limit
+            tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+        }
+
+        let processed_amount = (batch_index * MASS_MODERATION_BATCH_SIZE) + batch.len();
+
+        if processed_amount < target_user_ids.len() {
+            reply_handle.edit(
+                poise::Context::Application(*ctx),
+                poise::CreateReply::default()
+                .embed(create_progress_embed(action, &executing_member, processed_amount, target_user_ids.len(), reason))
+            ).await?;
+        }
+    }
+
+    let moderation_case = moderation::create_moderation_case(
+        ctx,
+        action.moderation_case_action(),
+        None,
+        &format!(
+            "{}\n\n{} of {} users were {}.",
+            reason,
+            succeeded_user_ids.len(),
+            target_user_ids.len(),
+            action.past_tense(),
+        ),
+        None,
+    ).await?;
+
+    let case_id = moderation_case.get_case_id().await;
+
+    let mut summary_embed =
+        serenity::CreateEmbed::default()
+        .color(branding::color::PRIMARY)
+        .title("Moderation")
+        .description(
+            format!(
+                "{} {} {} of {} users for:\n{}",
+                executing_member.user.mention(),
+                action.past_tense(),
+                succeeded_user_ids.len(),
+                target_user_ids.len(),
+                create_escaped_code_block(None, reason),
+            )
+        )
+        .footer(moderation::create_moderation_case_footer(case_id));
+
+    if !failures.is_empty() {
+        summary_embed = summary_embed.field(
+            format!("Failed ({})", failures.len()),
+            create_failures_string(&failures),
+            false,
+        );
+    }
+
+    reply_handle.edit(
+        poise::Context::Application(*ctx),
+        poise::CreateReply::default()
+        .embed(summary_embed)
+    ).await?;
+
+    Ok(())
+}
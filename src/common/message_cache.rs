@@ -0,0 +1,146 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use std::collections::{HashMap, VecDeque};
+
+use std::sync::{LazyLock, Mutex};
+
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+/// The maximum amount of messages retained per guild, the oldest are evicted first.
+const MESSAGE_CACHE_MAX_MESSAGES_PER_GUILD: usize = 1000;
+
+/// Messages older than this are evicted, regardless of how many are retained.
+const MESSAGE_CACHE_MAX_AGE_SECONDS: i64 = 6 * 60 * 60;
+
+//------------------------------------------------------------//
+
+#[derive(Debug, Clone)]
+pub struct CachedMessage {
+    pub message_id: serenity::MessageId,
+    pub channel_id: serenity::GenericChannelId,
+    pub author_id: serenity::UserId,
+    pub content: String,
+    pub attachment_urls: Vec<String>,
+    pub edited_timestamp: Option<serenity::Timestamp>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl CachedMessage {
+    fn from_message(
+        message: &serenity::Message,
+    ) -> Self {
+        Self {
+            message_id: message.id,
+            channel_id: message.channel_id,
+            author_id: message.author.id,
+            content: message.content.to_string(),
+            attachment_urls: message.attachments.iter().map(|attachment| attachment.url.to_string()).collect(),
+            edited_timestamp: message.edited_timestamp,
+            created_at: chrono::Utc::now(),
+            deleted_at: None,
+        }
+    }
+}
+
+//------------------------------------------------------------//
+
+static MESSAGE_CACHE: LazyLock<Mutex<HashMap<serenity::GuildId, VecDeque<CachedMessage>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn evict_expired_messages(
+    guild_messages: &mut VecDeque<CachedMessage>,
+) {
+    let cutoff = chrono::Utc::now() - chrono::Duration::seconds(MESSAGE_CACHE_MAX_AGE_SECONDS);
+
+    // messages are stored oldest first
+    while guild_messages.front().is_some_and(|cached_message| cached_message.created_at < cutoff) {
+        guild_messages.pop_front();
+    }
+
+    while guild_messages.len() > MESSAGE_CACHE_MAX_MESSAGES_PER_GUILD {
+        guild_messages.pop_front();
+    }
+}
+
+//------------------------------------------------------------//
+
+pub fn cache_message(
+    guild_id: serenity::GuildId,
+    message: &serenity::Message,
+) {
+    let mut message_cache = MESSAGE_CACHE.lock().expect("message cache lock should not be poisoned");
+
+    let guild_messages = message_cache.entry(guild_id).or_default();
+
+    guild_messages.push_back(CachedMessage::from_message(message));
+
+    evict_expired_messages(guild_messages);
+}
+
+/// Updates the content of a cached message, returning the previously cached version.
+pub fn update_cached_message(
+    guild_id: serenity::GuildId,
+    message: &serenity::Message,
+) -> Option<CachedMessage> {
+    let mut message_cache = MESSAGE_CACHE.lock().expect("message cache lock should not be poisoned");
+
+    let guild_messages = message_cache.get_mut(&guild_id)?;
+
+    let cached_message = guild_messages.iter_mut().find(|cached_message| cached_message.message_id == message.id)?;
+
+    let previous_cached_message = cached_message.clone();
+
+    cached_message.content = message.content.to_string();
+    cached_message.edited_timestamp = message.edited_timestamp;
+
+    Some(previous_cached_message)
+}
+
+/// Marks a cached message as deleted (so that it can be sniped), returning it.
+pub fn mark_cached_message_deleted(
+    guild_id: serenity::GuildId,
+    message_id: serenity::MessageId,
+) -> Option<CachedMessage> {
+    let mut message_cache = MESSAGE_CACHE.lock().expect("message cache lock should not be poisoned");
+
+    let guild_messages = message_cache.get_mut(&guild_id)?;
+
+    let cached_message = guild_messages.iter_mut().find(|cached_message| cached_message.message_id == message_id)?;
+
+    cached_message.deleted_at = Some(chrono::Utc::now());
+
+    Some(cached_message.clone())
+}
+
+/// Returns the most recently deleted message in a channel.
+pub fn get_last_deleted_message(
+    guild_id: serenity::GuildId,
+    channel_id: serenity::GenericChannelId,
+) -> Option<CachedMessage> {
+    let mut message_cache = MESSAGE_CACHE.lock().expect("message cache lock should not be poisoned");
+
+    let guild_messages = message_cache.get_mut(&guild_id)?;
+
+    evict_expired_messages(guild_messages);
+
+    guild_messages
+    .iter()
+    .filter(|cached_message| cached_message.channel_id == channel_id)
+    .filter(|cached_message| cached_message.deleted_at.is_some())
+    .max_by_key(|cached_message| cached_message.deleted_at)
+    .cloned()
+}
+
+/// Removes every cached message for a guild.
+pub fn clear_guild_messages(
+    guild_id: serenity::GuildId,
+) {
+    let mut message_cache = MESSAGE_CACHE.lock().expect("message cache lock should not be poisoned");
+
+    message_cache.remove(&guild_id);
+}
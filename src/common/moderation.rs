@@ -2,7 +2,7 @@
 //                   Copyright (c) MidSpike                   //
 //------------------------------------------------------------//
 
-use poise::serenity_prelude::{self as serenity};
+use poise::serenity_prelude::{self as serenity, FormattedTimestamp, FormattedTimestampStyle, Mentionable};
 
 //------------------------------------------------------------//
 
@@ -10,6 +10,24 @@ use crate::Error;
 
 use crate::Context;
 
+use crate::common::branding;
+
+use crate::common::helpers::bot::create_escaped_code_block;
+
+use crate::common::helpers::time::format_duration;
+
+use crate::common::database::interfaces::guild_config::{
+    GuildConfig,
+    GuildConfigLoggingChannelKind,
+    GuildConfigModerationCapability,
+};
+
+use crate::common::database::interfaces::moderation_case::{
+    ModerationCase,
+    ModerationCaseAction,
+    ModerationCaseOptions,
+};
+
 //------------------------------------------------------------//
 
 pub const LACKING_PERMISSIONS_MESSAGE: &str = "You do not have permission to perform this action.";
@@ -56,6 +74,124 @@ pub async fn assert_guild_member_permitted_by_discord(
 
 //------------------------------------------------------------//
 
+/// The discord permissions that correspond to a moderation capability, all of which are required.
+/// Commands that only need some of these use `assert_member_has_moderation_capability_with_discord_permissions`.
+fn get_discord_permissions_for_capability(
+    capability: GuildConfigModerationCapability,
+) -> serenity::Permissions {
+    match capability {
+        GuildConfigModerationCapability::Warn => serenity::Permissions::MODERATE_MEMBERS,
+        GuildConfigModerationCapability::Timeout => serenity::Permissions::MODERATE_MEMBERS,
+        GuildConfigModerationCapability::Kick => serenity::Permissions::KICK_MEMBERS,
+        GuildConfigModerationCapability::Ban => serenity::Permissions::BAN_MEMBERS,
+        GuildConfigModerationCapability::Purge => serenity::Permissions::MANAGE_MESSAGES,
+        GuildConfigModerationCapability::Voice =>
+            serenity::Permissions::MUTE_MEMBERS |
+            serenity::Permissions::DEAFEN_MEMBERS |
+            serenity::Permissions::MOVE_MEMBERS,
+        GuildConfigModerationCapability::Channels => serenity::Permissions::MANAGE_CHANNELS,
+        GuildConfigModerationCapability::Cases => serenity::Permissions::MODERATE_MEMBERS,
+    }
+}
+
+/// Determines whether a member may use a moderation capability, given their permissions in the relevant channel.
+///
+/// Guild administrators are always permitted, as are members with a role that was granted the capability.
+/// Discord's own permissions only count when the guild's moderation mode allows discord overrides,
+/// or when the capability has not been granted to any role yet.
+pub async fn is_member_granted_moderation_capability(
+    member: &serenity::Member,
+    member_permissions: serenity::Permissions,
+    capability: GuildConfigModerationCapability,
+) -> Result<bool, Error> {
+    is_member_granted_moderation_capability_with_discord_permissions(
+        member,
+        member_permissions,
+        capability,
+        get_discord_permissions_for_capability(capability),
+    ).await
+}
+
+/// Like `is_member_granted_moderation_capability`, but the member must have all of the specified
+/// discord permissions instead of the ones that correspond to the capability.
+pub async fn is_member_granted_moderation_capability_with_discord_permissions(
+    member: &serenity::Member,
+    member_permissions: serenity::Permissions,
+    capability: GuildConfigModerationCapability,
+    discord_permissions: serenity::Permissions,
+) -> Result<bool, Error> {
+    // check if the user is a guild administrator
+    if member_permissions.administrator() {
+        return Ok(true);
+    }
+
+    let guild_config = GuildConfig::ensure(member.guild_id).await?;
+
+    let moderation_roles = guild_config.get_moderation_roles().await;
+
+    // check if the user has a role that was granted the capability
+    if moderation_roles.is_capability_granted(&member.roles, capability) {
+        return Ok(true);
+    }
+
+    let are_discord_permissions_counted =
+        guild_config.get_moderation_mode().await.are_discord_overrides_allowed().await ||
+        !moderation_roles.is_capability_granted_to_any_role(capability);
+
+    // check if the user has the corresponding discord permissions
+    Ok(
+        are_discord_permissions_counted &&
+        member_permissions.contains(discord_permissions)
+    )
+}
+
+/// Ensures that a member may use a moderation capability in this guild.
+/// See `is_member_granted_moderation_capability` for how this is determined.
+///
+/// Commands that rely on this do not declare `default_member_permissions`,
+/// otherwise discord would hide them from the roles that were granted capabilities by this bot.
+pub async fn assert_member_has_moderation_capability(
+    ctx: &Context<'_>,
+    member: &serenity::Member,
+    capability: GuildConfigModerationCapability,
+) -> Result<(), Error> {
+    assert_member_has_moderation_capability_with_discord_permissions(
+        ctx,
+        member,
+        capability,
+        get_discord_permissions_for_capability(capability),
+    ).await
+}
+
+/// Ensures that a member may use a moderation capability in this guild, where members without a granted role
+/// must have all of the specified discord permissions, e.g. only `MUTE_MEMBERS` for a command that mutes.
+pub async fn assert_member_has_moderation_capability_with_discord_permissions(
+    ctx: &Context<'_>,
+    member: &serenity::Member,
+    capability: GuildConfigModerationCapability,
+    discord_permissions: serenity::Permissions,
+) -> Result<(), Error> {
+    let guild_channel =
+        ctx.channel().await
+        .map(|c| c.guild()).flatten()
+        .expect("This channel should be in a guild.");
+
+    let member_perms_in_channel = {
+        let guild = ctx.guild().expect("There should be a guild.");
+
+        guild.user_permissions_in(&guild_channel, member)
+    };
+
+    if is_member_granted_moderation_capability_with_discord_permissions(member, member_perms_in_channel, capability, discord_permissions).await? {
+        return Ok(());
+    }
+
+    // the user does not have the required permissions
+    Err(Error::from(LACKING_PERMISSIONS_MESSAGE))
+}
+
+//------------------------------------------------------------//
+
 pub async fn assert_member_above_other_member(
     ctx: &Context<'_>,
     member: &serenity::Member,
@@ -100,3 +236,210 @@ pub async fn assert_member_above_other_member(
 
     Ok(())
 }
+
+//------------------------------------------------------------//
+
+/// Moves a member that is connected to voice into another voice channel.
+pub async fn relocate_member_in_voice_channel(
+    ctx: &Context<'_>,
+    member: &mut serenity::Member,
+    target_voice_channel_id: serenity::ChannelId,
+    audit_log_reason: &str,
+) -> Result<(), Error> {
+    member.edit(
+        &ctx.http(),
+        serenity::EditMember::default()
+        .voice_channel(target_voice_channel_id)
+        .audit_log_reason(audit_log_reason)
+    ).await?;
+
+    Ok(())
+}
+
+//------------------------------------------------------------//
+
+/// Describes who is performing a moderation action, and where.
+/// This allows moderation actions to be performed outside of commands (e.g. by automod).
+pub struct ModerationActionContext<'a> {
+    pub http: &'a serenity::Http,
+    pub guild_id: serenity::GuildId,
+    pub guild_name: String,
+    pub actor_user_id: serenity::UserId,
+    pub channel_id: Option<serenity::GenericChannelId>,
+}
+
+impl<'a> ModerationActionContext<'a> {
+    /// Creates a moderation action context for an action performed by the author of a command.
+    pub fn from_context(
+        ctx: &'a Context<'_>,
+    ) -> Self {
+        let guild = ctx.guild().expect("There should be a guild in this context.");
+
+        Self {
+            http: ctx.http(),
+            guild_id: guild.id,
+            guild_name: guild.name.to_string(),
+            actor_user_id: ctx.author().id,
+            channel_id: Some(ctx.channel_id()),
+        }
+    }
+}
+
+/// Records a moderation case for an action, and logs it to the moderation actions logging channel.
+pub async fn record_moderation_case(
+    action_ctx: &ModerationActionContext<'_>,
+    action: ModerationCaseAction,
+    target_user_id: Option<serenity::UserId>,
+    reason: &str,
+    duration_seconds: Option<u32>,
+) -> Result<ModerationCase, Error> {
+    let moderation_case = ModerationCase::create(
+        ModerationCaseOptions {
+            discord_guild_id: action_ctx.guild_id,
+            action: action,
+            actor_user_id: action_ctx.actor_user_id,
+            target_user_id: target_user_id,
+            channel_id: action_ctx.channel_id,
+            reason: reason.to_string(),
+            duration_seconds: duration_seconds,
+        }
+    ).await?;
+
+    // the action has already been performed, so failing to log it should not fail the action
+    if let Err(why) = log_moderation_case(action_ctx.http, &moderation_case).await {
+        eprintln!("Failed to log moderation case to the moderation actions logging channel: {:?}", why);
+    }
+
+    Ok(moderation_case)
+}
+
+/// Records a moderation case for an action performed by the author of this context.
+pub async fn create_moderation_case(
+    ctx: &Context<'_>,
+    action: ModerationCaseAction,
+    target_user_id: Option<serenity::UserId>,
+    reason: &str,
+    duration_seconds: Option<u32>,
+) -> Result<ModerationCase, Error> {
+    record_moderation_case(
+        &ModerationActionContext::from_context(ctx),
+        action,
+        target_user_id,
+        reason,
+        duration_seconds,
+    ).await
+}
+
+pub fn create_moderation_case_footer<'a>(
+    case_id: u32,
+) -> serenity::CreateEmbedFooter<'a> {
+    serenity::CreateEmbedFooter::new(format!("Case #{}", case_id))
+}
+
+/// Creates the standardized embed describing a moderation case.
+pub async fn create_moderation_case_embed<'a>(
+    moderation_case: &ModerationCase,
+) -> Result<serenity::CreateEmbed<'a>, Error> {
+    let created_at = moderation_case.get_created_at().await;
+
+    let created_at_timestamp = serenity::Timestamp::from_millis(created_at.timestamp_millis())?;
+    let created_at_relative_format =
+        FormattedTimestamp::new(created_at_timestamp, Some(FormattedTimestampStyle::RelativeTime));
+    let created_at_full_format =
+        FormattedTimestamp::new(created_at_timestamp, Some(FormattedTimestampStyle::FullDateShortTime));
+
+    let target_string = match moderation_case.get_target_user_id().await {
+        Some(user_id) => format!("{} (`{}`)", user_id.mention(), user_id),
+        None => "None".to_string(),
+    };
+
+    let channel_string = match moderation_case.get_channel_id().await {
+        Some(channel_id) => channel_id.mention().to_string(),
+        None => "Unknown".to_string(),
+    };
+
+    let duration_string = match moderation_case.get_duration_seconds().await {
+        Some(seconds) => format_duration(std::time::Duration::from_secs(seconds as u64)),
+        None => "None".to_string(),
+    };
+
+    let actor_user_id = moderation_case.get_actor_user_id().await;
+
+    let embed_fields = [
+        (
+            "Action",
+            format!("`{}`", moderation_case.get_action().await.name()),
+            true,
+        ),
+        (
+            "Moderator",
+            format!("{} (`{}`)", actor_user_id.mention(), actor_user_id),
+            true,
+        ),
+        (
+            "Target",
+            target_string,
+            true,
+        ),
+        (
+            "Channel",
+            channel_string,
+            true,
+        ),
+        (
+            "Duration",
+            duration_string,
+            true,
+        ),
+        (
+            "Created On",
+            format!("{} ({})", created_at_full_format, created_at_relative_format),
+            false,
+        ),
+        (
+            "Reason",
+            create_escaped_code_block(None, &moderation_case.get_reason().await),
+            false,
+        ),
+    ];
+
+    let embed =
+        serenity::CreateEmbed::default()
+        .color(branding::color::PRIMARY)
+        .title(format!("Moderation - Case #{}", moderation_case.get_case_id().await))
+        .fields(embed_fields);
+
+    Ok(embed)
+}
+
+/// Posts a moderation case to the guild's moderation actions logging channel, if one is set.
+pub async fn log_moderation_case(
+    http: &serenity::Http,
+    moderation_case: &ModerationCase,
+) -> Result<(), Error> {
+    let guild_id = moderation_case.get_discord_guild_id().await;
+
+    let embed = create_moderation_case_embed(moderation_case).await?;
+
+    send_moderation_actions_log_embed(http, guild_id, embed).await
+}
+
+/// Posts an embed to the guild's moderation actions logging channel, if one is set.
+pub async fn send_moderation_actions_log_embed(
+    http: &serenity::Http,
+    guild_id: serenity::GuildId,
+    embed: serenity::CreateEmbed<'_>,
+) -> Result<(), Error> {
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let Some(logging_channel_id) = guild_config.get_logging_channels().await.get(GuildConfigLoggingChannelKind::ModerationActions) else {
+        return Ok(()); // Graceful
+    };
+
+    logging_channel_id.send_message(
+        http,
+        serenity::CreateMessage::default().embed(embed)
+    ).await?;
+
+    Ok(())
+}
@@ -0,0 +1,211 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::serenity_prelude::{self as serenity, Mentionable};
+
+//------------------------------------------------------------//
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::common::helpers::bot::create_escaped_code_block;
+
+use crate::common::moderation;
+
+use crate::common::database::interfaces::guild_config::{
+    GuildConfig,
+    GuildConfigLoggingChannelKind,
+    GuildConfigModerationCapability,
+};
+
+use crate::common::database::interfaces::moderation_case::{
+    ModerationCase,
+    ModerationCaseAction,
+    ModerationCaseAppeal,
+};
+
+//------------------------------------------------------------//
+
+/// Every appeal component's custom id starts with this, so that they can be routed after restarts.
+pub const MODERATION_APPEAL_CUSTOM_ID_PREFIX: &str = "moderation_appeal";
+
+//------------------------------------------------------------//
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModerationAppealComponentKind {
+    /// Sent to the target of a case, to submit an appeal.
+    Open,
+
+    /// Sent to the appeals channel, to accept an appeal.
+    Accept,
+
+    /// Sent to the appeals channel, to deny an appeal.
+    Deny,
+}
+
+impl ModerationAppealComponentKind {
+    fn name(
+        &self,
+    ) -> &'static str {
+        match self {
+            ModerationAppealComponentKind::Open => "open",
+            ModerationAppealComponentKind::Accept => "accept",
+            ModerationAppealComponentKind::Deny => "deny",
+        }
+    }
+
+    fn from_name(
+        name: &str,
+    ) -> Option<Self> {
+        match name {
+            "open" => Some(ModerationAppealComponentKind::Open),
+            "accept" => Some(ModerationAppealComponentKind::Accept),
+            "deny" => Some(ModerationAppealComponentKind::Deny),
+            _ => None,
+        }
+    }
+}
+
+/// Identifies the case that an appeal component belongs to.
+/// Formatted as `moderation_appeal:<kind>:<guild_id>:<case_id>`.
+#[derive(Debug, Clone, Copy)]
+pub struct ModerationAppealComponentId {
+    pub kind: ModerationAppealComponentKind,
+    pub guild_id: serenity::GuildId,
+    pub case_id: u32,
+}
+
+impl ModerationAppealComponentId {
+    pub fn to_custom_id(
+        &self,
+    ) -> String {
+        format!("{}:{}:{}:{}", MODERATION_APPEAL_CUSTOM_ID_PREFIX, self.kind.name(), self.guild_id, self.case_id)
+    }
+
+    pub fn parse(
+        custom_id: &str,
+    ) -> Option<Self> {
+        let mut parts = custom_id.split(':');
+
+        if parts.next()? != MODERATION_APPEAL_CUSTOM_ID_PREFIX {
+            return None;
+        }
+
+        let kind = ModerationAppealComponentKind::from_name(parts.next()?)?;
+        let guild_id = parts.next()?.parse::<u64>().ok().filter(|guild_id| *guild_id > 0)?;
+        let case_id = parts.next()?.parse::<u32>().ok()?;
+
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(Self {
+            kind: kind,
+            guild_id: serenity::GuildId::new(guild_id),
+            case_id: case_id,
+        })
+    }
+}
+
+//------------------------------------------------------------//
+
+/// Only actions that can be reversed (or that stop counting once accepted) may be appealed.
+pub fn is_action_appealable(
+    action: ModerationCaseAction,
+) -> bool {
+    matches!(
+        action,
+        ModerationCaseAction::Ban |
+        ModerationCaseAction::Timeout |
+        ModerationCaseAction::Warn
+    )
+}
+
+/// The moderation capability required to review appeals of an action.
+pub fn get_appeal_review_capability(
+    action: ModerationCaseAction,
+) -> GuildConfigModerationCapability {
+    match action {
+        ModerationCaseAction::Ban => GuildConfigModerationCapability::Ban,
+        ModerationCaseAction::Timeout => GuildConfigModerationCapability::Timeout,
+        _ => GuildConfigModerationCapability::Warn,
+    }
+}
+
+pub async fn fetch_appeals_channel_id(
+    guild_id: serenity::GuildId,
+) -> Result<Option<serenity::GenericChannelId>, Error> {
+    let Some(guild_config) = GuildConfig::fetch(guild_id).await? else {
+        return Ok(None);
+    };
+
+    Ok(guild_config.get_logging_channels().await.get(GuildConfigLoggingChannelKind::ModerationAppeals))
+}
+
+//------------------------------------------------------------//
+
+/// Adds an "Appeal" button to the message that notified the target of a case.
+/// Nothing is added if the case cannot be appealed, or if the guild has no appeals channel.
+pub async fn add_appeal_button_to_notification(
+    http: &serenity::Http,
+    notification_message: Option<serenity::Message>,
+    moderation_case: &ModerationCase,
+) -> Result<(), Error> {
+    let Some(mut notification_message) = notification_message else {
+        return Ok(()); // the target could not be notified
+    };
+
+    if !is_action_appealable(moderation_case.get_action().await) {
+        return Ok(());
+    }
+
+    let guild_id = moderation_case.get_discord_guild_id().await;
+
+    if fetch_appeals_channel_id(guild_id).await?.is_none() {
+        return Ok(());
+    }
+
+    let appeal_component_id = ModerationAppealComponentId {
+        kind: ModerationAppealComponentKind::Open,
+        guild_id: guild_id,
+        case_id: moderation_case.get_case_id().await,
+    };
+
+    notification_message.edit(
+        http,
+        serenity::EditMessage::default()
+        .components(vec![
+            serenity::CreateComponent::ActionRow(
+                serenity::CreateActionRow::buttons(vec![
+                    serenity::CreateButton::new(appeal_component_id.to_custom_id())
+                    .style(serenity::ButtonStyle::Secondary)
+                    .label("Appeal"),
+                ])
+            )
+        ])
+    ).await?;
+
+    Ok(())
+}
+
+/// Creates the embed used to review an appeal, describing both the case and the appeal.
+pub async fn create_appeal_review_embed<'a>(
+    moderation_case: &ModerationCase,
+    appeal: &ModerationCaseAppeal,
+) -> Result<serenity::CreateEmbed<'a>, Error> {
+    let status_string = match appeal.reviewer_user_id {
+        Some(reviewer_user_id) => format!("{} by {}", appeal.status.name(), reviewer_user_id.mention()),
+        None => appeal.status.name().to_string(),
+    };
+
+    let embed =
+        moderation::create_moderation_case_embed(moderation_case).await?
+        .color(branding::color::PRIMARY)
+        .title(format!("Moderation - Appeal of Case #{}", moderation_case.get_case_id().await))
+        .field("Appeal", create_escaped_code_block(None, &appeal.content), false)
+        .field("Status", status_string, false);
+
+    Ok(embed)
+}
@@ -6,12 +6,16 @@ use std::sync::Arc;
 
 //------------------------------------------------------------//
 
-use poise::serenity_prelude::{self as serenity};
+use poise::serenity_prelude::{self as serenity, CreateAutocompleteResponse};
 
 //------------------------------------------------------------//
 
+use crate::Context;
+
 use crate::Error;
 
+use crate::common::database::interfaces::guild_config::GuildConfigMusicSearchSource;
+
 //------------------------------------------------------------//
 
 pub const LAVALINK_VOLUME_MULTIPLIER: u16 = 4; // DO NOT CHANGE THIS
@@ -73,6 +77,155 @@ impl Volume {
 
 //------------------------------------------------------------//
 
+#[derive(poise::ChoiceParameter, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LoopMode {
+    #[default]
+    #[name = "Off"]
+    Off,
+
+    /// The current track is replayed when it finishes.
+    #[name = "Track"]
+    Track,
+
+    /// Finished tracks are added back to the end of the queue.
+    #[name = "Queue"]
+    Queue,
+}
+
+/// Where the tracks of a player are announced.
+#[derive(Clone)]
+pub struct MusicAnnouncementTarget {
+    pub http: Arc<serenity::Http>,
+    pub channel_id: serenity::GenericChannelId,
+}
+
+/// State that is kept per guild for as long as its player exists.
+/// Use `set_loop_mode` to change the loop mode of a player, since its queue must be updated as well.
+#[derive(Default)]
+pub struct MusicPlayerData {
+    loop_mode: std::sync::Mutex<LoopMode>,
+
+    announcement_target: std::sync::Mutex<Option<MusicAnnouncementTarget>>,
+}
+
+impl MusicPlayerData {
+    pub fn get_loop_mode(
+        &self,
+    ) -> LoopMode {
+        *self.loop_mode.lock().expect("The loop mode lock should not be poisoned.")
+    }
+
+    fn set_loop_mode(
+        &self,
+        loop_mode: LoopMode,
+    ) {
+        *self.loop_mode.lock().expect("The loop mode lock should not be poisoned.") = loop_mode;
+    }
+
+    pub fn get_announcement_target(
+        &self,
+    ) -> Option<MusicAnnouncementTarget> {
+        self.announcement_target.lock().expect("The announcement target lock should not be poisoned.").clone()
+    }
+
+    pub fn set_announcement_target(
+        &self,
+        announcement_target: MusicAnnouncementTarget,
+    ) {
+        *self.announcement_target.lock().expect("The announcement target lock should not be poisoned.") = Some(announcement_target);
+    }
+}
+
+pub fn get_player_data(
+    player_context: &lavalink_rs::prelude::PlayerContext,
+) -> Result<Arc<MusicPlayerData>, Error> {
+    Ok(player_context.data::<MusicPlayerData>()?)
+}
+
+//------------------------------------------------------------//
+
+// Lavalink-rs advances the queue by itself: when a track finishes (or fails to load), the
+// library pops the front of the queue and plays it. Starting tracks from our own track end
+// handler would race with that, so looping builds on it instead. While a track plays, a copy
+// of it (the "loop copy") is kept in the queue, in front when looping the track, or at the back
+// when looping the queue, which the library then plays like any other queued track.
+
+/// Adds the loop copy of a track that started playing to the queue.
+pub fn queue_loop_copy(
+    player_context: &lavalink_rs::prelude::PlayerContext,
+    loop_mode: LoopMode,
+    track: &lavalink_rs::model::track::TrackData,
+) -> Result<(), Error> {
+    let queue = player_context.get_queue();
+
+    match loop_mode {
+        LoopMode::Off => {},
+        LoopMode::Track => queue.push_to_front(track.clone())?,
+        LoopMode::Queue => queue.push_to_back(track.clone())?,
+    }
+
+    Ok(())
+}
+
+/// Removes the loop copy of a track from the queue, if it is still there.
+pub async fn dequeue_loop_copy(
+    player_context: &lavalink_rs::prelude::PlayerContext,
+    loop_mode: LoopMode,
+    track: &lavalink_rs::model::track::TrackData,
+) -> Result<(), Error> {
+    let queue = player_context.get_queue();
+
+    let queue_items = queue.get_queue().await?;
+
+    let loop_copy_index = match loop_mode {
+        LoopMode::Off => None,
+        LoopMode::Track => queue_items.front().filter(|item| item.track.encoded == track.encoded).map(|_| 0),
+        LoopMode::Queue => queue_items.iter().rposition(|item| item.track.encoded == track.encoded),
+    };
+
+    if let Some(loop_copy_index) = loop_copy_index {
+        queue.remove(loop_copy_index)?;
+    }
+
+    Ok(())
+}
+
+/// Changes the loop mode of a player, moving the loop copy of the current track to where the new mode expects it.
+pub async fn set_loop_mode(
+    player_context: &lavalink_rs::prelude::PlayerContext,
+    loop_mode: LoopMode,
+) -> Result<(), Error> {
+    let player_data = get_player_data(player_context)?;
+
+    let previous_loop_mode = player_data.get_loop_mode();
+
+    player_data.set_loop_mode(loop_mode);
+
+    if let Some(current_track) = player_context.get_player().await?.track {
+        dequeue_loop_copy(player_context, previous_loop_mode, &current_track).await?;
+
+        queue_loop_copy(player_context, loop_mode, &current_track)?;
+    }
+
+    Ok(())
+}
+
+/// Ends the current track and plays the next one, without replaying a track that is looped.
+pub async fn skip_track(
+    player_context: &lavalink_rs::prelude::PlayerContext,
+    current_track: &lavalink_rs::model::track::TrackData,
+) -> Result<(), Error> {
+    if get_player_data(player_context)?.get_loop_mode() == LoopMode::Track {
+        dequeue_loop_copy(player_context, LoopMode::Track, current_track).await?;
+    }
+
+    player_context.finish(true)?;
+
+    Ok(())
+}
+
+//------------------------------------------------------------//
+
 pub enum JoinVoiceChannelResult {
     ConnectedToNewVoiceChannel,
     ConnectedToSameVoiceChannel,
@@ -102,8 +255,15 @@ pub async fn join_voice_channel(
         }
     };
 
+    // keep the state of an existing player, since it is recreated when (re)joining
+    let player_data =
+        lavalink_client
+        .get_player_context(guild_id.get())
+        .and_then(|player_context| get_player_data(&player_context).ok())
+        .unwrap_or_default();
+
     let lavalink_player_context_result =
-        lavalink_client.create_player_context(guild_id.get(), connection_info).await;
+        lavalink_client.create_player_context_with_data(guild_id.get(), connection_info, player_data).await;
 
     let result = match lavalink_player_context_result {
         Ok(_) => {
@@ -125,3 +285,98 @@ pub async fn join_voice_channel(
 
     result
 }
+
+//------------------------------------------------------------//
+
+/// Finds a search source by its name, shorthand (e.g. `sc`), or lavalink search prefix (e.g. `scsearch`).
+pub fn find_search_source(
+    value: &str,
+) -> Option<GuildConfigMusicSearchSource> {
+    let value = value.trim();
+
+    GuildConfigMusicSearchSource::ALL
+    .into_iter()
+    .find(|source| {
+        value.eq_ignore_ascii_case(source.name()) ||
+        value.eq_ignore_ascii_case(source.shorthand()) ||
+        value.eq_ignore_ascii_case(source.search_prefix())
+    })
+}
+
+/// Splits a search source prefix (e.g. `sc:` or `ytm:`) from the start of a query, if there is one.
+pub fn split_search_source_prefix(
+    query: &str,
+) -> Option<(GuildConfigMusicSearchSource, &str)> {
+    let (prefix, query) = query.split_once(':')?;
+
+    // names are not accepted as prefixes, since they could be part of a query
+    let source =
+        GuildConfigMusicSearchSource::ALL
+        .into_iter()
+        .find(|source| {
+            prefix.eq_ignore_ascii_case(source.shorthand()) ||
+            prefix.eq_ignore_ascii_case(source.search_prefix())
+        })?;
+
+    Some((source, query.trim()))
+}
+
+pub fn create_search_query(
+    source: GuildConfigMusicSearchSource,
+    query: &str,
+) -> String {
+    format!("{}:{}", source.search_prefix(), query)
+}
+
+/// Fetches the search sources that the guild's lavalink node has a source manager for.
+/// Returns `None` if the node could not be asked, in which case any source may be available.
+async fn fetch_available_search_sources(
+    ctx: Context<'_>,
+) -> Option<Vec<GuildConfigMusicSearchSource>> {
+    let lavalink_client = ctx.data().lavalink.as_ref()?;
+    let guild_id = ctx.guild_id()?;
+
+    let lavalink_info = match lavalink_client.request_info(guild_id.get()).await {
+        Ok(lavalink_info) => lavalink_info,
+        Err(why) => {
+            eprintln!("Failed to request lavalink info: {:?}", why);
+
+            return None;
+        },
+    };
+
+    let available_search_sources =
+        GuildConfigMusicSearchSource::ALL
+        .into_iter()
+        .filter(|source| lavalink_info.source_managers.iter().any(|name| name == source.source_manager_name()))
+        .collect();
+
+    Some(available_search_sources)
+}
+
+/// Suggests search sources for a command parameter, matching their names and shorthands.
+/// Only the sources that the lavalink node can search are suggested.
+pub async fn autocomplete_search_source<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> CreateAutocompleteResponse<'a> {
+    let lowercase_user_input = partial.trim().to_lowercase();
+
+    let search_sources =
+        fetch_available_search_sources(ctx).await
+        .unwrap_or(GuildConfigMusicSearchSource::ALL.to_vec());
+
+    let choices =
+        search_sources
+        .into_iter()
+        .filter(|source| {
+            source.name().to_lowercase().contains(&lowercase_user_input) ||
+            source.shorthand().starts_with(&lowercase_user_input)
+        })
+        .map(|source| {
+            serenity::AutocompleteChoice::new(format!("{} ({}:)", source.name(), source.shorthand()), source.shorthand())
+        })
+        .collect::<Vec<_>>();
+
+    CreateAutocompleteResponse::new().set_choices(choices)
+}
@@ -0,0 +1,267 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use std::sync::Arc;
+
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::Error;
+
+use crate::commands::moderation::unban::unban_user;
+
+use crate::common::anti_raid;
+
+use crate::common::moderation;
+
+use crate::common::helpers::bot::{is_forbidden_error, is_unknown_resource_error};
+
+use crate::common::database::interfaces::moderation_case::{
+    ModerationCase,
+    ModerationCaseAction,
+    ModerationCaseOptions,
+};
+
+pub use crate::common::database::interfaces::scheduled_job::ScheduledJobPayload;
+
+use crate::common::database::interfaces::scheduled_job::ScheduledJob;
+
+//------------------------------------------------------------//
+
+/// How often to check for jobs that are due.
+const SCHEDULER_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// How long a claimed job is locked before another scheduler may retry it.
+const SCHEDULER_JOB_LEASE_SECONDS: i64 = 5 * 60;
+
+/// Jobs that fail this many times are dropped.
+const SCHEDULER_MAX_ATTEMPTS: u32 = 5;
+
+/// The first retry is delayed by this amount, doubling for each subsequent attempt.
+const SCHEDULER_RETRY_BASE_DELAY_SECONDS: i64 = 30;
+
+/// Retries are never delayed by more than this amount.
+const SCHEDULER_RETRY_MAX_DELAY_SECONDS: i64 = 60 * 60;
+
+/// Jobs that are retried until they succeed are still dropped once they are this overdue.
+const SCHEDULER_RETRY_MAX_OVERDUE_SECONDS: i64 = 7 * 24 * 60 * 60;
+
+//------------------------------------------------------------//
+
+/// Schedules work to be performed at (or shortly after) the specified time.
+///
+/// Jobs are executed at least once, so handlers should tolerate running more than once.
+/// When a dedupe key is provided, any existing job with the same key is replaced.
+pub async fn schedule_job(
+    payload: ScheduledJobPayload,
+    run_at: chrono::DateTime<chrono::Utc>,
+    dedupe_key: Option<String>,
+) -> Result<(), Error> {
+    ScheduledJob::create(payload, run_at, dedupe_key).await?;
+
+    Ok(())
+}
+
+/// Cancels a pending job that was scheduled with the specified dedupe key.
+pub async fn cancel_job(
+    dedupe_key: &str,
+) -> Result<(), Error> {
+    ScheduledJob::delete_by_dedupe_key(dedupe_key).await
+}
+
+//------------------------------------------------------------//
+
+pub fn create_unban_dedupe_key(
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+) -> String {
+    format!("unban:{}:{}", guild_id, user_id)
+}
+
+pub fn create_voice_unmute_dedupe_key(
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+) -> String {
+    format!("voice_unmute:{}:{}", guild_id, user_id)
+}
+
+/// Runs the pending voice unmute of a member as soon as possible, if it was already due.
+/// Voice unmutes fail while the member is not connected to voice, so this is used once they (re)connect.
+pub async fn expedite_voice_unmute(
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+) -> Result<(), Error> {
+    ScheduledJob::expedite_failed_by_dedupe_key(&create_voice_unmute_dedupe_key(guild_id, user_id)).await
+}
+
+pub fn create_end_raid_mode_dedupe_key(
+    guild_id: serenity::GuildId,
+) -> String {
+    format!("end_raid_mode:{}", guild_id)
+}
+
+//------------------------------------------------------------//
+
+/// Whether a job is retried until it succeeds (or is `SCHEDULER_RETRY_MAX_OVERDUE_SECONDS` overdue),
+/// instead of being dropped after `SCHEDULER_MAX_ATTEMPTS`.
+/// Voice unmutes can only succeed once the member is connected to voice, which may take a while.
+fn is_job_retried_until_success(
+    payload: &ScheduledJobPayload,
+) -> bool {
+    matches!(payload, ScheduledJobPayload::VoiceUnmute { .. })
+}
+
+async fn execute_job(
+    http: &serenity::Http,
+    my_id: serenity::UserId,
+    payload: ScheduledJobPayload,
+) -> Result<(), Error> {
+    match payload {
+        ScheduledJobPayload::Unban { discord_guild_id, discord_user_id, reason } => {
+            let guild_name = match http.get_guild(discord_guild_id).await {
+                Ok(guild) => guild.name.to_string(),
+                Err(_) => discord_guild_id.to_string(), // the unban can still be attempted
+            };
+
+            unban_user(http, discord_guild_id, &guild_name, discord_user_id, my_id, &reason).await?;
+
+            let moderation_case = ModerationCase::create(
+                ModerationCaseOptions {
+                    discord_guild_id: discord_guild_id,
+                    action: ModerationCaseAction::Unban,
+                    actor_user_id: my_id,
+                    target_user_id: Some(discord_user_id),
+                    channel_id: None,
+                    reason: reason,
+                    duration_seconds: None,
+                }
+            ).await?;
+
+            // the unban has already been performed, so failing to log it should not retry the job
+            if let Err(why) = moderation::log_moderation_case(http, &moderation_case).await {
+                eprintln!("Failed to log moderation case to the moderation actions logging channel: {:?}", why);
+            }
+        },
+        ScheduledJobPayload::EndRaidMode { discord_guild_id } => {
+            anti_raid::end_raid_mode(http, discord_guild_id).await?;
+        },
+        ScheduledJobPayload::VoiceUnmute { discord_guild_id, discord_user_id, reason } => {
+            // discord rejects this while the member is not connected to voice, so it is retried until they are
+            let edit_result = discord_guild_id.edit_member(
+                http,
+                discord_user_id,
+                serenity::EditMember::default()
+                .mute(false)
+                .audit_log_reason(&reason),
+            ).await;
+
+            match edit_result {
+                Ok(_) => {},
+                Err(why) if is_unknown_resource_error(&why) => {
+                    return Ok(()); // the member left the guild, so there is nobody to unmute
+                },
+                Err(why) if is_forbidden_error(&why) => {
+                    // retrying won't help once the bot was removed from the guild or may no longer unmute members
+                    eprintln!("Dropping voice unmute that is no longer permitted in guild {}: {:?}", discord_guild_id, why);
+
+                    return Ok(());
+                },
+                Err(why) => return Err(why.into()),
+            }
+
+            let moderation_case = ModerationCase::create(
+                ModerationCaseOptions {
+                    discord_guild_id: discord_guild_id,
+                    action: ModerationCaseAction::Unmute,
+                    actor_user_id: my_id,
+                    target_user_id: Some(discord_user_id),
+                    channel_id: None,
+                    reason: reason,
+                    duration_seconds: None,
+                }
+            ).await?;
+
+            // the unmute has already been performed, so failing to log it should not retry the job
+            if let Err(why) = moderation::log_moderation_case(http, &moderation_case).await {
+                eprintln!("Failed to log moderation case to the moderation actions logging channel: {:?}", why);
+            }
+        },
+    }
+
+    Ok(())
+}
+
+async fn process_due_jobs(
+    http: &serenity::Http,
+    my_id: serenity::UserId,
+) -> Result<(), Error> {
+    let lease = chrono::Duration::seconds(SCHEDULER_JOB_LEASE_SECONDS);
+
+    while let Some(scheduled_job) = ScheduledJob::claim_due(lease).await? {
+        let payload = scheduled_job.get_payload().await;
+        let attempts = scheduled_job.get_attempts().await;
+        let first_run_at = scheduled_job.get_first_run_at().await;
+
+        let Err(why) = execute_job(http, my_id, payload.clone()).await else {
+            scheduled_job.delete().await?;
+
+            continue;
+        };
+
+        let is_retried =
+            if is_job_retried_until_success(&payload) {
+                chrono::Utc::now() - first_run_at < chrono::Duration::seconds(SCHEDULER_RETRY_MAX_OVERDUE_SECONDS)
+            } else {
+                attempts < SCHEDULER_MAX_ATTEMPTS
+            };
+
+        if !is_retried {
+            eprintln!("Dropping scheduled job after {} attempts: {:?}\n{:?}", attempts, payload, why);
+
+            scheduled_job.delete().await?;
+
+            continue;
+        }
+
+        let retry_delay_seconds =
+            SCHEDULER_RETRY_BASE_DELAY_SECONDS.saturating_mul(2_i64.saturating_pow(attempts - 1))
+            .min(SCHEDULER_RETRY_MAX_DELAY_SECONDS);
+
+        let retry_delay = chrono::Duration::seconds(retry_delay_seconds);
+
+        eprintln!("Scheduled job failed, retrying in {}s: {:?}\n{:?}", retry_delay.num_seconds(), payload, why);
+
+        scheduled_job.reschedule(chrono::Utc::now() + retry_delay, why.to_string()).await?;
+    }
+
+    Ok(())
+}
+
+//------------------------------------------------------------//
+
+/// Spawns the background task that executes scheduled jobs once they are due.
+/// Overdue jobs (e.g. from while the bot was offline) are recovered on the first poll.
+pub fn start_scheduler_task(
+    http: Arc<serenity::Http>,
+) {
+    tokio::spawn(async move {
+        let my_id = match http.get_current_user().await {
+            Ok(me) => me.id,
+            Err(why) => {
+                eprintln!("Failed to start scheduler task: {:?}", why);
+
+                return;
+            },
+        };
+
+        loop {
+            if let Err(why) = process_due_jobs(&http, my_id).await {
+                eprintln!("Failed to process due scheduled jobs: {:?}", why);
+            }
+
+            tokio::time::sleep(SCHEDULER_POLL_INTERVAL).await;
+        }
+    });
+}
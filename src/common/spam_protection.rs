@@ -0,0 +1,182 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use std::collections::{HashMap, VecDeque};
+
+use std::sync::{LazyLock, Mutex};
+
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::common::database::interfaces::guild_config::GuildConfigSpamProtection;
+
+//------------------------------------------------------------//
+
+/// Violations older than this no longer count towards escalation.
+const SPAM_VIOLATION_MEMORY_SECONDS: i64 = 10 * 60; // 10 minutes
+
+/// 28 days is the maximum timeout duration that discord allows.
+const SPAM_MAX_TIMEOUT_SECONDS: i64 = 28 * 24 * 60 * 60;
+
+/// Beyond this many tracked members, members without recent messages are forgotten.
+const SPAM_MAX_TRACKED_MEMBERS: usize = 10_000;
+
+//------------------------------------------------------------//
+
+#[derive(Clone)]
+struct TrackedMessage {
+    message_id: serenity::MessageId,
+    channel_id: serenity::GenericChannelId,
+    normalized_content: String,
+    sent_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Recent messages per guild member, used to detect message floods and duplicates.
+static RECENT_MESSAGES: LazyLock<Mutex<HashMap<(serenity::GuildId, serenity::UserId), VecDeque<TrackedMessage>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Recent spam violations per guild member, used to escalate actions.
+static RECENT_VIOLATIONS: LazyLock<Mutex<HashMap<(serenity::GuildId, serenity::UserId), VecDeque<chrono::DateTime<chrono::Utc>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+//------------------------------------------------------------//
+
+pub struct SpamViolation {
+    /// Why the messages were considered spam.
+    pub description: String,
+
+    /// The messages that made up the spam, including the latest message.
+    pub spam_messages: Vec<(serenity::GenericChannelId, serenity::MessageId)>,
+
+    /// How many times the member has recently violated spam protection, including this time.
+    pub violation_count: u32,
+}
+
+fn record_violation(
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+) -> u32 {
+    let mut recent_violations = RECENT_VIOLATIONS.lock().expect("recent violations lock should not be poisoned");
+
+    let now = chrono::Utc::now();
+    let cutoff = now - chrono::Duration::seconds(SPAM_VIOLATION_MEMORY_SECONDS);
+
+    // forget members that have not violated spam protection recently
+    recent_violations.retain(|_, member_violations| member_violations.back().is_some_and(|violated_at| *violated_at > cutoff));
+
+    let member_violations = recent_violations.entry((guild_id, user_id)).or_default();
+
+    member_violations.push_back(now);
+
+    member_violations.len() as u32
+}
+
+/// Records a message and checks whether its author is spamming.
+/// Exemptions are expected to be checked by the caller.
+pub fn check_message(
+    spam_protection: &GuildConfigSpamProtection,
+    guild_id: serenity::GuildId,
+    message: &serenity::Message,
+) -> Option<SpamViolation> {
+    let now = chrono::Utc::now();
+
+    let message_cutoff = now - chrono::Duration::seconds(spam_protection.message_window_seconds as i64);
+    let duplicate_cutoff = now - chrono::Duration::seconds(spam_protection.duplicate_window_seconds as i64);
+    let tracking_cutoff = message_cutoff.min(duplicate_cutoff);
+
+    let tracked_message = TrackedMessage {
+        message_id: message.id,
+        channel_id: message.channel_id,
+        normalized_content: message.content.trim().to_lowercase(),
+        sent_at: now,
+    };
+
+    let spam = {
+        let mut recent_messages = RECENT_MESSAGES.lock().expect("recent messages lock should not be poisoned");
+
+        // occasionally forget members that have not sent anything recently
+        if recent_messages.len() > SPAM_MAX_TRACKED_MEMBERS {
+            recent_messages.retain(|_, member_messages| member_messages.back().is_some_and(|message| message.sent_at > tracking_cutoff));
+        }
+
+        let member_messages = recent_messages.entry((guild_id, message.author.id)).or_default();
+
+        member_messages.retain(|message| message.sent_at > tracking_cutoff);
+
+        member_messages.push_back(tracked_message.clone());
+
+        let channel_messages: Vec<TrackedMessage> =
+            member_messages
+            .iter()
+            .filter(|message| message.channel_id == tracked_message.channel_id && message.sent_at > message_cutoff)
+            .cloned()
+            .collect();
+
+        // messages without content (e.g. only attachments) are not considered duplicates
+        let duplicate_messages: Vec<TrackedMessage> =
+            if tracked_message.normalized_content.is_empty() {
+                Vec::new()
+            } else {
+                member_messages
+                .iter()
+                .filter(|message| message.normalized_content == tracked_message.normalized_content && message.sent_at > duplicate_cutoff)
+                .cloned()
+                .collect()
+            };
+
+        let spam =
+            if channel_messages.len() as u32 > spam_protection.max_messages_per_channel {
+                Some((
+                    format!("Sent {} messages within {} seconds.", channel_messages.len(), spam_protection.message_window_seconds),
+                    channel_messages,
+                ))
+            } else if duplicate_messages.len() as u32 >= spam_protection.max_duplicate_messages {
+                Some((
+                    format!("Sent the same message {} times within {} seconds.", duplicate_messages.len(), spam_protection.duplicate_window_seconds),
+                    duplicate_messages,
+                ))
+            } else {
+                None
+            };
+
+        // spam that has been dealt with should not count towards the next violation
+        if let Some((_, spam_messages)) = &spam {
+            member_messages.retain(|message| !spam_messages.iter().any(|spam_message| spam_message.message_id == message.message_id));
+        }
+
+        spam
+    };
+
+    let (description, spam_messages) = spam?;
+
+    Some(
+        SpamViolation {
+            description: description,
+            spam_messages:
+                spam_messages
+                .into_iter()
+                .map(|message| (message.channel_id, message.message_id))
+                .collect(),
+            violation_count: record_violation(guild_id, message.author.id),
+        }
+    )
+}
+
+/// Determines how long to timeout a member for their latest violation.
+/// Returns `None` when the spam should only be deleted.
+pub fn get_spam_timeout_duration(
+    spam_protection: &GuildConfigSpamProtection,
+    violation_count: u32,
+) -> Option<chrono::Duration> {
+    if violation_count <= spam_protection.violations_before_timeout {
+        return None;
+    }
+
+    let doublings = (violation_count - spam_protection.violations_before_timeout - 1).min(16);
+
+    let timeout_seconds = (spam_protection.base_timeout_seconds as i64 * 2_i64.pow(doublings)).min(SPAM_MAX_TIMEOUT_SECONDS);
+
+    Some(chrono::Duration::seconds(timeout_seconds))
+}
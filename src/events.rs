@@ -2,10 +2,30 @@
 //                   Copyright (c) MidSpike                   //
 //------------------------------------------------------------//
 
+pub mod lavalink_manager;
+
 pub mod manager;
 
 pub mod handlers {
     pub mod guild_ai_chat_handler;
 
+    pub mod guild_anti_raid_handler;
+
+    pub mod guild_automod_handler;
+
     pub mod guild_logging_channels_handler;
+
+    pub mod guild_message_cache_handler;
+
+    pub mod guild_moderation_appeal_handler;
+
+    pub mod guild_moderation_audit_log_handler;
+
+    pub mod guild_spam_protection_handler;
+
+    pub mod guild_voice_unmute_handler;
+
+    pub mod music_announcement_handler;
+
+    pub mod music_queue_handler;
 }
@@ -0,0 +1,97 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::Error;
+
+use crate::common::anti_raid;
+
+use crate::common::moderation::ModerationActionContext;
+
+use crate::common::database::interfaces::guild_config::{GuildConfig, GuildConfigAntiRaidAction};
+
+use crate::commands::moderation::kick::kick_member;
+use crate::commands::moderation::timeout::timeout_member;
+
+//------------------------------------------------------------//
+
+/// Tracks the join rate of a guild, starting raid mode when it exceeds the configured threshold.
+/// While raid mode is active, new accounts are timed out or kicked as they join.
+pub async fn guild_anti_raid_member_join_handler(
+    ctx: &serenity::Context,
+    new_member: &serenity::Member,
+) -> Result<(), Error> {
+    if new_member.user.bot() {
+        return Ok(());
+    }
+
+    let guild_id = new_member.guild_id;
+
+    // attempt to fetch the guild config, if it doesn't exist, anti-raid is not enabled
+    let Some(guild_config) = GuildConfig::fetch(guild_id).await? else {
+        return Ok(());
+    };
+
+    let anti_raid = guild_config.get_anti_raid().await;
+
+    if !anti_raid.enabled {
+        return Ok(());
+    }
+
+    let join_count = anti_raid::record_member_join(guild_id, anti_raid.join_window_seconds);
+
+    let mut is_raid_mode_active = guild_config.get_raid_mode().await.is_some();
+
+    if !is_raid_mode_active && join_count >= anti_raid.join_threshold {
+        anti_raid::start_raid_mode(&ctx.http, guild_id, &anti_raid, join_count).await?;
+
+        is_raid_mode_active = true;
+    }
+
+    if !is_raid_mode_active {
+        return Ok(());
+    }
+
+    let account_age_seconds = chrono::Utc::now().timestamp() - new_member.user.id.created_at().unix_timestamp();
+
+    if account_age_seconds >= anti_raid.min_account_age_days as i64 * 24 * 60 * 60 {
+        return Ok(());
+    }
+
+    let reason = format!("Anti-raid: account is younger than {} days during a raid.", anti_raid.min_account_age_days);
+
+    let guild_name =
+        ctx.cache.guild(guild_id)
+        .map(|guild| guild.name.to_string())
+        .unwrap_or(guild_id.to_string());
+
+    let action_ctx = ModerationActionContext {
+        http: &ctx.http,
+        guild_id: guild_id,
+        guild_name: guild_name,
+        actor_user_id: ctx.cache.current_user().id,
+        channel_id: None,
+    };
+
+    match anti_raid.action {
+        GuildConfigAntiRaidAction::Timeout => {
+            let mut target_member = new_member.clone();
+
+            timeout_member(
+                &action_ctx,
+                &mut target_member,
+                chrono::Duration::minutes(anti_raid.raid_duration_minutes as i64),
+                &reason,
+            ).await?;
+        },
+        GuildConfigAntiRaidAction::Kick => {
+            kick_member(&action_ctx, new_member, &reason).await?;
+        },
+    }
+
+    Ok(())
+}
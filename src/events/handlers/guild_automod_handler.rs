@@ -0,0 +1,113 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::serenity_prelude::{self as serenity, Mentionable};
+
+//------------------------------------------------------------//
+
+use crate::Error;
+
+use crate::common::automod;
+
+use crate::common::moderation::ModerationActionContext;
+
+use crate::common::database::interfaces::guild_config::{GuildConfig, GuildConfigAutomodAction};
+
+use crate::commands::moderation::timeout::timeout_member;
+use crate::commands::moderation::warn::warn_member;
+
+//------------------------------------------------------------//
+
+/// Fallback duration for automod timeout rules that were configured without a duration.
+const DEFAULT_AUTOMOD_TIMEOUT_SECONDS: u32 = 10 * 60; // 10 minutes
+
+//------------------------------------------------------------//
+
+/// Enforces the guild's automod rules on a message.
+/// Returns whether the message was removed, in which case it should not be processed further.
+pub async fn guild_automod_handler(
+    ctx: &serenity::Context,
+    message: &serenity::Message,
+) -> Result<bool, Error> {
+    // don't moderate bots or system messages
+    if
+        message.author.bot() ||
+        message.author.system()
+    {
+        return Ok(false);
+    }
+
+    // only moderate messages in guilds
+    let Some(guild_id) = message.guild_id else {
+        return Ok(false);
+    };
+
+    // attempt to fetch the guild config, if it doesn't exist, there are no rules to enforce
+    let Some(guild_config) = GuildConfig::fetch(guild_id).await? else {
+        return Ok(false);
+    };
+
+    let automod = guild_config.get_automod().await;
+
+    if automod.rules.is_empty() {
+        return Ok(false);
+    }
+
+    if automod::is_message_exempt(ctx, guild_id, &automod, message) {
+        return Ok(false);
+    }
+
+    let Some((rule, violation)) = automod::find_violated_rule(&automod, guild_id, message) else {
+        return Ok(false);
+    };
+
+    let reason = format!("Automod: {}", violation);
+
+    message.delete(&ctx.http, Some(&reason)).await?;
+
+    let guild_name =
+        ctx.cache.guild(guild_id)
+        .map(|guild| guild.name.to_string())
+        .unwrap_or(guild_id.to_string());
+
+    let action_ctx = ModerationActionContext {
+        http: &ctx.http,
+        guild_id: guild_id,
+        guild_name: guild_name,
+        actor_user_id: ctx.cache.current_user().id,
+        channel_id: Some(message.channel_id),
+    };
+
+    match rule.action {
+        GuildConfigAutomodAction::Delete => {
+            // the violation may quote what was matched, which shouldn't be repeated in public
+            let public_violation = automod::get_public_violation_description(rule);
+
+            message.channel_id.send_message(
+                &ctx.http,
+                serenity::CreateMessage::default()
+                .content(format!("{}, your message was removed. {}", message.author.mention(), public_violation))
+            ).await?;
+        },
+        GuildConfigAutomodAction::Warn => {
+            let mut target_member = guild_id.member(&ctx, message.author.id).await?;
+
+            warn_member(&action_ctx, &mut target_member, &reason).await?;
+        },
+        GuildConfigAutomodAction::Timeout => {
+            let mut target_member = guild_id.member(&ctx, message.author.id).await?;
+
+            let duration_seconds = rule.duration_seconds.unwrap_or(DEFAULT_AUTOMOD_TIMEOUT_SECONDS);
+
+            timeout_member(
+                &action_ctx,
+                &mut target_member,
+                chrono::Duration::seconds(duration_seconds as i64),
+                &reason,
+            ).await?;
+        },
+    }
+
+    Ok(true)
+}
@@ -7,7 +7,8 @@ use poise::serenity_prelude::{
     CacheHttp,
     FormattedTimestamp,
     FormattedTimestampStyle,
-    GenericChannelId
+    GenericChannelId,
+    Mentionable,
 };
 
 //------------------------------------------------------------//
@@ -16,7 +17,16 @@ use poise::serenity_prelude::{
 
 use crate::Error;
 
-use crate::common::database::interfaces::guild_config::GuildConfig;
+use crate::common::helpers::bot::create_escaped_code_block;
+
+use crate::common::message_cache::CachedMessage;
+
+use crate::common::database::interfaces::guild_config::{GuildConfig, GuildConfigLoggingChannelKind};
+
+//------------------------------------------------------------//
+
+/// Updates of messages that aren't cached are only logged as edits if they were edited this recently.
+const UNCACHED_MESSAGE_EDIT_MAX_AGE_SECONDS: i64 = 60;
 
 //------------------------------------------------------------//
 
@@ -35,6 +45,81 @@ async fn get_guild_logging_channel(
     Ok(guild_channel)
 }
 
+/// Sends an embed to the logging channel configured for a kind of logging, if any.
+async fn send_guild_logging_embed(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    kind: GuildConfigLoggingChannelKind,
+    embed: serenity::CreateEmbed<'_>,
+) -> Result<(), Error> {
+    let guild_config = GuildConfig::ensure(guild_id).await?;
+
+    let Some(logging_channel_id) = guild_config.get_logging_channels().await.get(kind) else {
+        return Ok(()); // Graceful
+    };
+
+    let logging_channel = match get_guild_logging_channel(&ctx, &guild_id, &logging_channel_id).await? {
+        Some(channel) => channel,
+        None => {
+            eprintln!("[Ignorable] Logging channel not found for guild: {}", guild_id);
+
+            // TODO: Consider removing the logging channel from the guild config if it no longer exists.
+
+            return Ok(()); // Graceful
+        }
+    };
+
+    let message = serenity::CreateMessage::default().embed(embed);
+
+    logging_channel.send_message(&ctx.http(), message).await?;
+
+    Ok(())
+}
+
+//------------------------------------------------------------//
+
+/// Embed field values are limited to 1024 characters, so content is truncated to fit.
+const LOGGED_CONTENT_MAX_LENGTH: usize = 1000;
+
+fn create_logged_content_field_value(
+    content: Option<&str>,
+) -> String {
+    match content {
+        None => "*Unknown (not cached)*".to_string(),
+        Some(content) if content.is_empty() => "*No text content*".to_string(),
+        Some(content) => {
+            let truncated_content =
+                if content.chars().count() > LOGGED_CONTENT_MAX_LENGTH {
+                    format!("{}...", content.chars().take(LOGGED_CONTENT_MAX_LENGTH).collect::<String>())
+                } else {
+                    content.to_string()
+                };
+
+            create_escaped_code_block(None, &truncated_content)
+        },
+    }
+}
+
+fn create_now_field_value() -> String {
+    let now = chrono::Utc::now();
+
+    let now_timestamp =
+        serenity::Timestamp::from_millis(now.timestamp_millis())
+        .expect("Should not fail; failed to create timestamp from current time.");
+    let now_timestamp_relative_format =
+        FormattedTimestamp::new(now_timestamp, Some(FormattedTimestampStyle::RelativeTime));
+    let now_timestamp_full_format =
+        FormattedTimestamp::new(now_timestamp, Some(FormattedTimestampStyle::FullDateShortTime));
+
+    format!("{} ({})", now_timestamp_full_format, now_timestamp_relative_format)
+}
+
+fn create_user_field_value(
+    user: &serenity::User,
+) -> String {
+    format!("{} (`{}`)", user.mention(), user.id)
+}
+
 //------------------------------------------------------------//
 
 fn create_member_join_embed(
@@ -145,61 +230,350 @@ pub async fn guild_logging_channels_member_join_handler(
     ctx: &serenity::Context,
     new_member: &serenity::Member,
 ) -> Result<(), Error> {
-    let guild_id = new_member.guild_id;
+    let embed = create_member_join_embed(&new_member.user);
 
-    let guild_config = GuildConfig::ensure(guild_id).await?;
+    send_guild_logging_embed(ctx, new_member.guild_id, GuildConfigLoggingChannelKind::GuildMemberJoin, embed).await
+}
 
-    let Some(logging_channel_id) = guild_config.get_logging_channels().await.guild_member_join else {
-        return Ok(()); // Graceful
+pub async fn guild_logging_channels_member_leave_handler(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    user: &serenity::User,
+) -> Result<(), Error> {
+    let embed = create_member_leave_embed(&user);
+
+    send_guild_logging_embed(ctx, guild_id, GuildConfigLoggingChannelKind::GuildMemberLeave, embed).await
+}
+
+//------------------------------------------------------------//
+
+/// Whether a message update is an edit by its author, rather than e.g. embeds being resolved.
+/// Without the previous version of the message, only updates that were edited just now are considered edits.
+fn is_message_update_an_edit(
+    previous_cached_message: Option<&CachedMessage>,
+    new_message: &serenity::Message,
+) -> bool {
+    let Some(edited_timestamp) = new_message.edited_timestamp else {
+        return false; // messages that were never edited can only have been updated by discord
     };
 
-    let logging_channel = match get_guild_logging_channel(&ctx, &guild_id, &logging_channel_id).await? {
-        Some(channel) => channel,
+    match previous_cached_message {
+        Some(previous_cached_message) => {
+            previous_cached_message.content != new_message.content.as_str() &&
+            previous_cached_message.edited_timestamp != Some(edited_timestamp)
+        },
         None => {
-            eprintln!("[Ignorable] Logging channel not found for guild: {}", guild_id);
+            chrono::Utc::now().timestamp() - edited_timestamp.unix_timestamp() <= UNCACHED_MESSAGE_EDIT_MAX_AGE_SECONDS
+        },
+    }
+}
 
-            // TODO: Consider removing the logging channel from the guild config if it no longer exists.
+pub async fn guild_logging_channels_message_edit_handler(
+    ctx: &serenity::Context,
+    previous_cached_message: Option<&CachedMessage>,
+    new_message: &serenity::Message,
+) -> Result<(), Error> {
+    let Some(guild_id) = new_message.guild_id else {
+        return Ok(()); // Graceful
+    };
 
-            return Ok(()); // Graceful
-        }
+    if new_message.author.bot() {
+        return Ok(()); // Graceful
+    }
+
+    if !is_message_update_an_edit(previous_cached_message, new_message) {
+        return Ok(());
+    }
+
+    let old_content = previous_cached_message.map(|cached_message| cached_message.content.as_str());
+
+    let embed_fields = [
+        ("Author", create_user_field_value(&new_message.author), true),
+        ("Channel", new_message.channel_id.mention().to_string(), true),
+        ("Message", format!("[Jump to message]({})", new_message.link()), true),
+        ("Before", create_logged_content_field_value(old_content), false),
+        ("After", create_logged_content_field_value(Some(new_message.content.as_str())), false),
+        ("Edited On", create_now_field_value(), false),
+    ];
+
+    let embed =
+        serenity::CreateEmbed::default()
+        .color(0xFFFF00)
+        .title("Message Edited")
+        .fields(embed_fields);
+
+    send_guild_logging_embed(ctx, guild_id, GuildConfigLoggingChannelKind::MessageEdit, embed).await
+}
+
+pub async fn guild_logging_channels_message_delete_handler(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    channel_id: serenity::GenericChannelId,
+    message_id: serenity::MessageId,
+    cached_message: Option<&CachedMessage>,
+) -> Result<(), Error> {
+    // the content of deleted messages is only available if they were retained
+    let author_string = match cached_message {
+        Some(cached_message) => format!("{} (`{}`)", cached_message.author_id.mention(), cached_message.author_id),
+        None => "*Unknown (not cached)*".to_string(),
     };
 
-    let embed = create_member_join_embed(&new_member.user);
+    let attachments_string = match cached_message {
+        Some(cached_message) if !cached_message.attachment_urls.is_empty() => {
+            // only a few urls will fit within the embed field value limit
+            cached_message.attachment_urls.iter().take(5).cloned().collect::<Vec<String>>().join("\n")
+        },
+        _ => "*None*".to_string(),
+    };
 
-    let message = serenity::CreateMessage::default().embed(embed);
+    let embed_fields = [
+        ("Author", author_string, true),
+        ("Channel", channel_id.mention().to_string(), true),
+        ("Snowflake", format!("`{}`", message_id), true),
+        ("Content", create_logged_content_field_value(cached_message.map(|cached_message| cached_message.content.as_str())), false),
+        ("Attachments", attachments_string, false),
+        ("Deleted On", create_now_field_value(), false),
+    ];
 
-    logging_channel.send_message(&ctx.http(), message).await?;
+    let embed =
+        serenity::CreateEmbed::default()
+        .color(0xFF0000)
+        .title("Message Deleted")
+        .fields(embed_fields);
 
-    Ok(())
+    send_guild_logging_embed(ctx, guild_id, GuildConfigLoggingChannelKind::MessageDelete, embed).await
 }
 
-pub async fn guild_logging_channels_member_leave_handler(
+pub async fn guild_logging_channels_message_bulk_delete_handler(
     ctx: &serenity::Context,
     guild_id: serenity::GuildId,
-    user: &serenity::User,
+    channel_id: serenity::GenericChannelId,
+    message_ids: &[serenity::MessageId],
 ) -> Result<(), Error> {
-    let guild_config = GuildConfig::ensure(guild_id).await?;
+    let embed_fields = [
+        ("Channel", channel_id.mention().to_string(), true),
+        ("Messages", format!("`{}`", message_ids.len()), true),
+        ("Deleted On", create_now_field_value(), false),
+    ];
+
+    let embed =
+        serenity::CreateEmbed::default()
+        .color(0xFF0000)
+        .title("Messages Bulk Deleted")
+        .fields(embed_fields);
+
+    send_guild_logging_embed(ctx, guild_id, GuildConfigLoggingChannelKind::MessageBulkDelete, embed).await
+}
 
-    let Some(logging_channel_id) = guild_config.get_logging_channels().await.guild_member_leave else {
+//------------------------------------------------------------//
+
+pub async fn guild_logging_channels_member_update_handler(
+    ctx: &serenity::Context,
+    old_member: Option<&serenity::Member>,
+    new_member: &serenity::Member,
+) -> Result<(), Error> {
+    // without the previous state (not cached), there is nothing to compare against
+    let Some(old_member) = old_member else {
         return Ok(()); // Graceful
     };
 
-    let logging_channel = match get_guild_logging_channel(&ctx, &guild_id, &logging_channel_id).await? {
-        Some(channel) => channel,
-        None => {
-            eprintln!("[Ignorable] Logging channel not found for guild: {}", guild_id);
+    let guild_id = new_member.guild_id;
 
-            // TODO: Consider removing the logging channel from the guild config if it no longer exists.
+    let added_roles =
+        new_member.roles.iter()
+        .filter(|role_id| !old_member.roles.contains(role_id))
+        .map(|role_id| role_id.mention().to_string())
+        .collect::<Vec<String>>();
+
+    let removed_roles =
+        old_member.roles.iter()
+        .filter(|role_id| !new_member.roles.contains(role_id))
+        .map(|role_id| role_id.mention().to_string())
+        .collect::<Vec<String>>();
+
+    if !added_roles.is_empty() || !removed_roles.is_empty() {
+        let format_roles = |roles: &Vec<String>| if roles.is_empty() { "*None*".to_string() } else { roles.join(", ") };
+
+        let embed_fields = [
+            ("Member", create_user_field_value(&new_member.user), false),
+            ("Added Roles", format_roles(&added_roles), false),
+            ("Removed Roles", format_roles(&removed_roles), false),
+            ("Updated On", create_now_field_value(), false),
+        ];
+
+        let embed =
+            serenity::CreateEmbed::default()
+            .color(0xFFFF00)
+            .title("Member Roles Updated")
+            .fields(embed_fields);
+
+        send_guild_logging_embed(ctx, guild_id, GuildConfigLoggingChannelKind::MemberRoleUpdate, embed).await?;
+    }
 
-            return Ok(()); // Graceful
-        }
+    if old_member.nick != new_member.nick {
+        let format_nickname = |nick: Option<&str>| match nick {
+            Some(nick) => format!("`{}`", nick),
+            None => "*None*".to_string(),
+        };
+
+        let embed_fields = [
+            ("Member", create_user_field_value(&new_member.user), false),
+            ("Before", format_nickname(old_member.nick.as_deref()), true),
+            ("After", format_nickname(new_member.nick.as_deref()), true),
+            ("Updated On", create_now_field_value(), false),
+        ];
+
+        let embed =
+            serenity::CreateEmbed::default()
+            .color(0xFFFF00)
+            .title("Member Nickname Updated")
+            .fields(embed_fields);
+
+        send_guild_logging_embed(ctx, guild_id, GuildConfigLoggingChannelKind::MemberNicknameUpdate, embed).await?;
+    }
+
+    Ok(())
+}
+
+//------------------------------------------------------------//
+
+pub async fn guild_logging_channels_voice_state_update_handler(
+    ctx: &serenity::Context,
+    old_voice_state: Option<&serenity::VoiceState>,
+    new_voice_state: &serenity::VoiceState,
+) -> Result<(), Error> {
+    let Some(guild_id) = new_voice_state.guild_id else {
+        return Ok(()); // Graceful
     };
 
-    let embed = create_member_leave_embed(&user);
+    let old_channel_id = old_voice_state.and_then(|voice_state| voice_state.channel_id);
+    let new_channel_id = new_voice_state.channel_id;
 
-    let message = serenity::CreateMessage::default().embed(embed);
+    let (title, color, channel_string) = match (old_channel_id, new_channel_id) {
+        (None, Some(new_channel_id)) => (
+            "Voice Channel Joined",
+            0x00FF00,
+            new_channel_id.mention().to_string(),
+        ),
+        (Some(old_channel_id), None) => (
+            "Voice Channel Left",
+            0xFF0000,
+            old_channel_id.mention().to_string(),
+        ),
+        (Some(old_channel_id), Some(new_channel_id)) if old_channel_id != new_channel_id => (
+            "Voice Channel Moved",
+            0xFFFF00,
+            format!("{} -> {}", old_channel_id.mention(), new_channel_id.mention()),
+        ),
+        _ => return Ok(()), // mute, deafen, stream, etc. changes are not logged
+    };
 
-    logging_channel.send_message(&ctx.http(), message).await?;
+    let member_string = match &new_voice_state.member {
+        Some(member) => create_user_field_value(&member.user),
+        None => format!("{} (`{}`)", new_voice_state.user_id.mention(), new_voice_state.user_id),
+    };
 
-    Ok(())
+    let embed_fields = [
+        ("Member", member_string, true),
+        ("Channel", channel_string, true),
+        ("Occurred On", create_now_field_value(), false),
+    ];
+
+    let embed =
+        serenity::CreateEmbed::default()
+        .color(color)
+        .title(title)
+        .fields(embed_fields);
+
+    send_guild_logging_embed(ctx, guild_id, GuildConfigLoggingChannelKind::VoiceActivity, embed).await
+}
+
+//------------------------------------------------------------//
+
+pub async fn guild_logging_channels_channel_create_handler(
+    ctx: &serenity::Context,
+    channel: &serenity::GuildChannel,
+) -> Result<(), Error> {
+    let embed_fields = [
+        ("Channel", format!("{} (`{}`)", channel.mention(), channel.base.name), true),
+        ("Snowflake", format!("`{}`", channel.id), true),
+        ("Kind", format!("`{:?}`", channel.base.kind), true),
+        ("Created On", create_now_field_value(), false),
+    ];
+
+    let embed =
+        serenity::CreateEmbed::default()
+        .color(0x00FF00)
+        .title("Channel Created")
+        .fields(embed_fields);
+
+    send_guild_logging_embed(ctx, channel.base.guild_id, GuildConfigLoggingChannelKind::ChannelCreate, embed).await
+}
+
+pub async fn guild_logging_channels_channel_delete_handler(
+    ctx: &serenity::Context,
+    channel: &serenity::GuildChannel,
+) -> Result<(), Error> {
+    let embed_fields = [
+        ("Channel", format!("`{}`", channel.base.name), true),
+        ("Snowflake", format!("`{}`", channel.id), true),
+        ("Kind", format!("`{:?}`", channel.base.kind), true),
+        ("Deleted On", create_now_field_value(), false),
+    ];
+
+    let embed =
+        serenity::CreateEmbed::default()
+        .color(0xFF0000)
+        .title("Channel Deleted")
+        .fields(embed_fields);
+
+    send_guild_logging_embed(ctx, channel.base.guild_id, GuildConfigLoggingChannelKind::ChannelDelete, embed).await
+}
+
+//------------------------------------------------------------//
+
+pub async fn guild_logging_channels_ban_add_handler(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    user: &serenity::User,
+) -> Result<(), Error> {
+    let embed_fields = [
+        ("User", create_user_field_value(user), true),
+        ("Banned On", create_now_field_value(), false),
+    ];
+
+    let mut embed =
+        serenity::CreateEmbed::default()
+        .color(0xFF0000)
+        .title("User Banned")
+        .fields(embed_fields);
+
+    if let Some(user_avatar_url) = user.avatar_url() {
+        embed = embed.thumbnail(user_avatar_url, None);
+    }
+
+    send_guild_logging_embed(ctx, guild_id, GuildConfigLoggingChannelKind::GuildBanAdd, embed).await
+}
+
+pub async fn guild_logging_channels_ban_remove_handler(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    user: &serenity::User,
+) -> Result<(), Error> {
+    let embed_fields = [
+        ("User", create_user_field_value(user), true),
+        ("Unbanned On", create_now_field_value(), false),
+    ];
+
+    let mut embed =
+        serenity::CreateEmbed::default()
+        .color(0x00FF00)
+        .title("User Unbanned")
+        .fields(embed_fields);
+
+    if let Some(user_avatar_url) = user.avatar_url() {
+        embed = embed.thumbnail(user_avatar_url, None);
+    }
+
+    send_guild_logging_embed(ctx, guild_id, GuildConfigLoggingChannelKind::GuildBanRemove, embed).await
 }
@@ -0,0 +1,51 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::Error;
+
+use crate::common::message_cache;
+
+use crate::common::database::interfaces::guild_config::GuildConfig;
+
+//------------------------------------------------------------//
+
+pub async fn guild_message_cache_handler(
+    message: &serenity::Message,
+) -> Result<(), Error> {
+    // don't retain messages from bots or system messages
+    if
+        message.author.bot() ||
+        message.author.system()
+    {
+        return Ok(());
+    }
+
+    // only retain messages in guilds
+    let Some(guild_id) = message.guild_id else {
+        return Ok(());
+    };
+
+    // attempt to fetch the guild config, if it doesn't exist, nothing would use the message
+    let Some(guild_config) = GuildConfig::fetch(guild_id).await? else {
+        return Ok(());
+    };
+
+    let message_retention_mode = guild_config.get_message_retention_mode().await;
+    let logging_channels = guild_config.get_logging_channels().await;
+
+    if !message_retention_mode.should_retain_messages(&logging_channels) {
+        // ensure nothing lingers after retention was disabled
+        message_cache::clear_guild_messages(guild_id);
+
+        return Ok(());
+    }
+
+    message_cache::cache_message(guild_id, message);
+
+    Ok(())
+}
@@ -0,0 +1,351 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::serenity_prelude::{self as serenity, Mentionable, QuickModal};
+
+//------------------------------------------------------------//
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::common::helpers::bot::create_escaped_code_block;
+
+use crate::common::moderation::{self, ModerationActionContext};
+
+use crate::common::moderation_appeals::{self, ModerationAppealComponentId, ModerationAppealComponentKind};
+
+use crate::common::database::interfaces::moderation_case::{
+    ModerationCase,
+    ModerationCaseAction,
+    ModerationCaseAppeal,
+    ModerationCaseAppealStatus,
+};
+
+use crate::common::scheduler;
+
+use crate::commands::moderation::unban::unban_user;
+
+//------------------------------------------------------------//
+
+async fn respond_ephemerally(
+    http: &serenity::Http,
+    component_interaction: &serenity::ComponentInteraction,
+    content: &str,
+) -> Result<(), Error> {
+    component_interaction.create_response(
+        http,
+        serenity::CreateInteractionResponse::Message(
+            serenity::CreateInteractionResponseMessage::default()
+            .content(content)
+            .ephemeral(true)
+        )
+    ).await?;
+
+    Ok(())
+}
+
+async fn fetch_guild_name(
+    http: &serenity::Http,
+    guild_id: serenity::GuildId,
+) -> String {
+    match http.get_guild(guild_id).await {
+        Ok(guild) => guild.name.to_string(),
+        Err(_) => guild_id.to_string(), // the guild name is only used for display
+    }
+}
+
+//------------------------------------------------------------//
+
+/// Lets the target of a case submit an appeal, then forwards it to the appeals channel for review.
+async fn open_appeal(
+    ctx: &serenity::Context,
+    component_interaction: &serenity::ComponentInteraction,
+    component_id: ModerationAppealComponentId,
+) -> Result<(), Error> {
+    let Some(moderation_case) = ModerationCase::fetch(component_id.guild_id, component_id.case_id).await? else {
+        return respond_ephemerally(&ctx.http, component_interaction, "This case no longer exists.").await;
+    };
+
+    if moderation_case.get_target_user_id().await != Some(component_interaction.user.id) {
+        return respond_ephemerally(&ctx.http, component_interaction, "Only the target of this case may appeal it.").await;
+    }
+
+    if let Some(appeal) = moderation_case.get_appeal().await {
+        return respond_ephemerally(
+            &ctx.http,
+            component_interaction,
+            &format!("This case was already appealed. Status: {}", appeal.status.name()),
+        ).await;
+    }
+
+    let Some(appeals_channel_id) = moderation_appeals::fetch_appeals_channel_id(component_id.guild_id).await? else {
+        return respond_ephemerally(&ctx.http, component_interaction, "This server is no longer accepting appeals.").await;
+    };
+
+    let modal_response = component_interaction.quick_modal(
+        ctx,
+        serenity::CreateQuickModal::new(format!("Appeal Case #{}", component_id.case_id))
+        .timeout(std::time::Duration::from_secs(10 * 60))
+        .field(
+            "Why should this action be reversed?",
+            serenity::CreateInputText::new(
+                serenity::InputTextStyle::Paragraph,
+                "appeal_content",
+            )
+            .min_length(10)
+            .max_length(1000)
+            .required(true)
+        )
+    ).await?;
+
+    let Some(modal_response) = modal_response else {
+        return Ok(());
+    };
+
+    let Some(content) = modal_response.inputs.get(0).map(|input| input.to_string()) else {
+        return Err("There should be an appeal content input in the modal response.".into());
+    };
+
+    let appeal = ModerationCaseAppeal {
+        content: content,
+        status: ModerationCaseAppealStatus::Pending,
+        created_at: chrono::Utc::now(),
+        reviewer_user_id: None,
+        reviewed_at: None,
+    };
+
+    // the case may have been appealed while the modal was open
+    if !moderation_case.submit_appeal(appeal.clone()).await? {
+        modal_response.interaction.create_response(
+            &ctx.http,
+            serenity::CreateInteractionResponse::Message(
+                serenity::CreateInteractionResponseMessage::default()
+                .content("This case was already appealed.")
+                .ephemeral(true)
+            )
+        ).await?;
+
+        return Ok(());
+    }
+
+    let accept_component_id = ModerationAppealComponentId {
+        kind: ModerationAppealComponentKind::Accept,
+        ..component_id
+    };
+
+    let deny_component_id = ModerationAppealComponentId {
+        kind: ModerationAppealComponentKind::Deny,
+        ..component_id
+    };
+
+    let review_embed = moderation_appeals::create_appeal_review_embed(&moderation_case, &appeal).await?;
+
+    let send_result = appeals_channel_id.send_message(
+        &ctx.http,
+        serenity::CreateMessage::default()
+        .embed(review_embed)
+        .components(vec![
+            serenity::CreateComponent::ActionRow(
+                serenity::CreateActionRow::buttons(vec![
+                    serenity::CreateButton::new(accept_component_id.to_custom_id())
+                    .style(serenity::ButtonStyle::Success)
+                    .label("Accept"),
+                    serenity::CreateButton::new(deny_component_id.to_custom_id())
+                    .style(serenity::ButtonStyle::Danger)
+                    .label("Deny"),
+                ])
+            )
+        ])
+    ).await;
+
+    if let Err(why) = send_result {
+        eprintln!("Failed to send appeal of case #{} to the appeals channel: {:?}", component_id.case_id, why);
+
+        // an appeal that can't be reviewed shouldn't prevent appealing again later
+        moderation_case.set_appeal(None).await?;
+
+        modal_response.interaction.create_response(
+            &ctx.http,
+            serenity::CreateInteractionResponse::Message(
+                serenity::CreateInteractionResponseMessage::default()
+                .content("Your appeal could not be submitted for review, please try again later.")
+                .ephemeral(true)
+            )
+        ).await?;
+
+        return Ok(());
+    }
+
+    modal_response.interaction.create_response(
+        &ctx.http,
+        serenity::CreateInteractionResponse::Message(
+            serenity::CreateInteractionResponseMessage::default()
+            .content(format!("Your appeal of case #{} was submitted for review.", component_id.case_id))
+        )
+    ).await?;
+
+    Ok(())
+}
+
+//------------------------------------------------------------//
+
+/// Reverses the action of an accepted appeal, recording the reversal as a new case.
+/// Warnings have nothing to reverse, as accepted warnings no longer count towards escalations.
+async fn reverse_appealed_action(
+    ctx: &serenity::Context,
+    moderation_case: &ModerationCase,
+    reviewer_user_id: serenity::UserId,
+) -> Result<(), Error> {
+    let guild_id = moderation_case.get_discord_guild_id().await;
+
+    let Some(target_user_id) = moderation_case.get_target_user_id().await else {
+        return Ok(());
+    };
+
+    let action_ctx = ModerationActionContext {
+        http: &ctx.http,
+        guild_id: guild_id,
+        guild_name: fetch_guild_name(&ctx.http, guild_id).await,
+        actor_user_id: reviewer_user_id,
+        channel_id: None,
+    };
+
+    let reason = format!("The appeal of case #{} was accepted.", moderation_case.get_case_id().await);
+
+    match moderation_case.get_action().await {
+        ModerationCaseAction::Ban => {
+            unban_user(&ctx.http, guild_id, &action_ctx.guild_name, target_user_id, reviewer_user_id, &reason).await?;
+
+            // the appeal supersedes any scheduled unban
+            scheduler::cancel_job(&scheduler::create_unban_dedupe_key(guild_id, target_user_id)).await?;
+
+            moderation::record_moderation_case(&action_ctx, ModerationCaseAction::Unban, Some(target_user_id), &reason, None).await?;
+        },
+        ModerationCaseAction::Timeout => {
+            guild_id.edit_member(
+                &ctx.http,
+                target_user_id,
+                serenity::EditMember::default()
+                .enable_communication()
+                .audit_log_reason(&reason),
+            ).await?;
+
+            moderation::record_moderation_case(&action_ctx, ModerationCaseAction::Untimeout, Some(target_user_id), &reason, None).await?;
+        },
+        _ => {},
+    }
+
+    Ok(())
+}
+
+/// Accepts or denies a pending appeal, then notifies the target of the outcome.
+async fn review_appeal(
+    ctx: &serenity::Context,
+    component_interaction: &serenity::ComponentInteraction,
+    component_id: ModerationAppealComponentId,
+) -> Result<(), Error> {
+    let Some(member) = &component_interaction.member else {
+        return Ok(()); // appeals are only reviewed in guilds
+    };
+
+    if component_interaction.guild_id != Some(component_id.guild_id) {
+        return Ok(()); // the component was not sent by this bot
+    }
+
+    let Some(moderation_case) = ModerationCase::fetch(component_id.guild_id, component_id.case_id).await? else {
+        return respond_ephemerally(&ctx.http, component_interaction, "This case no longer exists.").await;
+    };
+
+    let capability = moderation_appeals::get_appeal_review_capability(moderation_case.get_action().await);
+
+    let member_permissions = member.permissions.unwrap_or_default();
+
+    if !moderation::is_member_granted_moderation_capability(member, member_permissions, capability).await? {
+        return respond_ephemerally(&ctx.http, component_interaction, moderation::LACKING_PERMISSIONS_MESSAGE).await;
+    }
+
+    let Some(pending_appeal) = moderation_case.get_appeal().await else {
+        return respond_ephemerally(&ctx.http, component_interaction, "This case has not been appealed.").await;
+    };
+
+    let is_accepted = component_id.kind == ModerationAppealComponentKind::Accept;
+
+    let appeal = ModerationCaseAppeal {
+        status: if is_accepted { ModerationCaseAppealStatus::Accepted } else { ModerationCaseAppealStatus::Denied },
+        reviewer_user_id: Some(member.user.id),
+        reviewed_at: Some(chrono::Utc::now()),
+        ..pending_appeal.clone()
+    };
+
+    // claim the review before acting on it, so that concurrent reviews can't both go through
+    if !moderation_case.review_appeal(appeal.clone()).await? {
+        return respond_ephemerally(&ctx.http, component_interaction, "This appeal was already reviewed.").await;
+    }
+
+    component_interaction.defer(&ctx.http).await?;
+
+    if is_accepted {
+        if let Err(why) = reverse_appealed_action(ctx, &moderation_case, member.user.id).await {
+            // let the appeal be reviewed again
+            moderation_case.set_appeal(Some(pending_appeal)).await?;
+
+            component_interaction.create_followup(
+                &ctx.http,
+                serenity::CreateInteractionResponseFollowup::default()
+                .content(format!("Failed to reverse the action of case #{}: {}", component_id.case_id, why))
+                .ephemeral(true)
+            ).await?;
+
+            return Ok(());
+        }
+    }
+
+    if let Some(target_user_id) = moderation_case.get_target_user_id().await {
+        let guild_name = fetch_guild_name(&ctx.http, component_id.guild_id).await;
+
+        target_user_id.dm(
+            &ctx.http,
+            serenity::CreateMessage::default()
+            .embed(
+                serenity::CreateEmbed::default()
+                .color(branding::color::PRIMARY)
+                .title(format!("{} - Moderation", guild_name))
+                .description(
+                    format!(
+                        "Your appeal of case #{} was {} by {}:\n{}",
+                        component_id.case_id,
+                        appeal.status.name().to_lowercase(),
+                        member.user.mention(),
+                        create_escaped_code_block(None, &appeal.content),
+                    )
+                )
+            )
+        ).await.ok(); // ignore errors
+    }
+
+    component_interaction.edit_response(
+        &ctx.http,
+        serenity::EditInteractionResponse::default()
+        .embed(moderation_appeals::create_appeal_review_embed(&moderation_case, &appeal).await?)
+        .components(vec![])
+    ).await?;
+
+    Ok(())
+}
+
+//------------------------------------------------------------//
+
+/// Handles the appeal buttons sent to case targets and to appeals channels.
+pub async fn guild_moderation_appeal_component_interaction_handler(
+    ctx: &serenity::Context,
+    component_interaction: &serenity::ComponentInteraction,
+    component_id: ModerationAppealComponentId,
+) -> Result<(), Error> {
+    match component_id.kind {
+        ModerationAppealComponentKind::Open => open_appeal(ctx, component_interaction, component_id).await,
+        ModerationAppealComponentKind::Accept |
+        ModerationAppealComponentKind::Deny => review_appeal(ctx, component_interaction, component_id).await,
+    }
+}
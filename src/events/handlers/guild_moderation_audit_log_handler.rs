@@ -0,0 +1,110 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::Error;
+
+use crate::common::moderation;
+
+use crate::common::scheduler;
+
+use crate::common::database::interfaces::moderation_case::{
+    ModerationCase,
+    ModerationCaseAction,
+    ModerationCaseOptions,
+};
+
+//------------------------------------------------------------//
+
+/// Determines the moderation action (and duration) represented by an audit log entry, if any.
+fn get_moderation_action_from_audit_log_entry(
+    entry: &serenity::AuditLogEntry,
+) -> Option<(ModerationCaseAction, Option<u32>)> {
+    match entry.action {
+        serenity::audit_log::Action::Member(serenity::audit_log::MemberAction::Kick) => {
+            Some((ModerationCaseAction::Kick, None))
+        },
+        serenity::audit_log::Action::Member(serenity::audit_log::MemberAction::BanAdd) => {
+            Some((ModerationCaseAction::Ban, None))
+        },
+        serenity::audit_log::Action::Member(serenity::audit_log::MemberAction::BanRemove) => {
+            Some((ModerationCaseAction::Unban, None))
+        },
+        serenity::audit_log::Action::Member(serenity::audit_log::MemberAction::Update) => {
+            // only timeout changes are considered moderation actions
+            entry.changes.as_ref()?.iter().find_map(|change| {
+                let serenity::audit_log::Change::CommunicationDisabledUntil { new, .. } = change else {
+                    return None;
+                };
+
+                match new {
+                    Some(timed_out_until) => {
+                        let duration_seconds = (timed_out_until.unix_timestamp() - chrono::Utc::now().timestamp()).max(0);
+
+                        Some((ModerationCaseAction::Timeout, Some(duration_seconds as u32)))
+                    },
+                    None => Some((ModerationCaseAction::Untimeout, None)),
+                }
+            })
+        },
+        _ => None,
+    }
+}
+
+//------------------------------------------------------------//
+
+/// Records and logs moderation actions that were performed outside of this bot (e.g. via the Discord client).
+pub async fn guild_moderation_audit_log_handler(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    entry: &serenity::AuditLogEntry,
+) -> Result<(), Error> {
+    let Some(actor_user_id) = entry.user_id else {
+        return Ok(()); // Graceful
+    };
+
+    // actions performed by this bot are already recorded by the command (or job) that performed them
+    if actor_user_id == ctx.cache.current_user().id {
+        return Ok(());
+    }
+
+    let Some((action, duration_seconds)) = get_moderation_action_from_audit_log_entry(entry) else {
+        return Ok(()); // not a moderation action
+    };
+
+    let Some(target_id) = entry.target_id else {
+        return Ok(()); // Graceful
+    };
+
+    let target_user_id = serenity::UserId::new(target_id.get());
+
+    // a manual unban supersedes any scheduled unban
+    if action == ModerationCaseAction::Unban {
+        scheduler::cancel_job(&scheduler::create_unban_dedupe_key(guild_id, target_user_id)).await?;
+    }
+
+    let reason = match &entry.reason {
+        Some(reason) => reason.to_string(),
+        None => "A reason was not provided.".to_string(),
+    };
+
+    let moderation_case = ModerationCase::create(
+        ModerationCaseOptions {
+            discord_guild_id: guild_id,
+            action: action,
+            actor_user_id: actor_user_id,
+            target_user_id: Some(target_user_id),
+            channel_id: None,
+            reason: reason,
+            duration_seconds: duration_seconds,
+        }
+    ).await?;
+
+    moderation::log_moderation_case(&ctx.http, &moderation_case).await?;
+
+    Ok(())
+}
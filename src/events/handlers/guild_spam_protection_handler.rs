@@ -0,0 +1,128 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use std::collections::HashMap;
+
+use poise::serenity_prelude::{self as serenity, Mentionable};
+
+//------------------------------------------------------------//
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::common::automod;
+
+use crate::common::spam_protection;
+
+use crate::common::moderation::{self, ModerationActionContext};
+
+use crate::common::database::interfaces::guild_config::GuildConfig;
+
+use crate::commands::moderation::timeout::timeout_member;
+
+//------------------------------------------------------------//
+
+/// Enforces the guild's spam protection on a message.
+/// Returns whether the message was removed, in which case it should not be processed further.
+pub async fn guild_spam_protection_handler(
+    ctx: &serenity::Context,
+    message: &serenity::Message,
+) -> Result<bool, Error> {
+    // don't moderate bots or system messages
+    if
+        message.author.bot() ||
+        message.author.system()
+    {
+        return Ok(false);
+    }
+
+    // only moderate messages in guilds
+    let Some(guild_id) = message.guild_id else {
+        return Ok(false);
+    };
+
+    // attempt to fetch the guild config, if it doesn't exist, spam protection is not enabled
+    let Some(guild_config) = GuildConfig::fetch(guild_id).await? else {
+        return Ok(false);
+    };
+
+    let spam_protection = guild_config.get_spam_protection().await;
+
+    if !spam_protection.enabled {
+        return Ok(false);
+    }
+
+    if automod::is_message_exempt(ctx, guild_id, &guild_config.get_automod().await, message) {
+        return Ok(false);
+    }
+
+    let Some(violation) = spam_protection::check_message(&spam_protection, guild_id, message) else {
+        return Ok(false);
+    };
+
+    let reason = format!("Spam protection: {}", violation.description);
+
+    let mut spam_message_ids_by_channel: HashMap<serenity::GenericChannelId, Vec<serenity::MessageId>> = HashMap::new();
+
+    for (channel_id, message_id) in &violation.spam_messages {
+        spam_message_ids_by_channel.entry(*channel_id).or_default().push(*message_id);
+    }
+
+    for (channel_id, message_ids) in spam_message_ids_by_channel {
+        // bulk deletes require at least two messages
+        let delete_result =
+            if let [message_id] = message_ids.as_slice() {
+                channel_id.delete_message(&ctx.http, *message_id, Some(&reason)).await
+            } else {
+                channel_id.delete_messages(&ctx.http, &message_ids, Some(&reason)).await
+            };
+
+        // some of the messages may have already been deleted by someone else, which is not fatal
+        if let Err(why) = delete_result {
+            eprintln!("Failed to delete spam messages in channel {}: {:?}", channel_id, why);
+        }
+    }
+
+    let Some(timeout_duration) = spam_protection::get_spam_timeout_duration(&spam_protection, violation.violation_count) else {
+        moderation::send_moderation_actions_log_embed(
+            &ctx.http,
+            guild_id,
+            serenity::CreateEmbed::default()
+            .color(branding::color::PRIMARY)
+            .title("Moderation - Spam Removed")
+            .description(
+                format!(
+                    "Removed {} messages from {} (`{}`) in {}.\n{}",
+                    violation.spam_messages.len(),
+                    message.author.mention(),
+                    message.author.id,
+                    message.channel_id.mention(),
+                    violation.description,
+                )
+            )
+        ).await?;
+
+        return Ok(true);
+    };
+
+    let guild_name =
+        ctx.cache.guild(guild_id)
+        .map(|guild| guild.name.to_string())
+        .unwrap_or(guild_id.to_string());
+
+    let action_ctx = ModerationActionContext {
+        http: &ctx.http,
+        guild_id: guild_id,
+        guild_name: guild_name,
+        actor_user_id: ctx.cache.current_user().id,
+        channel_id: Some(message.channel_id),
+    };
+
+    let mut target_member = guild_id.member(&ctx, message.author.id).await?;
+
+    timeout_member(&action_ctx, &mut target_member, timeout_duration, &reason).await?;
+
+    Ok(true)
+}
@@ -0,0 +1,33 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::Error;
+
+use crate::common::scheduler;
+
+//------------------------------------------------------------//
+
+/// Runs the overdue voice unmute of a server muted member once they connect to voice.
+/// Discord only allows unmuting members that are connected, so these are retried until then.
+pub async fn guild_voice_unmute_voice_state_update_handler(
+    old_voice_state: Option<&serenity::VoiceState>,
+    new_voice_state: &serenity::VoiceState,
+) -> Result<(), Error> {
+    let Some(guild_id) = new_voice_state.guild_id else {
+        return Ok(()); // Graceful
+    };
+
+    let was_connected = old_voice_state.is_some_and(|voice_state| voice_state.channel_id.is_some());
+    let is_connected = new_voice_state.channel_id.is_some();
+
+    if was_connected || !is_connected || !new_voice_state.mute {
+        return Ok(());
+    }
+
+    scheduler::expedite_voice_unmute(guild_id, new_voice_state.user_id).await
+}
@@ -0,0 +1,135 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use lavalink_rs::prelude::*;
+
+use lavalink_rs::model::events::{TrackException, TrackStart, TrackStuck, WebSocketClosed};
+
+use lavalink_rs::model::track::TrackData;
+
+use poise::serenity_prelude::{self as serenity};
+
+//------------------------------------------------------------//
+
+use crate::Error;
+
+use crate::common::branding;
+
+use crate::common::helpers::time::format_duration;
+
+use crate::common::music;
+
+use crate::events::handlers::music_queue_handler::is_voice_connection_gone;
+
+//------------------------------------------------------------//
+
+fn format_track(
+    track: &TrackData,
+) -> String {
+    match &track.info.uri {
+        Some(uri) => format!("[{} - {}](<{}>)", track.info.author, track.info.title, uri),
+        None => format!("{} - {}", track.info.author, track.info.title),
+    }
+}
+
+/// Posts an embed to the channel that music was last requested in, if the guild has a player.
+async fn send_announcement(
+    lavalink_client: &LavalinkClient,
+    guild_id: lavalink_rs::model::GuildId,
+    embed: serenity::CreateEmbed<'_>,
+) -> Result<(), Error> {
+    let Some(player_context) = lavalink_client.get_player_context(guild_id) else {
+        return Ok(()); // the player was destroyed
+    };
+
+    let Some(announcement_target) = music::get_player_data(&player_context)?.get_announcement_target() else {
+        return Ok(()); // music was not requested from a channel
+    };
+
+    announcement_target.channel_id.send_message(
+        &announcement_target.http,
+        serenity::CreateMessage::default().embed(embed)
+    ).await?;
+
+    Ok(())
+}
+
+//------------------------------------------------------------//
+
+pub async fn music_announcement_track_start_handler(
+    lavalink_client: &LavalinkClient,
+    event: &TrackStart,
+) -> Result<(), Error> {
+    let duration_string =
+        if event.track.info.is_stream { "Live".to_string() }
+        else { format_duration(std::time::Duration::from_millis(event.track.info.length)) };
+
+    let mut embed =
+        serenity::CreateEmbed::default()
+        .color(branding::color::PRIMARY)
+        .title("Music - Now Playing")
+        .description(format_track(&event.track))
+        .field("Duration", duration_string, true);
+
+    if let Some(artwork_url) = &event.track.info.artwork_url {
+        embed = embed.thumbnail(artwork_url.clone());
+    }
+
+    send_announcement(lavalink_client, event.guild_id, embed).await
+}
+
+pub async fn music_announcement_track_exception_handler(
+    lavalink_client: &LavalinkClient,
+    event: &TrackException,
+) -> Result<(), Error> {
+    let reason = event.exception.message.clone().unwrap_or("An unknown error occurred.".to_string());
+
+    // lavalink ends the track afterwards, which advances the queue
+    send_announcement(
+        lavalink_client,
+        event.guild_id,
+        serenity::CreateEmbed::default()
+        .color(branding::color::PRIMARY)
+        .title("Music - Playback Failed")
+        .description(format!("Skipped {}, since it failed to play:\n{}", format_track(&event.track), reason)),
+    ).await
+}
+
+pub async fn music_announcement_track_stuck_handler(
+    lavalink_client: &LavalinkClient,
+    event: &TrackStuck,
+) -> Result<(), Error> {
+    send_announcement(
+        lavalink_client,
+        event.guild_id,
+        serenity::CreateEmbed::default()
+        .color(branding::color::PRIMARY)
+        .title("Music - Playback Stuck")
+        .description(
+            format!(
+                "Skipped {}, since it stopped playing for {}.",
+                format_track(&event.track),
+                format_duration(std::time::Duration::from_millis(event.threshold_ms)),
+            )
+        ),
+    ).await
+}
+
+pub async fn music_announcement_websocket_closed_handler(
+    lavalink_client: &LavalinkClient,
+    event: &WebSocketClosed,
+) -> Result<(), Error> {
+    if !is_voice_connection_gone(event) {
+        return Ok(());
+    }
+
+    send_announcement(
+        lavalink_client,
+        event.guild_id,
+        serenity::CreateEmbed::default()
+        .color(branding::color::PRIMARY)
+        .title("Music - Disconnected")
+        .description("I was disconnected from the voice channel, so the queue was cleared."),
+    ).await
+}
@@ -0,0 +1,104 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use lavalink_rs::prelude::*;
+
+use lavalink_rs::model::events::{TrackEnd, TrackEndReason, TrackStart, TrackStuck, WebSocketClosed};
+
+//------------------------------------------------------------//
+
+use crate::Error;
+
+use crate::common::music::{self, LoopMode};
+
+//------------------------------------------------------------//
+
+/// Queues the loop copy of a track that started playing, according to the guild's loop mode.
+/// Lavalink-rs plays the copy once the track finishes, see `music::queue_loop_copy`.
+pub async fn music_queue_track_start_handler(
+    lavalink_client: &LavalinkClient,
+    event: &TrackStart,
+) -> Result<(), Error> {
+    let Some(player_context) = lavalink_client.get_player_context(event.guild_id) else {
+        return Ok(()); // the player was destroyed
+    };
+
+    let loop_mode = music::get_player_data(&player_context)?.get_loop_mode();
+
+    music::queue_loop_copy(&player_context, loop_mode, &event.track)?;
+
+    Ok(())
+}
+
+/// Turns looping off when a track fails to load, since its loop copy would fail forever.
+///
+/// The queue itself is not advanced here: for tracks that finished or failed to load,
+/// lavalink-rs plays the next queued track by itself, and doing so here as well would skip a track.
+pub async fn music_queue_track_end_handler(
+    lavalink_client: &LavalinkClient,
+    event: &TrackEnd,
+) -> Result<(), Error> {
+    if !matches!(event.reason, TrackEndReason::LoadFailed) {
+        return Ok(());
+    }
+
+    let Some(player_context) = lavalink_client.get_player_context(event.guild_id) else {
+        return Ok(()); // the player was destroyed
+    };
+
+    if music::get_player_data(&player_context)?.get_loop_mode() == LoopMode::Off {
+        return Ok(());
+    }
+
+    music::set_loop_mode(&player_context, LoopMode::Off).await?;
+
+    Ok(())
+}
+
+/// Skips a track that stopped providing audio, lavalink does not end these on its own.
+pub async fn music_queue_track_stuck_handler(
+    lavalink_client: &LavalinkClient,
+    event: &TrackStuck,
+) -> Result<(), Error> {
+    let Some(player_context) = lavalink_client.get_player_context(event.guild_id) else {
+        return Ok(()); // the player was destroyed
+    };
+
+    music::skip_track(&player_context, &event.track).await?;
+
+    Ok(())
+}
+
+/// Destroys the player of a guild once its voice connection is gone for good.
+/// Discord closes the connection when the bot is disconnected, or when the voice session is no longer valid.
+pub async fn music_queue_websocket_closed_handler(
+    lavalink_client: &LavalinkClient,
+    event: &WebSocketClosed,
+) -> Result<(), Error> {
+    if !is_voice_connection_gone(event) {
+        return Ok(()); // lavalink resumes other closures on its own
+    }
+
+    let Some(player_context) = lavalink_client.get_player_context(event.guild_id) else {
+        return Ok(()); // the player was already destroyed
+    };
+
+    player_context.get_queue().clear()?;
+
+    lavalink_client.delete_player(event.guild_id).await?;
+
+    Ok(())
+}
+
+/// Whether a websocket closure means that the bot is no longer connected to voice.
+/// See: https://discord.com/developers/docs/topics/opcodes-and-status-codes#voice-voice-close-event-codes
+pub fn is_voice_connection_gone(
+    event: &WebSocketClosed,
+) -> bool {
+    matches!(
+        event.code,
+        4006 | // session no longer valid
+        4014 // disconnected (kicked, moved, or the channel was deleted)
+    )
+}
@@ -0,0 +1,105 @@
+//------------------------------------------------------------//
+//                   Copyright (c) MidSpike                   //
+//------------------------------------------------------------//
+
+use lavalink_rs::hook;
+
+use lavalink_rs::prelude::*;
+
+use lavalink_rs::model::events::{Events, TrackEnd, TrackException, TrackStart, TrackStuck, WebSocketClosed};
+
+//------------------------------------------------------------//
+
+use crate::events::handlers::music_announcement_handler::music_announcement_track_start_handler;
+use crate::events::handlers::music_announcement_handler::music_announcement_track_exception_handler;
+use crate::events::handlers::music_announcement_handler::music_announcement_track_stuck_handler;
+use crate::events::handlers::music_announcement_handler::music_announcement_websocket_closed_handler;
+
+use crate::events::handlers::music_queue_handler::music_queue_track_start_handler;
+use crate::events::handlers::music_queue_handler::music_queue_track_end_handler;
+use crate::events::handlers::music_queue_handler::music_queue_track_stuck_handler;
+use crate::events::handlers::music_queue_handler::music_queue_websocket_closed_handler;
+
+//------------------------------------------------------------//
+
+#[hook]
+async fn track_start_event_handler(
+    lavalink_client: LavalinkClient,
+    _session_id: String,
+    event: &TrackStart,
+) {
+    if let Err(why) = music_queue_track_start_handler(&lavalink_client, event).await {
+        eprintln!("Error handling music queue track start: {:?}", why);
+    }
+
+    if let Err(why) = music_announcement_track_start_handler(&lavalink_client, event).await {
+        eprintln!("Error handling music announcement track start: {:?}", why);
+    }
+}
+
+#[hook]
+async fn track_end_event_handler(
+    lavalink_client: LavalinkClient,
+    _session_id: String,
+    event: &TrackEnd,
+) {
+    if let Err(why) = music_queue_track_end_handler(&lavalink_client, event).await {
+        eprintln!("Error handling music queue track end: {:?}", why);
+    }
+}
+
+#[hook]
+async fn track_exception_event_handler(
+    lavalink_client: LavalinkClient,
+    _session_id: String,
+    event: &TrackException,
+) {
+    if let Err(why) = music_announcement_track_exception_handler(&lavalink_client, event).await {
+        eprintln!("Error handling music announcement track exception: {:?}", why);
+    }
+}
+
+#[hook]
+async fn track_stuck_event_handler(
+    lavalink_client: LavalinkClient,
+    _session_id: String,
+    event: &TrackStuck,
+) {
+    if let Err(why) = music_announcement_track_stuck_handler(&lavalink_client, event).await {
+        eprintln!("Error handling music announcement track stuck: {:?}", why);
+    }
+
+    if let Err(why) = music_queue_track_stuck_handler(&lavalink_client, event).await {
+        eprintln!("Error handling music queue track stuck: {:?}", why);
+    }
+}
+
+#[hook]
+async fn websocket_closed_event_handler(
+    lavalink_client: LavalinkClient,
+    _session_id: String,
+    event: &WebSocketClosed,
+) {
+    // announce before cleaning up, since the announcement channel is stored by the player
+    if let Err(why) = music_announcement_websocket_closed_handler(&lavalink_client, event).await {
+        eprintln!("Error handling music announcement websocket closed: {:?}", why);
+    }
+
+    if let Err(why) = music_queue_websocket_closed_handler(&lavalink_client, event).await {
+        eprintln!("Error handling music queue websocket closed: {:?}", why);
+    }
+}
+
+//------------------------------------------------------------//
+
+/// Creates the lavalink event handlers, which are registered when the lavalink client is created.
+pub fn create_lavalink_events() -> Events {
+    Events {
+        track_start: Some(track_start_event_handler),
+        track_end: Some(track_end_event_handler),
+        track_exception: Some(track_exception_event_handler),
+        track_stuck: Some(track_stuck_event_handler),
+        websocket_closed: Some(websocket_closed_event_handler),
+        ..Events::default()
+    }
+}
@@ -14,18 +14,54 @@ use crate::Error;
 
 use crate::common::telemetry;
 
+use crate::common::message_cache;
+
+use crate::common::moderation_appeals::ModerationAppealComponentId;
+
 use crate::commands::create_commands;
 
 use crate::events::handlers::guild_ai_chat_handler::guild_ai_chat_handler;
 
+use crate::events::handlers::guild_anti_raid_handler::guild_anti_raid_member_join_handler;
+
+use crate::events::handlers::guild_automod_handler::guild_automod_handler;
+
 use crate::events::handlers::guild_logging_channels_handler::guild_logging_channels_member_join_handler;
 use crate::events::handlers::guild_logging_channels_handler::guild_logging_channels_member_leave_handler;
+use crate::events::handlers::guild_logging_channels_handler::guild_logging_channels_member_update_handler;
+use crate::events::handlers::guild_logging_channels_handler::guild_logging_channels_message_edit_handler;
+use crate::events::handlers::guild_logging_channels_handler::guild_logging_channels_message_delete_handler;
+use crate::events::handlers::guild_logging_channels_handler::guild_logging_channels_message_bulk_delete_handler;
+use crate::events::handlers::guild_logging_channels_handler::guild_logging_channels_voice_state_update_handler;
+use crate::events::handlers::guild_logging_channels_handler::guild_logging_channels_channel_create_handler;
+use crate::events::handlers::guild_logging_channels_handler::guild_logging_channels_channel_delete_handler;
+use crate::events::handlers::guild_logging_channels_handler::guild_logging_channels_ban_add_handler;
+use crate::events::handlers::guild_logging_channels_handler::guild_logging_channels_ban_remove_handler;
+
+use crate::events::handlers::guild_message_cache_handler::guild_message_cache_handler;
+
+use crate::events::handlers::guild_moderation_appeal_handler::guild_moderation_appeal_component_interaction_handler;
+
+use crate::events::handlers::guild_moderation_audit_log_handler::guild_moderation_audit_log_handler;
+
+use crate::events::handlers::guild_spam_protection_handler::guild_spam_protection_handler;
+
+use crate::events::handlers::guild_voice_unmute_handler::guild_voice_unmute_voice_state_update_handler;
 
 //------------------------------------------------------------//
 
+/// Routes component interactions with persistent custom ids, which must keep working after restarts.
+/// Components that are only awaited by a running command (e.g. pagination) are handled by their collectors instead.
 async fn component_interaction_handler(
-    _component_interaction: &serenity::ComponentInteraction,
+    ctx: &serenity::Context,
+    component_interaction: &serenity::ComponentInteraction,
 ) -> Result<(), Error> {
+    let custom_id = component_interaction.data.custom_id.as_str();
+
+    if let Some(component_id) = ModerationAppealComponentId::parse(custom_id) {
+        return guild_moderation_appeal_component_interaction_handler(ctx, component_interaction, component_id).await;
+    }
+
     Ok(())
 }
 
@@ -53,7 +89,7 @@ async fn event_handler(
 
         serenity::FullEvent::InteractionCreate { interaction, .. } => {
             if let serenity::Interaction::Component(component_interaction) = interaction {
-                if let Err(why) = component_interaction_handler(component_interaction).await {
+                if let Err(why) = component_interaction_handler(&ctx, component_interaction).await {
                     eprintln!("Error handling component interaction: {:?}", why);
 
                     return Ok(()); // Graceful
@@ -62,6 +98,26 @@ async fn event_handler(
         },
 
         serenity::FullEvent::Message { new_message, .. } => {
+            if let Err(why) = guild_message_cache_handler(new_message).await {
+                eprintln!("Error handling guild message cache: {:?}", why);
+            }
+
+            match guild_automod_handler(&ctx, new_message).await {
+                Ok(true) => return Ok(()), // the message was removed
+                Ok(false) => {}, // continue
+                Err(why) => {
+                    eprintln!("Error handling guild automod: {:?}", why);
+                },
+            }
+
+            match guild_spam_protection_handler(&ctx, new_message).await {
+                Ok(true) => return Ok(()), // the message was removed
+                Ok(false) => {}, // continue
+                Err(why) => {
+                    eprintln!("Error handling guild spam protection: {:?}", why);
+                },
+            }
+
             if let Err(why) = guild_ai_chat_handler(&ctx, new_message).await {
                 eprintln!("Error handling guild AI chat: {:?}", why);
 
@@ -87,6 +143,10 @@ async fn event_handler(
         },
 
         serenity::FullEvent::GuildMemberAddition { new_member, .. } => {
+            if let Err(why) = guild_anti_raid_member_join_handler(&ctx, new_member).await {
+                eprintln!("Error handling guild anti-raid member join: {:?}", why);
+            }
+
             if let Err(why) = guild_logging_channels_member_join_handler(&ctx, new_member).await {
                 eprintln!("Error handling guild logging channels member join: {:?}", why);
 
@@ -102,6 +162,105 @@ async fn event_handler(
             }
         },
 
+        serenity::FullEvent::GuildMemberUpdate { old_if_available, new, .. } => {
+            if let Some(new_member) = new {
+                if let Err(why) = guild_logging_channels_member_update_handler(&ctx, old_if_available.as_ref(), new_member).await {
+                    eprintln!("Error handling guild logging channels member update: {:?}", why);
+
+                    return Ok(()); // Graceful
+                }
+            }
+        },
+
+        serenity::FullEvent::MessageUpdate { new, .. } => {
+            if let Some(new_message) = new {
+                // the previous content is only available if the message was retained
+                let previous_cached_message =
+                    new_message.guild_id
+                    .and_then(|guild_id| message_cache::update_cached_message(guild_id, new_message));
+
+                if let Err(why) = guild_logging_channels_message_edit_handler(&ctx, previous_cached_message.as_ref(), new_message).await {
+                    eprintln!("Error handling guild logging channels message edit: {:?}", why);
+
+                    return Ok(()); // Graceful
+                }
+            }
+        },
+
+        serenity::FullEvent::MessageDelete { channel_id, deleted_message_id, guild_id, .. } => {
+            if let Some(guild_id) = guild_id {
+                let cached_message = message_cache::mark_cached_message_deleted(*guild_id, *deleted_message_id);
+
+                if let Err(why) = guild_logging_channels_message_delete_handler(&ctx, *guild_id, *channel_id, *deleted_message_id, cached_message.as_ref()).await {
+                    eprintln!("Error handling guild logging channels message delete: {:?}", why);
+
+                    return Ok(()); // Graceful
+                }
+            }
+        },
+
+        serenity::FullEvent::MessageDeleteBulk { channel_id, multiple_deleted_messages_ids, guild_id, .. } => {
+            if let Some(guild_id) = guild_id {
+                if let Err(why) = guild_logging_channels_message_bulk_delete_handler(&ctx, *guild_id, *channel_id, multiple_deleted_messages_ids).await {
+                    eprintln!("Error handling guild logging channels message bulk delete: {:?}", why);
+
+                    return Ok(()); // Graceful
+                }
+            }
+        },
+
+        serenity::FullEvent::VoiceStateUpdate { old, new, .. } => {
+            if let Err(why) = guild_voice_unmute_voice_state_update_handler(old.as_ref(), new).await {
+                eprintln!("Error handling guild voice unmute voice state update: {:?}", why);
+            }
+
+            if let Err(why) = guild_logging_channels_voice_state_update_handler(&ctx, old.as_ref(), new).await {
+                eprintln!("Error handling guild logging channels voice state update: {:?}", why);
+
+                return Ok(()); // Graceful
+            }
+        },
+
+        serenity::FullEvent::ChannelCreate { channel, .. } => {
+            if let Err(why) = guild_logging_channels_channel_create_handler(&ctx, channel).await {
+                eprintln!("Error handling guild logging channels channel create: {:?}", why);
+
+                return Ok(()); // Graceful
+            }
+        },
+
+        serenity::FullEvent::ChannelDelete { channel, .. } => {
+            if let Err(why) = guild_logging_channels_channel_delete_handler(&ctx, channel).await {
+                eprintln!("Error handling guild logging channels channel delete: {:?}", why);
+
+                return Ok(()); // Graceful
+            }
+        },
+
+        serenity::FullEvent::GuildBanAddition { guild_id, banned_user, .. } => {
+            if let Err(why) = guild_logging_channels_ban_add_handler(&ctx, *guild_id, banned_user).await {
+                eprintln!("Error handling guild logging channels ban add: {:?}", why);
+
+                return Ok(()); // Graceful
+            }
+        },
+
+        serenity::FullEvent::GuildBanRemoval { guild_id, unbanned_user, .. } => {
+            if let Err(why) = guild_logging_channels_ban_remove_handler(&ctx, *guild_id, unbanned_user).await {
+                eprintln!("Error handling guild logging channels ban remove: {:?}", why);
+
+                return Ok(()); // Graceful
+            }
+        },
+
+        serenity::FullEvent::GuildAuditLogEntryCreate { entry, guild_id, .. } => {
+            if let Err(why) = guild_moderation_audit_log_handler(&ctx, *guild_id, entry).await {
+                eprintln!("Error handling guild moderation audit log entry: {:?}", why);
+
+                return Ok(()); // Graceful
+            }
+        },
+
         _ => {}, // ignore other events
     }
 
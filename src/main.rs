@@ -26,10 +26,14 @@ pub mod events;
 
 use crate::commands::create_commands;
 
+use crate::common::command_access::check_command_invocation;
+
 use crate::common::helpers::{libre_translate, bot::create_default_allowed_mentions};
 
 use crate::events::manager::EventHandler;
 
+use crate::events::lavalink_manager::create_lavalink_events;
+
 use crate::common::telemetry::anonymous_command_log::telemetry_anonymous_command_log;
 
 //------------------------------------------------------------//
@@ -51,6 +55,17 @@ async fn create_client_builder() -> serenity::ClientBuilder {
     let framework_options = poise::FrameworkOptions {
         allowed_mentions: Some(create_default_allowed_mentions()),
         commands: create_commands(),
+        command_check: Some(|context| {
+            // This will run before every command invocation (and before `pre_command`)
+            Box::pin(async move {
+                match context {
+                    poise::Context::Application(ctx) => check_command_invocation(ctx).await,
+                    _ => Ok(true),
+                }
+            })
+        }),
+        // cooldowns are enforced by the command check, so that guilds can override them
+        manual_cooldowns: true,
         pre_command: |context| {
             // This will run before every command invocation
             Box::pin(async move {
@@ -79,6 +94,7 @@ async fn create_client_builder() -> serenity::ClientBuilder {
     let gateway_intents =
         // serenity::GatewayIntents::non_privileged() |
         serenity::GatewayIntents::GUILDS |
+        serenity::GatewayIntents::GUILD_MODERATION |
         serenity::GatewayIntents::GUILD_MESSAGES |
         serenity::GatewayIntents::DIRECT_MESSAGES |
         serenity::GatewayIntents::GUILD_MESSAGE_REACTIONS |
@@ -120,7 +136,7 @@ async fn create_client_builder() -> serenity::ClientBuilder {
             };
 
             let lavalink_rs_client = LavalinkClient::new(
-                lavalink_rs::model::events::Events::default(),
+                create_lavalink_events(),
                 vec![lavalink_rs_node],
                 NodeDistributionStrategy::default(),
             ).await;
@@ -178,6 +194,8 @@ async fn main() -> Result<()> {
 
     let mut client = client_builder.await.context("Failed to create discord client")?;
 
+    common::scheduler::start_scheduler_task(Arc::clone(&client.http));
+
     let shard_count = client.http.get_bot_gateway().await.context("Failed to get bot gateway")?.shards.get();
 
     client.start_shards(shard_count).await.context("Failed to start discord client")?;